@@ -0,0 +1,847 @@
+#![allow(dead_code)]
+
+use core::fmt;
+use core::mem::size_of;
+use std::boxed::Box;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::string::String;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::vec::Vec;
+
+use super::hooked_accessor::{AccessKind, AccessValue};
+use super::mem_accessor::{
+    expect_subclone, for_each_access_type, subregion_size, MemAccess, MemAccessTryError,
+};
+use super::tracing_accessor::{AccessRecord, TraceSink};
+
+/// 記録したセッションの 1 アクセス。
+///
+/// `AccessRecord` から時刻とスレッドを除き、コピーの転送データ `data` を加えたもの。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionEntry {
+    pub kind: AccessKind,
+    pub phys_addr: usize,
+    pub offset: usize,
+    pub width: usize,
+    pub count: usize,
+    pub value: u64,
+    pub failed: bool,
+    pub data: Vec<u8>,
+}
+
+impl SessionEntry {
+    pub fn from_record(record: &AccessRecord, data: &[u8]) -> Self {
+        Self {
+            kind: record.kind,
+            phys_addr: record.phys_addr,
+            offset: record.offset,
+            width: record.width,
+            count: record.count,
+            value: record.value,
+            failed: record.failed,
+            data: data.to_vec(),
+        }
+    }
+
+    fn is_write(&self) -> bool {
+        matches!(self.kind, AccessKind::Write | AccessKind::WriteReg)
+    }
+
+    // 書き込み値・コピー元データまで含めて、期待どおりのアクセスか
+    fn matches(&self, actual: &SessionEntry) -> bool {
+        self.kind == actual.kind
+            && self.phys_addr == actual.phys_addr
+            && self.offset == actual.offset
+            && self.width == actual.width
+            && self.count == actual.count
+            && (!self.is_write() || self.value == actual.value)
+            && (self.kind != AccessKind::CopyFrom
+                || self.data.is_empty()
+                || self.data == actual.data)
+    }
+
+    /// JSON 1 行（改行なし）に変換する。
+    pub fn to_json(&self) -> String {
+        let mut data = String::with_capacity(self.data.len() * 2);
+        for byte in &self.data {
+            data.push_str(&std::format!("{:02x}", byte));
+        }
+        std::format!(
+            "{{\"kind\":\"{}\",\"phys_addr\":{},\"offset\":{},\"width\":{},\"count\":{},\"value\":{},\"failed\":{},\"data\":\"{}\"}}",
+            self.kind, self.phys_addr, self.offset, self.width, self.count, self.value, self.failed, data
+        )
+    }
+
+    /// `to_json` の出力を読む。未知のキーは無視する。
+    pub fn from_json(line: &str) -> Option<Self> {
+        let body = line.trim().strip_prefix('{')?.strip_suffix('}')?;
+        let mut entry = SessionEntry {
+            kind: AccessKind::Read,
+            phys_addr: 0,
+            offset: 0,
+            width: 0,
+            count: 1,
+            value: 0,
+            failed: false,
+            data: Vec::new(),
+        };
+        let mut has_kind = false;
+        for field in body.split(',').filter(|field| !field.trim().is_empty()) {
+            let (key, value) = field.split_once(':')?;
+            let key = key.trim().strip_prefix('"')?.strip_suffix('"')?;
+            let value = value.trim();
+            let string = || value.strip_prefix('"')?.strip_suffix('"');
+            match key {
+                "kind" => {
+                    entry.kind = parse_kind(string()?)?;
+                    has_kind = true;
+                }
+                "phys_addr" => entry.phys_addr = value.parse().ok()?,
+                "offset" => entry.offset = value.parse().ok()?,
+                "width" => entry.width = value.parse().ok()?,
+                "count" => entry.count = value.parse().ok()?,
+                "value" => entry.value = value.parse().ok()?,
+                "failed" => entry.failed = value.parse().ok()?,
+                "data" => entry.data = parse_hex(string()?)?,
+                _ => {}
+            }
+        }
+        has_kind.then_some(entry)
+    }
+}
+
+fn parse_kind(name: &str) -> Option<AccessKind> {
+    Some(match name {
+        "read" => AccessKind::Read,
+        "write" => AccessKind::Write,
+        "read_reg" => AccessKind::ReadReg,
+        "write_reg" => AccessKind::WriteReg,
+        "copy_to" => AccessKind::CopyTo,
+        "copy_from" => AccessKind::CopyFrom,
+        _ => return None,
+    })
+}
+
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// セッションの保存・読み込みエラー。
+#[derive(Debug)]
+pub enum SessionError {
+    Io(std::io::Error),
+    /// 解釈できない行（1 始まりの行番号）
+    Parse(usize),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "session I/O error: {}", err),
+            Self::Parse(line) => write!(f, "invalid session entry at line {}", line),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl From<std::io::Error> for SessionError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// リプレイで検出した不一致（`index` はセッション内の位置）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// 記録と異なるアクセス（`expected` が `None` なら記録を使い切った後のアクセス）
+    Mismatch {
+        index: usize,
+        expected: Option<Box<SessionEntry>>,
+        actual: Box<SessionEntry>,
+    },
+    /// 記録では成功したアクセスが失敗した
+    Access {
+        index: usize,
+        error: MemAccessTryError,
+    },
+    /// リプレイできない記録（未対応の幅、基準外のレジスタアクセス、データのないコピー）
+    Unsupported(usize),
+    /// 終了時に残っている記録の数
+    Unconsumed(usize),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mismatch {
+                index,
+                expected: Some(expected),
+                actual,
+            } => write!(
+                f,
+                "replay mismatch at entry {}: expected {}, got {}",
+                index,
+                expected.to_json(),
+                actual.to_json()
+            ),
+            Self::Mismatch {
+                index,
+                expected: None,
+                actual,
+            } => write!(
+                f,
+                "unexpected access after the end of the session (entry {}): {}",
+                index,
+                actual.to_json()
+            ),
+            Self::Access { index, error } => {
+                write!(f, "access failed at entry {}: {:?}", index, error)
+            }
+            Self::Unsupported(index) => write!(f, "entry {} cannot be replayed", index),
+            Self::Unconsumed(count) => write!(f, "{} recorded accesses were not replayed", count),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// 実機へのリプレイで読み出し値をどう扱うか。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// 読み出し値を記録と比較し、違えば `Mismatch` を返す（決定的なデバイスやメモリ向け）
+    #[default]
+    Verify,
+    /// 記録どおりにアクセスを発行するだけで読み出し値は比較しない
+    /// （ステータスやカウンタが毎回変わる実機の初期化シーケンスを流し直す用）
+    Issue,
+}
+
+/// 記録したアクセスの列。JSON Lines で保存・読み込みできる。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessSession {
+    entries: Vec<SessionEntry>,
+}
+
+impl AccessSession {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_entries(entries: Vec<SessionEntry>) -> Self {
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[SessionEntry] {
+        &self.entries
+    }
+
+    pub fn push(&mut self, entry: SessionEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<(), SessionError> {
+        for entry in &self.entries {
+            writeln!(writer, "{}", entry.to_json())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn load<R: BufRead>(reader: R) -> Result<Self, SessionError> {
+        let mut entries = Vec::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(SessionEntry::from_json(&line).ok_or(SessionError::Parse(i + 1))?);
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn save_file(&self, path: impl AsRef<Path>) -> Result<(), SessionError> {
+        self.save(BufWriter::new(File::create(path)?))
+    }
+
+    pub fn load_file(path: impl AsRef<Path>) -> Result<Self, SessionError> {
+        Self::load(BufReader::new(File::open(path)?))
+    }
+
+    /// 実機のアクセサへ記録どおりにアクセスし、読み出し値を記録と比較する。
+    ///
+    /// `acc` は記録時と同じ物理アドレスにあるものとする（`replay_at(acc, acc.phys_addr())`）。
+    pub fn replay<A: MemAccess>(&self, acc: &A) -> Result<(), ReplayError> {
+        self.replay_at(acc, acc.phys_addr())
+    }
+
+    /// 記録上の物理アドレス `base` を `acc` の先頭に対応させてリプレイする。
+    ///
+    /// - メモリアクセスとコピーは `phys_addr - base + offset` にアクセスする
+    /// - レジスタアクセスは `phys_addr == base` のものだけリプレイできる
+    /// - 記録で失敗したアクセスも発行するが、結果は比較しない
+    /// - 読み出し値は常に比較する（比較しない場合は `replay_with_mode` に `ReplayMode::Issue`）
+    pub fn replay_at<A: MemAccess>(&self, acc: &A, base: usize) -> Result<(), ReplayError> {
+        self.replay_with_mode(acc, base, ReplayMode::Verify)
+    }
+
+    /// `replay_at` と同じだが、読み出し値を比較するかを `mode` で選ぶ。
+    ///
+    /// `ReplayMode::Issue` でも、記録で成功したアクセスの失敗は `Access` として返す。
+    pub fn replay_with_mode<A: MemAccess>(
+        &self,
+        acc: &A,
+        base: usize,
+        mode: ReplayMode,
+    ) -> Result<(), ReplayError> {
+        for (index, entry) in self.entries.iter().enumerate() {
+            let offset = match entry.kind {
+                AccessKind::ReadReg | AccessKind::WriteReg if entry.phys_addr != base => {
+                    return Err(ReplayError::Unsupported(index));
+                }
+                AccessKind::ReadReg | AccessKind::WriteReg => entry.offset,
+                _ => entry
+                    .phys_addr
+                    .checked_sub(base)
+                    .and_then(|rel| rel.checked_add(entry.offset))
+                    .ok_or(ReplayError::Unsupported(index))?,
+            };
+            let result = unsafe { replay_entry(acc, entry, offset) };
+            match result {
+                Ok(Some(actual))
+                    if mode == ReplayMode::Verify
+                        && !entry.failed
+                        && (actual.value != entry.value
+                            || (!entry.data.is_empty() && actual.data != entry.data)) =>
+                {
+                    return Err(ReplayError::Mismatch {
+                        index,
+                        expected: Some(Box::new(entry.clone())),
+                        actual: Box::new(actual),
+                    });
+                }
+                Err(ReplayError::Access { .. }) if entry.failed => {}
+                Err(ReplayError::Access { error, .. }) => {
+                    return Err(ReplayError::Access { index, error });
+                }
+                Err(_) => return Err(ReplayError::Unsupported(index)),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+macro_rules! replay_width {
+    ($width:expr, $u8:expr, $u16:expr, $u32:expr, $u64:expr) => {
+        match $width {
+            1 => $u8,
+            2 => $u16,
+            4 => $u32,
+            8 => $u64,
+            _ => return Err(ReplayError::Unsupported(0)),
+        }
+    };
+}
+
+// 1 件を発行し、読み出し系なら実際の値を入れたエントリを返す
+unsafe fn replay_entry<A: MemAccess>(
+    acc: &A,
+    entry: &SessionEntry,
+    offset: usize,
+) -> Result<Option<SessionEntry>, ReplayError> {
+    let access = |error| ReplayError::Access { index: 0, error };
+    let value = entry.value;
+    unsafe {
+        match entry.kind {
+            AccessKind::Write => {
+                replay_width!(
+                    entry.width,
+                    acc.try_write_mem_u8(offset, value as u8),
+                    acc.try_write_mem_u16(offset, value as u16),
+                    acc.try_write_mem_u32(offset, value as u32),
+                    acc.try_write_mem_u64(offset, value)
+                )
+                .map_err(access)?;
+                Ok(None)
+            }
+            AccessKind::WriteReg => {
+                replay_width!(
+                    entry.width,
+                    acc.try_write_reg_u8(offset, value as u8),
+                    acc.try_write_reg_u16(offset, value as u16),
+                    acc.try_write_reg_u32(offset, value as u32),
+                    acc.try_write_reg_u64(offset, value)
+                )
+                .map_err(access)?;
+                Ok(None)
+            }
+            AccessKind::Read => {
+                let value = replay_width!(
+                    entry.width,
+                    acc.try_read_mem_u8(offset).map(u64::from),
+                    acc.try_read_mem_u16(offset).map(u64::from),
+                    acc.try_read_mem_u32(offset).map(u64::from),
+                    acc.try_read_mem_u64(offset)
+                )
+                .map_err(access)?;
+                Ok(Some(SessionEntry {
+                    value,
+                    ..entry.clone()
+                }))
+            }
+            AccessKind::ReadReg => {
+                let value = replay_width!(
+                    entry.width,
+                    acc.try_read_reg_u8(offset).map(u64::from),
+                    acc.try_read_reg_u16(offset).map(u64::from),
+                    acc.try_read_reg_u32(offset).map(u64::from),
+                    acc.try_read_reg_u64(offset)
+                )
+                .map_err(access)?;
+                Ok(Some(SessionEntry {
+                    value,
+                    ..entry.clone()
+                }))
+            }
+            AccessKind::CopyFrom => {
+                if entry.data.len() != entry.count * entry.width {
+                    return Err(ReplayError::Unsupported(0));
+                }
+                let data = &entry.data;
+                replay_width!(
+                    entry.width,
+                    acc.try_copy_from_u8(data.as_ptr(), offset, entry.count),
+                    acc.try_copy_from_u16(words::<u16>(data).as_ptr(), offset, entry.count),
+                    acc.try_copy_from_u32(words::<u32>(data).as_ptr(), offset, entry.count),
+                    acc.try_copy_from_u64(words::<u64>(data).as_ptr(), offset, entry.count)
+                )
+                .map_err(access)?;
+                Ok(None)
+            }
+            AccessKind::CopyTo => {
+                let count = entry.count;
+                let data = replay_width!(
+                    entry.width,
+                    copy_to_bytes::<u8>(count, |p| acc.try_copy_to_u8(offset, p, count)),
+                    copy_to_bytes::<u16>(count, |p| acc.try_copy_to_u16(offset, p, count)),
+                    copy_to_bytes::<u32>(count, |p| acc.try_copy_to_u32(offset, p, count)),
+                    copy_to_bytes::<u64>(count, |p| acc.try_copy_to_u64(offset, p, count))
+                )
+                .map_err(access)?;
+                Ok(Some(SessionEntry {
+                    data,
+                    ..entry.clone()
+                }))
+            }
+        }
+    }
+}
+
+// 記録のバイト列（ホストのメモリ表現）を語の列に戻す
+fn words<T: AccessValue + Default>(data: &[u8]) -> Vec<T> {
+    data.chunks_exact(size_of::<T>())
+        .map(|chunk| {
+            let mut word = T::default();
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    chunk.as_ptr(),
+                    &mut word as *mut T as *mut u8,
+                    size_of::<T>(),
+                )
+            };
+            word
+        })
+        .collect()
+}
+
+fn copy_to_bytes<T: AccessValue + Default>(
+    count: usize,
+    copy: impl FnOnce(*mut T) -> Result<(), MemAccessTryError>,
+) -> Result<Vec<u8>, MemAccessTryError> {
+    let mut buf = std::vec![T::default(); count];
+    copy(buf.as_mut_ptr())?;
+    let bytes =
+        unsafe { core::slice::from_raw_parts(buf.as_ptr() as *const u8, count * size_of::<T>()) };
+    Ok(bytes.to_vec())
+}
+
+/// `TracingAccessor` の sink として使い、コピーのデータも含めてセッションを記録する。
+/// clone したハンドルは同じセッションを指す。
+#[derive(Debug, Clone, Default)]
+pub struct SessionRecorder {
+    session: Arc<Mutex<AccessSession>>,
+}
+
+impl SessionRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ここまでの記録のコピー。
+    pub fn session(&self) -> AccessSession {
+        self.lock().clone()
+    }
+
+    /// ここまでの記録を取り出して空にする。
+    pub fn take(&self) -> AccessSession {
+        core::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, AccessSession> {
+        self.session
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl TraceSink for SessionRecorder {
+    fn record(&self, record: &AccessRecord) {
+        self.lock().push(SessionEntry::from_record(record, &[]));
+    }
+
+    fn record_data(&self, record: &AccessRecord, data: &[u8]) {
+        self.lock().push(SessionEntry::from_record(record, data));
+    }
+}
+
+#[derive(Debug)]
+struct ReplayState {
+    session: AccessSession,
+    next: usize,
+    error: Option<ReplayError>,
+}
+
+/// 記録したセッションを再生するモック `MemAccess`。
+///
+/// - アクセスは記録と同じ順序・種類・アドレス・幅で、書き込みは値まで一致する必要がある
+/// - 読み出しとコピー（デバイス → ホスト）は記録した値を返す
+/// - 不一致は最初の 1 件を保持し、`try_*` は `AccessFault`、それ以外は panic する
+/// - 記録で失敗したアクセスは `try_*` では `AccessFault` を返す
+/// - 最後に `finish` で不一致や未消化の記録がないことを確認する
+#[derive(Debug, Clone)]
+pub struct ReplayAccessor {
+    state: Arc<Mutex<ReplayState>>,
+    phys_addr: usize,
+    size: usize,
+}
+
+impl ReplayAccessor {
+    /// `phys_addr` は記録時のアクセサの物理アドレス。
+    pub fn new(session: AccessSession, phys_addr: usize, size: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ReplayState {
+                session,
+                next: 0,
+                error: None,
+            })),
+            phys_addr,
+            size,
+        }
+    }
+
+    /// 同じセッションを共有する部分領域（範囲外は panic）。
+    pub fn subclone(&self, offset: usize, size: usize) -> Self {
+        expect_subclone(self.try_subclone(offset, size), offset, size)
+    }
+
+    /// 同じセッションを共有する部分領域（範囲外は `OutOfBounds`）。
+    pub fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        let size = subregion_size(self.size, offset, size)?;
+        Ok(Self {
+            state: self.state.clone(),
+            phys_addr: self.phys_addr + offset,
+            size,
+        })
+    }
+
+    /// まだ再生していない記録の数。
+    pub fn remaining(&self) -> usize {
+        let state = self.lock();
+        state.session.len() - state.next
+    }
+
+    pub fn finish(&self) -> Result<(), ReplayError> {
+        let state = self.lock();
+        if let Some(error) = &state.error {
+            return Err(error.clone());
+        }
+        match state.session.len() - state.next {
+            0 => Ok(()),
+            remaining => Err(ReplayError::Unconsumed(remaining)),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ReplayState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn replay(
+        &self,
+        kind: AccessKind,
+        offset: usize,
+        width: usize,
+        count: usize,
+        value: u64,
+        data: &[u8],
+    ) -> Result<SessionEntry, MemAccessTryError> {
+        let mut state = self.lock();
+        if state.error.is_some() {
+            return Err(MemAccessTryError::AccessFault);
+        }
+        let actual = SessionEntry {
+            kind,
+            phys_addr: self.phys_addr,
+            offset,
+            width,
+            count,
+            value,
+            failed: false,
+            data: data.to_vec(),
+        };
+        let index = state.next;
+        let expected = state.session.entries.get(index).cloned();
+        match expected {
+            Some(expected) if expected.matches(&actual) => {
+                state.next += 1;
+                if expected.failed {
+                    Err(MemAccessTryError::AccessFault)
+                } else {
+                    Ok(expected)
+                }
+            }
+            expected => {
+                state.error = Some(ReplayError::Mismatch {
+                    index,
+                    expected: expected.map(Box::new),
+                    actual: Box::new(actual),
+                });
+                Err(MemAccessTryError::AccessFault)
+            }
+        }
+    }
+
+    fn unwrap<T>(&self, result: Result<T, MemAccessTryError>) -> T {
+        result.unwrap_or_else(|_| match &self.lock().error {
+            Some(error) => panic!("{}", error),
+            None => panic!("access failed in the recorded session"),
+        })
+    }
+
+    fn write<V: AccessValue>(
+        &self,
+        kind: AccessKind,
+        offset: usize,
+        data: V,
+    ) -> Result<(), MemAccessTryError> {
+        self.replay(kind, offset, size_of::<V>(), 1, data.to_u64(), &[])
+            .map(|_| ())
+    }
+
+    fn read<V: AccessValue>(
+        &self,
+        kind: AccessKind,
+        offset: usize,
+    ) -> Result<V, MemAccessTryError> {
+        self.replay(kind, offset, size_of::<V>(), 1, 0, &[])
+            .map(|entry| V::from_u64(entry.value))
+    }
+
+    unsafe fn copy_to<V>(
+        &self,
+        offset: usize,
+        dst_ptr: *mut V,
+        count: usize,
+    ) -> Result<(), MemAccessTryError> {
+        let entry = self.replay(AccessKind::CopyTo, offset, size_of::<V>(), count, 0, &[])?;
+        // データのない記録（TraceRing 由来など）は書き込み先を変更しない
+        if entry.data.len() == count * size_of::<V>() {
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    entry.data.as_ptr(),
+                    dst_ptr as *mut u8,
+                    entry.data.len(),
+                )
+            };
+        }
+        Ok(())
+    }
+
+    unsafe fn copy_from<V>(
+        &self,
+        src_ptr: *const V,
+        offset: usize,
+        count: usize,
+    ) -> Result<(), MemAccessTryError> {
+        let data =
+            unsafe { core::slice::from_raw_parts(src_ptr as *const u8, count * size_of::<V>()) };
+        self.replay(AccessKind::CopyFrom, offset, size_of::<V>(), count, 0, data)
+            .map(|_| ())
+    }
+}
+
+macro_rules! replay_copy_fns {
+    ($t:ty, $copy_to:ident, $try_copy_to:ident, $copy_from:ident, $try_copy_from:ident) => {
+        unsafe fn $copy_to(&self, src_adr: usize, dst_ptr: *mut $t, count: usize) {
+            self.unwrap(unsafe { self.copy_to(src_adr, dst_ptr, count) })
+        }
+        unsafe fn $try_copy_to(
+            &self,
+            src_adr: usize,
+            dst_ptr: *mut $t,
+            count: usize,
+        ) -> Result<(), MemAccessTryError> {
+            unsafe { self.copy_to(src_adr, dst_ptr, count) }
+        }
+        unsafe fn $copy_from(&self, src_ptr: *const $t, dst_adr: usize, count: usize) {
+            self.unwrap(unsafe { self.copy_from(src_ptr, dst_adr, count) })
+        }
+        unsafe fn $try_copy_from(
+            &self,
+            src_ptr: *const $t,
+            dst_adr: usize,
+            count: usize,
+        ) -> Result<(), MemAccessTryError> {
+            unsafe { self.copy_from(src_ptr, dst_adr, count) }
+        }
+    };
+}
+
+macro_rules! replay_rw_fns {
+    ($t:ty, $write_kind:ident, $write:ident, $try_write:ident, $read_kind:ident, $read:ident, $try_read:ident) => {
+        unsafe fn $write(&self, offset: usize, data: $t) {
+            self.unwrap(self.write(AccessKind::$write_kind, offset, data))
+        }
+        unsafe fn $try_write(&self, offset: usize, data: $t) -> Result<(), MemAccessTryError> {
+            self.write(AccessKind::$write_kind, offset, data)
+        }
+        unsafe fn $read(&self, offset: usize) -> $t {
+            self.unwrap(self.read(AccessKind::$read_kind, offset))
+        }
+        unsafe fn $try_read(&self, offset: usize) -> Result<$t, MemAccessTryError> {
+            self.read(AccessKind::$read_kind, offset)
+        }
+    };
+}
+
+impl MemAccess for ReplayAccessor {
+    fn addr(&self) -> usize {
+        self.phys_addr
+    }
+    fn size(&self) -> usize {
+        self.size
+    }
+    fn phys_addr(&self) -> usize {
+        self.phys_addr
+    }
+
+    for_each_access_type!(replay_copy_fns, replay_rw_fns);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mmio_accessor::MmioAccessor;
+    use crate::tracing_accessor::TracingAccessor;
+
+    // テスト対象の「ドライバ」
+    unsafe fn driver<A: MemAccess>(acc: &A, start: u32) -> Result<u32, MemAccessTryError> {
+        unsafe {
+            acc.try_write_reg_u32(0, start)?;
+            let status = acc.try_read_reg_u32(1)?;
+            let data = [0x11u16, 0x22, 0x33];
+            acc.try_copy_from_u16(data.as_ptr(), 0x10, data.len())?;
+            let mut out = [0u8; 4];
+            acc.try_copy_to_u8(0x20, out.as_mut_ptr(), out.len())?;
+            Ok(status + out[3] as u32)
+        }
+    }
+
+    fn record(mem: &mut [u32; 16]) -> (AccessSession, usize) {
+        mem[1] = 0x40;
+        mem[8] = 0x0500_0000;
+        let acc = MmioAccessor::<u32>::new(mem.as_mut_ptr() as usize, 64);
+        let recorder = SessionRecorder::new();
+        let traced = TracingAccessor::new(acc.clone(), recorder.clone());
+        assert_eq!(unsafe { driver(&traced, 1) }, Ok(0x45));
+        (recorder.take(), acc.phys_addr())
+    }
+
+    #[test]
+    fn save_load_and_mock_replay() {
+        let mut mem = [0u32; 16];
+        let (session, base) = record(&mut mem);
+        assert_eq!(session.len(), 4);
+        assert_eq!(session.entries()[2].data.len(), 6);
+
+        let mut file = Vec::new();
+        session.save(&mut file).unwrap();
+        let loaded = AccessSession::load(&file[..]).unwrap();
+        assert_eq!(loaded, session);
+        assert!(matches!(
+            AccessSession::load(&b"{\"kind\":\"poke\"}\n"[..]),
+            Err(SessionError::Parse(1))
+        ));
+
+        // 同じ書き込みなら記録どおりの値が返る
+        let mock = ReplayAccessor::new(loaded.clone(), base, 64);
+        assert_eq!(unsafe { driver(&mock, 1) }, Ok(0x45));
+        assert_eq!(mock.finish(), Ok(()));
+
+        // 異なる値を書いたら不一致
+        let mock = ReplayAccessor::new(loaded, base, 64);
+        assert!(unsafe { driver(&mock, 2) }.is_err());
+        match mock.finish() {
+            Err(ReplayError::Mismatch { index: 0, .. }) => {}
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn live_replay() {
+        let mut mem = [0u32; 16];
+        let (session, base) = record(&mut mem);
+
+        let mut target = [0u32; 16];
+        target[1] = 0x40;
+        target[8] = 0x0500_0000;
+        let acc = MmioAccessor::<u32>::new(target.as_mut_ptr() as usize, 64);
+        assert_eq!(session.replay_at(&acc, base), Ok(()));
+        assert_eq!(target[0], 1);
+        assert_eq!(target[4..7], mem[4..7]);
+
+        unsafe { acc.write_mem_u32(4, 0x41) };
+        match session.replay_at(&acc, base) {
+            Err(ReplayError::Mismatch { index: 1, .. }) => {}
+            other => panic!("{:?}", other),
+        }
+
+        // 比較しないモードでは読み出し値が違っても最後まで書き込む
+        target[0] = 0;
+        target[4] = 0x41;
+        assert_eq!(
+            session.replay_with_mode(&acc, base, ReplayMode::Issue),
+            Ok(())
+        );
+        assert_eq!(target[0], 1);
+        assert_eq!(target[4..7], mem[4..7]);
+    }
+}
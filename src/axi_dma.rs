@@ -0,0 +1,747 @@
+#![allow(dead_code)]
+
+use core::fmt;
+
+use super::*;
+
+#[cfg(feature = "std")]
+use std::boxed::Box;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+// Driver for Xilinx AXI DMA (PG021), simple and scatter-gather mode.
+// Registers are accessed through any MemAccess (UioAccessor on target,
+// MmioAccessor over a plain array in tests) and buffers are passed by
+// their physical address (UdmabufAccessor / DmaBuffer).
+
+// ---------------------------------
+//  Registers
+// ---------------------------------
+
+pub const AXI_DMA_MM2S_DMACR: usize = 0x00;
+pub const AXI_DMA_MM2S_DMASR: usize = 0x04;
+pub const AXI_DMA_MM2S_CURDESC: usize = 0x08;
+pub const AXI_DMA_MM2S_CURDESC_MSB: usize = 0x0c;
+pub const AXI_DMA_MM2S_TAILDESC: usize = 0x10;
+pub const AXI_DMA_MM2S_TAILDESC_MSB: usize = 0x14;
+pub const AXI_DMA_MM2S_SA: usize = 0x18;
+pub const AXI_DMA_MM2S_SA_MSB: usize = 0x1c;
+pub const AXI_DMA_MM2S_LENGTH: usize = 0x28;
+pub const AXI_DMA_SG_CTL: usize = 0x2c;
+pub const AXI_DMA_S2MM_DMACR: usize = 0x30;
+pub const AXI_DMA_S2MM_DMASR: usize = 0x34;
+pub const AXI_DMA_S2MM_CURDESC: usize = 0x38;
+pub const AXI_DMA_S2MM_CURDESC_MSB: usize = 0x3c;
+pub const AXI_DMA_S2MM_TAILDESC: usize = 0x40;
+pub const AXI_DMA_S2MM_TAILDESC_MSB: usize = 0x44;
+pub const AXI_DMA_S2MM_DA: usize = 0x48;
+pub const AXI_DMA_S2MM_DA_MSB: usize = 0x4c;
+pub const AXI_DMA_S2MM_LENGTH: usize = 0x58;
+
+pub const AXI_DMA_DMACR_RS: u32 = 1 << 0;
+pub const AXI_DMA_DMACR_RESET: u32 = 1 << 2;
+pub const AXI_DMA_DMACR_KEYHOLE: u32 = 1 << 3;
+pub const AXI_DMA_DMACR_CYCLIC_BD_ENABLE: u32 = 1 << 4;
+pub const AXI_DMA_DMACR_IOC_IRQ_EN: u32 = 1 << 12;
+pub const AXI_DMA_DMACR_DLY_IRQ_EN: u32 = 1 << 13;
+pub const AXI_DMA_DMACR_ERR_IRQ_EN: u32 = 1 << 14;
+pub const AXI_DMA_DMACR_ALL_IRQ_EN: u32 =
+    AXI_DMA_DMACR_IOC_IRQ_EN | AXI_DMA_DMACR_DLY_IRQ_EN | AXI_DMA_DMACR_ERR_IRQ_EN;
+
+pub const AXI_DMA_DMASR_HALTED: u32 = 1 << 0;
+pub const AXI_DMA_DMASR_IDLE: u32 = 1 << 1;
+pub const AXI_DMA_DMASR_SG_INCLD: u32 = 1 << 3;
+pub const AXI_DMA_DMASR_DMA_INT_ERR: u32 = 1 << 4;
+pub const AXI_DMA_DMASR_DMA_SLV_ERR: u32 = 1 << 5;
+pub const AXI_DMA_DMASR_DMA_DEC_ERR: u32 = 1 << 6;
+pub const AXI_DMA_DMASR_SG_INT_ERR: u32 = 1 << 8;
+pub const AXI_DMA_DMASR_SG_SLV_ERR: u32 = 1 << 9;
+pub const AXI_DMA_DMASR_SG_DEC_ERR: u32 = 1 << 10;
+pub const AXI_DMA_DMASR_IOC_IRQ: u32 = 1 << 12;
+pub const AXI_DMA_DMASR_DLY_IRQ: u32 = 1 << 13;
+pub const AXI_DMA_DMASR_ERR_IRQ: u32 = 1 << 14;
+pub const AXI_DMA_DMASR_ALL_IRQ: u32 =
+    AXI_DMA_DMASR_IOC_IRQ | AXI_DMA_DMASR_DLY_IRQ | AXI_DMA_DMASR_ERR_IRQ;
+pub const AXI_DMA_DMASR_ALL_ERR: u32 = AXI_DMA_DMASR_DMA_INT_ERR
+    | AXI_DMA_DMASR_DMA_SLV_ERR
+    | AXI_DMA_DMASR_DMA_DEC_ERR
+    | AXI_DMA_DMASR_SG_INT_ERR
+    | AXI_DMA_DMASR_SG_SLV_ERR
+    | AXI_DMA_DMASR_SG_DEC_ERR;
+
+// ---------------------------------
+//  Scatter-gather descriptor
+// ---------------------------------
+
+pub const AXI_DMA_DESC_CTRL_LEN_MASK: u32 = 0x03ff_ffff;
+pub const AXI_DMA_DESC_CTRL_EOF: u32 = 1 << 26;
+pub const AXI_DMA_DESC_CTRL_SOF: u32 = 1 << 27;
+
+pub const AXI_DMA_DESC_STS_LEN_MASK: u32 = 0x03ff_ffff;
+pub const AXI_DMA_DESC_STS_RXEOF: u32 = 1 << 26;
+pub const AXI_DMA_DESC_STS_RXSOF: u32 = 1 << 27;
+pub const AXI_DMA_DESC_STS_INT_ERR: u32 = 1 << 28;
+pub const AXI_DMA_DESC_STS_SLV_ERR: u32 = 1 << 29;
+pub const AXI_DMA_DESC_STS_DEC_ERR: u32 = 1 << 30;
+pub const AXI_DMA_DESC_STS_CMPLT: u32 = 1 << 31;
+
+// descriptors must be placed on 16 word (0x40) boundaries
+pub const AXI_DMA_DESC_ALIGN: usize = 0x40;
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AxiDmaSgDesc {
+    pub next_desc: u32,
+    pub next_desc_msb: u32,
+    pub buffer_addr: u32,
+    pub buffer_addr_msb: u32,
+    pub reserved: [u32; 2],
+    pub control: u32,
+    pub status: u32,
+    pub app: [u32; 5],
+    pub padding: [u32; 3],
+}
+
+impl AxiDmaSgDesc {
+    pub fn new(buffer_phys_addr: usize, len: usize, sof: bool, eof: bool) -> Self {
+        let mut control = len as u32 & AXI_DMA_DESC_CTRL_LEN_MASK;
+        if sof {
+            control |= AXI_DMA_DESC_CTRL_SOF;
+        }
+        if eof {
+            control |= AXI_DMA_DESC_CTRL_EOF;
+        }
+        AxiDmaSgDesc {
+            buffer_addr: buffer_phys_addr as u32,
+            buffer_addr_msb: (buffer_phys_addr as u64 >> 32) as u32,
+            control,
+            ..Default::default()
+        }
+    }
+
+    pub fn buffer_phys_addr(&self) -> usize {
+        ((self.buffer_addr_msb as u64) << 32 | self.buffer_addr as u64) as usize
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.status & AXI_DMA_DESC_STS_CMPLT != 0
+    }
+
+    pub fn transferred_len(&self) -> usize {
+        (self.status & AXI_DMA_DESC_STS_LEN_MASK) as usize
+    }
+
+    pub fn check_error(&self) -> Result<(), AxiDmaError> {
+        if self.status & AXI_DMA_DESC_STS_DEC_ERR != 0 {
+            Err(AxiDmaError::DecodeError(self.status))
+        } else if self.status & AXI_DMA_DESC_STS_SLV_ERR != 0 {
+            Err(AxiDmaError::SlaveError(self.status))
+        } else if self.status & AXI_DMA_DESC_STS_INT_ERR != 0 {
+            Err(AxiDmaError::InternalError(self.status))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// `#[repr(C)]`, sixteen `u32` words, no padding; the CMPLT bit lives in `status`
+unsafe impl Descriptor for AxiDmaSgDesc {
+    const OWNER_WORD: usize = 7;
+
+    fn set_next(&mut self, next_phys_addr: usize) {
+        self.next_desc = next_phys_addr as u32;
+        self.next_desc_msb = (next_phys_addr as u64 >> 32) as u32;
+    }
+
+    fn is_hw_owned(&self) -> bool {
+        !self.is_complete()
+    }
+
+    fn set_hw_owned(&mut self, owned: bool) {
+        if owned {
+            self.status = 0;
+        } else {
+            self.status |= AXI_DMA_DESC_STS_CMPLT;
+        }
+    }
+}
+
+// ---------------------------------
+//  Driver
+// ---------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxiDmaChannel {
+    Mm2s,
+    S2mm,
+}
+
+impl AxiDmaChannel {
+    fn base(self) -> usize {
+        match self {
+            AxiDmaChannel::Mm2s => AXI_DMA_MM2S_DMACR,
+            AxiDmaChannel::S2mm => AXI_DMA_S2MM_DMACR,
+        }
+    }
+
+    fn status_reg(self) -> usize {
+        match self {
+            AxiDmaChannel::Mm2s => AXI_DMA_MM2S_DMASR,
+            AxiDmaChannel::S2mm => AXI_DMA_S2MM_DMASR,
+        }
+    }
+
+    fn curdesc_reg(self) -> usize {
+        match self {
+            AxiDmaChannel::Mm2s => AXI_DMA_MM2S_CURDESC,
+            AxiDmaChannel::S2mm => AXI_DMA_S2MM_CURDESC,
+        }
+    }
+
+    fn curdesc_msb_reg(self) -> usize {
+        match self {
+            AxiDmaChannel::Mm2s => AXI_DMA_MM2S_CURDESC_MSB,
+            AxiDmaChannel::S2mm => AXI_DMA_S2MM_CURDESC_MSB,
+        }
+    }
+
+    fn taildesc_reg(self) -> usize {
+        match self {
+            AxiDmaChannel::Mm2s => AXI_DMA_MM2S_TAILDESC,
+            AxiDmaChannel::S2mm => AXI_DMA_S2MM_TAILDESC,
+        }
+    }
+
+    fn taildesc_msb_reg(self) -> usize {
+        match self {
+            AxiDmaChannel::Mm2s => AXI_DMA_MM2S_TAILDESC_MSB,
+            AxiDmaChannel::S2mm => AXI_DMA_S2MM_TAILDESC_MSB,
+        }
+    }
+
+    fn addr_reg(self) -> usize {
+        match self {
+            AxiDmaChannel::Mm2s => AXI_DMA_MM2S_SA,
+            AxiDmaChannel::S2mm => AXI_DMA_S2MM_DA,
+        }
+    }
+
+    fn length_reg(self) -> usize {
+        match self {
+            AxiDmaChannel::Mm2s => AXI_DMA_MM2S_LENGTH,
+            AxiDmaChannel::S2mm => AXI_DMA_S2MM_LENGTH,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxiDmaError {
+    Timeout,
+    InvalidLength(usize),
+    NotSupported,
+    MisalignedDesc(usize),
+    InternalError(u32),
+    SlaveError(u32),
+    DecodeError(u32),
+    SgInternalError(u32),
+    SgSlaveError(u32),
+    SgDecodeError(u32),
+}
+
+impl fmt::Display for AxiDmaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout => write!(f, "AxiDmaError: timeout"),
+            Self::InvalidLength(len) => write!(f, "AxiDmaError: invalid length {}", len),
+            Self::NotSupported => write!(f, "AxiDmaError: not supported by this IP configuration"),
+            Self::MisalignedDesc(addr) => write!(
+                f,
+                "AxiDmaError: descriptor 0x{:x} is not 0x{:x} aligned",
+                addr, AXI_DMA_DESC_ALIGN
+            ),
+            Self::InternalError(sts) => {
+                write!(f, "AxiDmaError: DMA internal error (0x{:08x})", sts)
+            }
+            Self::SlaveError(sts) => write!(f, "AxiDmaError: DMA slave error (0x{:08x})", sts),
+            Self::DecodeError(sts) => write!(f, "AxiDmaError: DMA decode error (0x{:08x})", sts),
+            Self::SgInternalError(sts) => {
+                write!(f, "AxiDmaError: SG internal error (0x{:08x})", sts)
+            }
+            Self::SgSlaveError(sts) => write!(f, "AxiDmaError: SG slave error (0x{:08x})", sts),
+            Self::SgDecodeError(sts) => write!(f, "AxiDmaError: SG decode error (0x{:08x})", sts),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AxiDmaError {}
+
+/// AXI DMA register block.
+///
+/// Every register access is `unsafe`: the caller guarantees that `regs` maps the
+/// AXI DMA and that buffer/descriptor addresses handed to it stay valid
+/// until the engine is done with them.
+#[derive(Debug)]
+pub struct AxiDma<T> {
+    regs: T,
+    max_len: usize,
+}
+
+impl<T: MemAccess> AxiDma<T> {
+    // Width of Buffer Length Register defaults to the IP maximum (26 bit)
+    pub fn new(regs: T) -> Self {
+        Self::with_length_width(regs, 26)
+    }
+
+    pub fn with_length_width(regs: T, length_width: u32) -> Self {
+        debug_assert!((8..=26).contains(&length_width));
+        AxiDma {
+            regs,
+            max_len: (1usize << length_width) - 1,
+        }
+    }
+
+    pub fn regs(&self) -> &T {
+        &self.regs
+    }
+
+    pub fn regs_mut(&mut self) -> &mut T {
+        &mut self.regs
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Reads DMACR of `ch`.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    pub unsafe fn control(&self, ch: AxiDmaChannel) -> u32 {
+        self.regs.read_mem_u32(ch.base())
+    }
+
+    /// Writes DMACR of `ch`.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    /// Setting RS starts the engine on whatever addresses are programmed.
+    pub unsafe fn set_control(&self, ch: AxiDmaChannel, data: u32) {
+        self.regs.write_mem_u32(ch.base(), data);
+    }
+
+    /// Reads DMASR of `ch`.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    pub unsafe fn status(&self, ch: AxiDmaChannel) -> u32 {
+        self.regs.read_mem_u32(ch.status_reg())
+    }
+
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    pub unsafe fn is_halted(&self, ch: AxiDmaChannel) -> bool {
+        self.status(ch) & AXI_DMA_DMASR_HALTED != 0
+    }
+
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    pub unsafe fn is_idle(&self, ch: AxiDmaChannel) -> bool {
+        self.status(ch) & AXI_DMA_DMASR_IDLE != 0
+    }
+
+    /// Whether the IP was built with the scatter-gather engine.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    pub unsafe fn has_sg(&self) -> bool {
+        self.status(AxiDmaChannel::Mm2s) & AXI_DMA_DMASR_SG_INCLD != 0
+    }
+
+    /// Soft reset (resets both channels), polls `max_polls` times.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    /// Transfers in flight on either channel are aborted.
+    pub unsafe fn reset(&self, max_polls: usize) -> Result<(), AxiDmaError> {
+        self.set_control(AxiDmaChannel::Mm2s, AXI_DMA_DMACR_RESET);
+        for _ in 0..max_polls {
+            if self.control(AxiDmaChannel::Mm2s) & AXI_DMA_DMACR_RESET == 0 {
+                return Ok(());
+            }
+            core::hint::spin_loop();
+        }
+        Err(AxiDmaError::Timeout)
+    }
+
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    /// The engine starts on the addresses already programmed for `ch`.
+    pub unsafe fn run(&self, ch: AxiDmaChannel) {
+        self.set_control(ch, self.control(ch) | AXI_DMA_DMACR_RS);
+    }
+
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    pub unsafe fn stop(&self, ch: AxiDmaChannel) {
+        self.set_control(ch, self.control(ch) & !AXI_DMA_DMACR_RS);
+    }
+
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    pub unsafe fn set_irq_enable(&self, ch: AxiDmaChannel, enable: bool) {
+        let control = self.control(ch) & !AXI_DMA_DMACR_ALL_IRQ_EN;
+        if enable {
+            self.set_control(ch, control | AXI_DMA_DMACR_ALL_IRQ_EN);
+        } else {
+            self.set_control(ch, control);
+        }
+    }
+
+    /// Clears (write 1 to clear) pending interrupts and returns the status before clearing.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    pub unsafe fn ack_irq(&self, ch: AxiDmaChannel) -> u32 {
+        let status = self.status(ch);
+        self.regs
+            .write_mem_u32(ch.status_reg(), status & AXI_DMA_DMASR_ALL_IRQ);
+        status
+    }
+
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    pub unsafe fn check_error(&self, ch: AxiDmaChannel) -> Result<(), AxiDmaError> {
+        Self::status_to_result(self.status(ch))
+    }
+
+    pub fn status_to_result(status: u32) -> Result<(), AxiDmaError> {
+        if status & AXI_DMA_DMASR_DMA_DEC_ERR != 0 {
+            Err(AxiDmaError::DecodeError(status))
+        } else if status & AXI_DMA_DMASR_DMA_SLV_ERR != 0 {
+            Err(AxiDmaError::SlaveError(status))
+        } else if status & AXI_DMA_DMASR_DMA_INT_ERR != 0 {
+            Err(AxiDmaError::InternalError(status))
+        } else if status & AXI_DMA_DMASR_SG_DEC_ERR != 0 {
+            Err(AxiDmaError::SgDecodeError(status))
+        } else if status & AXI_DMA_DMASR_SG_SLV_ERR != 0 {
+            Err(AxiDmaError::SgSlaveError(status))
+        } else if status & AXI_DMA_DMASR_SG_INT_ERR != 0 {
+            Err(AxiDmaError::SgInternalError(status))
+        } else {
+            Ok(())
+        }
+    }
+
+    // ---------- //  Simple mode
+
+    /// Starts a simple mode transfer; writing LENGTH kicks the engine.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    /// `phys_addr..phys_addr + len` must be DMA-able memory that stays valid
+    /// (and, for S2MM, unaliased by the CPU) until the transfer completes.
+    pub unsafe fn start_simple(
+        &self,
+        ch: AxiDmaChannel,
+        phys_addr: usize,
+        len: usize,
+    ) -> Result<(), AxiDmaError> {
+        if len == 0 || len > self.max_len {
+            return Err(AxiDmaError::InvalidLength(len));
+        }
+        self.run(ch);
+        self.regs.write_mem_u32(ch.addr_reg(), phys_addr as u32);
+        self.regs
+            .write_mem_u32(ch.addr_reg() + 4, (phys_addr as u64 >> 32) as u32);
+        self.regs.write_mem_u32(ch.length_reg(), len as u32);
+        Ok(())
+    }
+
+    /// Transfers `len` bytes from/to `offset` of a DMA buffer (e.g. `UdmabufAccessor`).
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    /// `buf` must be physically contiguous and outlive the transfer.
+    pub unsafe fn start_transfer<B: MemAccess>(
+        &self,
+        ch: AxiDmaChannel,
+        buf: &B,
+        offset: usize,
+        len: usize,
+    ) -> Result<(), AxiDmaError> {
+        if offset.checked_add(len).map_or(true, |end| end > buf.size()) {
+            return Err(AxiDmaError::InvalidLength(len));
+        }
+        self.start_simple(ch, buf.phys_addr() + offset, len)
+    }
+
+    /// Bytes actually written by the last S2MM transfer.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    pub unsafe fn transferred_len(&self, ch: AxiDmaChannel) -> usize {
+        self.regs.read_mem_u32(ch.length_reg()) as usize
+    }
+
+    /// `Ok(true)` once the channel is idle, error bits are reported as `Err`.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    pub unsafe fn poll_done(&self, ch: AxiDmaChannel) -> Result<bool, AxiDmaError> {
+        let status = self.status(ch);
+        Self::status_to_result(status)?;
+        Ok(status & (AXI_DMA_DMASR_IDLE | AXI_DMA_DMASR_HALTED) != 0)
+    }
+
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    pub unsafe fn wait_poll(&self, ch: AxiDmaChannel, max_polls: usize) -> Result<(), AxiDmaError> {
+        for _ in 0..max_polls {
+            if self.poll_done(ch)? {
+                return Ok(());
+            }
+            core::hint::spin_loop();
+        }
+        Err(AxiDmaError::Timeout)
+    }
+
+    // ---------- //  Scatter-gather mode
+
+    /// Points CURDESC at the ring tail and starts `ch`.
+    ///
+    /// Fails with `MisalignedDesc` unless the ring sits on `AXI_DMA_DESC_ALIGN`.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI DMA.
+    /// The channel must be halted (CURDESC is ignored otherwise), and `ring` and
+    /// the buffers its descriptors point at must outlive the transfers.
+    pub unsafe fn start_sg<R: MemAccess>(
+        &self,
+        ch: AxiDmaChannel,
+        ring: &DescRing<R, AxiDmaSgDesc>,
+    ) -> Result<(), AxiDmaError> {
+        if !self.has_sg() {
+            return Err(AxiDmaError::NotSupported);
+        }
+        let first = ring.desc_phys_addr(0);
+        if first % AXI_DMA_DESC_ALIGN != 0 || ring.stride() % AXI_DMA_DESC_ALIGN != 0 {
+            return Err(AxiDmaError::MisalignedDesc(first));
+        }
+        let cur = ring.desc_phys_addr(ring.tail());
+        self.regs.write_mem_u32(ch.curdesc_reg(), cur as u32);
+        self.regs
+            .write_mem_u32(ch.curdesc_msb_reg(), (cur as u64 >> 32) as u32);
+        self.run(ch);
+        Ok(())
+    }
+
+    /// Writes TAILDESC, handing every descriptor up to the last produced one to the engine.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`start_sg`](Self::start_sg), which must have been called on `ring` first.
+    pub unsafe fn submit_sg<R: MemAccess>(
+        &self,
+        ch: AxiDmaChannel,
+        ring: &DescRing<R, AxiDmaSgDesc>,
+    ) {
+        if ring.is_empty() {
+            return;
+        }
+        let last = if ring.head() == 0 {
+            ring.len() - 1
+        } else {
+            ring.head() - 1
+        };
+        let tail = ring.desc_phys_addr(last);
+        self.regs
+            .write_mem_u32(ch.taildesc_msb_reg(), (tail as u64 >> 32) as u32);
+        self.regs.write_mem_u32(ch.taildesc_reg(), tail as u32);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<U> AxiDma<UioAccessor<U>> {
+    // enable the UIO interrupt, block until it fires, then acknowledge the DMA
+    pub fn wait_irq(&mut self, ch: AxiDmaChannel) -> Result<u32, Box<dyn Error>> {
+        unsafe {
+            self.set_irq_enable(ch, true);
+        }
+        self.regs.set_irq_enable(true)?;
+        self.regs.wait_irq()?;
+        let status = unsafe { self.ack_irq(ch) };
+        Self::status_to_result(status)?;
+        Ok(status)
+    }
+
+    pub fn poll_irq(
+        &mut self,
+        ch: AxiDmaChannel,
+        timeout_ms: i32,
+    ) -> Result<Option<u32>, Box<dyn Error>> {
+        unsafe {
+            self.set_irq_enable(ch, true);
+        }
+        self.regs.set_irq_enable(true)?;
+        if self.regs.poll_irq(timeout_ms)?.is_none() {
+            return Ok(None);
+        }
+        let status = unsafe { self.ack_irq(ch) };
+        Self::status_to_result(status)?;
+        Ok(Some(status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_mode_registers() {
+        let mut regs = [0u32; 32];
+        let mut mem = [0u64; 64];
+        let dma = AxiDma::new(MmioAccessor::<u32>::new(regs.as_mut_ptr() as usize, 0x80));
+        let buf = MmioAccessor::<u32>::new(mem.as_mut_ptr() as usize, 512);
+
+        unsafe {
+            dma.start_transfer(AxiDmaChannel::S2mm, &buf, 0x40, 0x100)
+                .unwrap();
+            assert_eq!(
+                dma.start_transfer(AxiDmaChannel::S2mm, &buf, 0x100, 0x200),
+                Err(AxiDmaError::InvalidLength(0x200))
+            );
+            assert_eq!(
+                dma.start_simple(AxiDmaChannel::Mm2s, 0, 1 << 26),
+                Err(AxiDmaError::InvalidLength(1 << 26))
+            );
+        }
+
+        let phys = buf.phys_addr() + 0x40;
+        assert_eq!(
+            regs[AXI_DMA_S2MM_DMACR / 4] & AXI_DMA_DMACR_RS,
+            AXI_DMA_DMACR_RS
+        );
+        assert_eq!(regs[AXI_DMA_S2MM_DA / 4], phys as u32);
+        assert_eq!(regs[AXI_DMA_S2MM_DA_MSB / 4], (phys as u64 >> 32) as u32);
+        assert_eq!(regs[AXI_DMA_S2MM_LENGTH / 4], 0x100);
+        assert_eq!(regs[AXI_DMA_MM2S_LENGTH / 4], 0);
+    }
+
+    #[test]
+    fn poll_status() {
+        let mut regs = [0u32; 32];
+        let dma = AxiDma::new(MmioAccessor::<u32>::new(regs.as_mut_ptr() as usize, 0x80));
+
+        unsafe {
+            assert_eq!(
+                dma.wait_poll(AxiDmaChannel::Mm2s, 10),
+                Err(AxiDmaError::Timeout)
+            );
+
+            dma.regs().write_mem_u32(
+                AXI_DMA_MM2S_DMASR,
+                AXI_DMA_DMASR_IDLE | AXI_DMA_DMASR_IOC_IRQ,
+            );
+            assert_eq!(dma.wait_poll(AxiDmaChannel::Mm2s, 10), Ok(()));
+
+            dma.regs().write_mem_u32(
+                AXI_DMA_S2MM_DMASR,
+                AXI_DMA_DMASR_HALTED | AXI_DMA_DMASR_DMA_SLV_ERR,
+            );
+            assert!(matches!(
+                dma.poll_done(AxiDmaChannel::S2mm),
+                Err(AxiDmaError::SlaveError(_))
+            ));
+        }
+    }
+
+    #[repr(align(64))]
+    struct Aligned64([u32; 128]);
+
+    #[test]
+    fn scatter_gather() {
+        let mut regs = [0u32; 32];
+        let mut ring_mem = Aligned64([0; 128]);
+        let dma = AxiDma::new(MmioAccessor::<u32>::new(regs.as_mut_ptr() as usize, 0x80));
+        let ring_acc = MmioAccessor::<u32>::new(ring_mem.0.as_mut_ptr() as usize, 512);
+        let mut ring = DescRing::<_, AxiDmaSgDesc>::new(ring_acc, 4, AXI_DMA_DESC_ALIGN).unwrap();
+        assert_eq!(ring.stride(), AXI_DMA_DESC_ALIGN);
+
+        unsafe {
+            ring.init(AxiDmaSgDesc::default());
+
+            // SG engine not present
+            assert_eq!(
+                dma.start_sg(AxiDmaChannel::Mm2s, &ring),
+                Err(AxiDmaError::NotSupported)
+            );
+            dma.regs().write_mem_u32(
+                AXI_DMA_MM2S_DMASR,
+                AXI_DMA_DMASR_SG_INCLD | AXI_DMA_DMASR_HALTED,
+            );
+
+            dma.start_sg(AxiDmaChannel::Mm2s, &ring).unwrap();
+            ring.produce(AxiDmaSgDesc::new(0x1000, 0x80, true, false))
+                .unwrap();
+            ring.produce(AxiDmaSgDesc::new(0x2000, 0x80, false, true))
+                .unwrap();
+            dma.submit_sg(AxiDmaChannel::Mm2s, &ring);
+
+            let desc = ring.read_desc(1);
+            assert_eq!(desc.buffer_phys_addr(), 0x2000);
+            assert_eq!(desc.control, 0x80 | AXI_DMA_DESC_CTRL_EOF);
+            assert!(desc.is_hw_owned());
+        }
+
+        assert_eq!(
+            regs[AXI_DMA_MM2S_CURDESC / 4],
+            ring.desc_phys_addr(0) as u32
+        );
+        assert_eq!(
+            regs[AXI_DMA_MM2S_TAILDESC / 4],
+            ring.desc_phys_addr(1) as u32
+        );
+        assert_eq!(
+            regs[AXI_DMA_MM2S_DMACR / 4] & AXI_DMA_DMACR_RS,
+            AXI_DMA_DMACR_RS
+        );
+    }
+
+    #[test]
+    fn scatter_gather_misaligned_ring() {
+        let mut regs = [0u32; 32];
+        let mut ring_mem = Aligned64([0; 128]);
+        regs[AXI_DMA_MM2S_DMASR / 4] = AXI_DMA_DMASR_SG_INCLD | AXI_DMA_DMASR_HALTED;
+        let dma = AxiDma::new(MmioAccessor::<u32>::new(regs.as_mut_ptr() as usize, 0x80));
+        let ring_acc = MmioAccessor::<u32>::new(ring_mem.0.as_mut_ptr() as usize + 8, 448);
+        let ring = DescRing::<_, AxiDmaSgDesc>::new(ring_acc, 4, 8).unwrap();
+
+        unsafe {
+            assert_eq!(
+                dma.start_sg(AxiDmaChannel::S2mm, &ring),
+                Err(AxiDmaError::MisalignedDesc(ring.desc_phys_addr(0)))
+            );
+        }
+        assert_eq!(regs[AXI_DMA_S2MM_CURDESC / 4], 0);
+        assert_eq!(regs[AXI_DMA_S2MM_DMACR / 4] & AXI_DMA_DMACR_RS, 0);
+    }
+}
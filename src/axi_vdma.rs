@@ -0,0 +1,695 @@
+#![allow(dead_code)]
+
+use core::fmt;
+
+use super::*;
+
+// Driver for Xilinx AXI VDMA (PG020) and frame buffers living in DMA memory
+// (typically one UdmabufAccessor holding all frame stores).
+// Channels are selected with AxiDmaChannel (MM2S = read, S2MM = write).
+
+// ---------------------------------
+//  Registers
+// ---------------------------------
+
+pub const AXI_VDMA_MM2S_VDMACR: usize = 0x00;
+pub const AXI_VDMA_MM2S_VDMASR: usize = 0x04;
+pub const AXI_VDMA_PARK_PTR_REG: usize = 0x28;
+pub const AXI_VDMA_VERSION: usize = 0x2c;
+pub const AXI_VDMA_S2MM_VDMACR: usize = 0x30;
+pub const AXI_VDMA_S2MM_VDMASR: usize = 0x34;
+pub const AXI_VDMA_MM2S_VSIZE: usize = 0x50;
+pub const AXI_VDMA_MM2S_HSIZE: usize = 0x54;
+pub const AXI_VDMA_MM2S_FRMDLY_STRIDE: usize = 0x58;
+pub const AXI_VDMA_MM2S_START_ADDRESS: usize = 0x5c;
+pub const AXI_VDMA_S2MM_VSIZE: usize = 0xa0;
+pub const AXI_VDMA_S2MM_HSIZE: usize = 0xa4;
+pub const AXI_VDMA_S2MM_FRMDLY_STRIDE: usize = 0xa8;
+pub const AXI_VDMA_S2MM_START_ADDRESS: usize = 0xac;
+
+pub const AXI_VDMA_MAX_FRAMES: usize = 32;
+
+pub const AXI_VDMA_VDMACR_RS: u32 = 1 << 0;
+pub const AXI_VDMA_VDMACR_CIRCULAR_PARK: u32 = 1 << 1;
+pub const AXI_VDMA_VDMACR_RESET: u32 = 1 << 2;
+pub const AXI_VDMA_VDMACR_GENLOCK_EN: u32 = 1 << 3;
+pub const AXI_VDMA_VDMACR_FRAME_CNT_EN: u32 = 1 << 4;
+pub const AXI_VDMA_VDMACR_FRM_CNT_IRQ_EN: u32 = 1 << 12;
+pub const AXI_VDMA_VDMACR_DLY_CNT_IRQ_EN: u32 = 1 << 13;
+pub const AXI_VDMA_VDMACR_ERR_IRQ_EN: u32 = 1 << 14;
+pub const AXI_VDMA_VDMACR_ALL_IRQ_EN: u32 =
+    AXI_VDMA_VDMACR_FRM_CNT_IRQ_EN | AXI_VDMA_VDMACR_DLY_CNT_IRQ_EN | AXI_VDMA_VDMACR_ERR_IRQ_EN;
+pub const AXI_VDMA_VDMACR_IRQ_FRAME_COUNT_SHIFT: u32 = 16;
+
+pub const AXI_VDMA_VDMASR_HALTED: u32 = 1 << 0;
+pub const AXI_VDMA_VDMASR_INT_ERR: u32 = 1 << 4;
+pub const AXI_VDMA_VDMASR_SLV_ERR: u32 = 1 << 5;
+pub const AXI_VDMA_VDMASR_DEC_ERR: u32 = 1 << 6;
+pub const AXI_VDMA_VDMASR_SOF_EARLY_ERR: u32 = 1 << 7;
+pub const AXI_VDMA_VDMASR_EOL_EARLY_ERR: u32 = 1 << 8;
+pub const AXI_VDMA_VDMASR_SOF_LATE_ERR: u32 = 1 << 11;
+pub const AXI_VDMA_VDMASR_FRM_CNT_IRQ: u32 = 1 << 12;
+pub const AXI_VDMA_VDMASR_DLY_CNT_IRQ: u32 = 1 << 13;
+pub const AXI_VDMA_VDMASR_ERR_IRQ: u32 = 1 << 14;
+pub const AXI_VDMA_VDMASR_EOL_LATE_ERR: u32 = 1 << 15;
+pub const AXI_VDMA_VDMASR_ALL_IRQ: u32 =
+    AXI_VDMA_VDMASR_FRM_CNT_IRQ | AXI_VDMA_VDMASR_DLY_CNT_IRQ | AXI_VDMA_VDMASR_ERR_IRQ;
+
+// ---------------------------------
+//  Video format / frame buffers
+// ---------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VideoFormat {
+    pub width: usize,
+    pub height: usize,
+    pub bytes_per_pixel: usize,
+    pub stride: usize,
+}
+
+impl VideoFormat {
+    // stride is rounded up to `align` bytes (VDMA requires a multiple of the bus width)
+    pub fn new(width: usize, height: usize, bytes_per_pixel: usize, align: usize) -> Self {
+        debug_assert!(align.is_power_of_two());
+        let hsize = width * bytes_per_pixel;
+        VideoFormat {
+            width,
+            height,
+            bytes_per_pixel,
+            stride: (hsize + align - 1) & !(align - 1),
+        }
+    }
+
+    // bytes per line actually transferred
+    pub fn hsize(&self) -> usize {
+        self.width * self.bytes_per_pixel
+    }
+
+    pub fn frame_size(&self) -> usize {
+        self.stride * self.height
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxiVdmaError {
+    Timeout,
+    InvalidFormat,
+    InvalidFrameCount(usize),
+    InvalidFrame(usize),
+    BufferTooSmall,
+    InternalError(u32),
+    SlaveError(u32),
+    DecodeError(u32),
+    SyncError(u32),
+}
+
+impl fmt::Display for AxiVdmaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout => write!(f, "AxiVdmaError: timeout"),
+            Self::InvalidFormat => write!(f, "AxiVdmaError: invalid video format"),
+            Self::InvalidFrameCount(n) => write!(f, "AxiVdmaError: invalid frame count {}", n),
+            Self::InvalidFrame(n) => write!(f, "AxiVdmaError: invalid frame {}", n),
+            Self::BufferTooSmall => write!(f, "AxiVdmaError: buffer too small"),
+            Self::InternalError(sts) => {
+                write!(f, "AxiVdmaError: VDMA internal error (0x{:08x})", sts)
+            }
+            Self::SlaveError(sts) => write!(f, "AxiVdmaError: VDMA slave error (0x{:08x})", sts),
+            Self::DecodeError(sts) => write!(f, "AxiVdmaError: VDMA decode error (0x{:08x})", sts),
+            Self::SyncError(sts) => write!(f, "AxiVdmaError: video sync error (0x{:08x})", sts),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AxiVdmaError {}
+
+// Frame stores placed back to back (each rounded up to `align`) in one DMA buffer
+#[derive(Debug)]
+pub struct FrameBuffers<B> {
+    buf: B,
+    format: VideoFormat,
+    count: usize,
+    frame_pitch: usize,
+}
+
+impl<B: MemAccess> FrameBuffers<B> {
+    pub fn new(
+        buf: B,
+        format: VideoFormat,
+        count: usize,
+        align: usize,
+    ) -> Result<Self, AxiVdmaError> {
+        if count == 0 || count > AXI_VDMA_MAX_FRAMES {
+            return Err(AxiVdmaError::InvalidFrameCount(count));
+        }
+        if !align.is_power_of_two() || format.hsize() == 0 || format.hsize() > format.stride {
+            return Err(AxiVdmaError::InvalidFormat);
+        }
+        let frame_pitch = (format.frame_size() + align - 1) & !(align - 1);
+        match frame_pitch.checked_mul(count) {
+            Some(total) if total <= buf.size() => {}
+            _ => return Err(AxiVdmaError::BufferTooSmall),
+        }
+        Ok(FrameBuffers {
+            buf,
+            format,
+            count,
+            frame_pitch,
+        })
+    }
+
+    pub fn buffer(&self) -> &B {
+        &self.buf
+    }
+
+    pub fn format(&self) -> &VideoFormat {
+        &self.format
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn frame_offset(&self, index: usize) -> usize {
+        debug_assert!(index < self.count);
+        index * self.frame_pitch
+    }
+
+    pub fn frame_phys_addr(&self, index: usize) -> usize {
+        self.buf.phys_addr() + self.frame_offset(index)
+    }
+
+    pub fn frame(&self, index: usize) -> FrameView<'_, B> {
+        FrameView {
+            buf: &self.buf,
+            offset: self.frame_offset(index),
+            format: self.format,
+        }
+    }
+}
+
+// 2D view of one frame store (stride aware)
+#[derive(Debug, Clone, Copy)]
+pub struct FrameView<'a, B> {
+    buf: &'a B,
+    offset: usize,
+    format: VideoFormat,
+}
+
+impl<B: MemAccess> FrameView<'_, B> {
+    pub fn format(&self) -> &VideoFormat {
+        &self.format
+    }
+
+    pub fn width(&self) -> usize {
+        self.format.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.format.height
+    }
+
+    pub fn stride(&self) -> usize {
+        self.format.stride
+    }
+
+    pub fn phys_addr(&self) -> usize {
+        self.buf.phys_addr() + self.offset
+    }
+
+    // offset of pixel (x, y) from the top of the underlying buffer
+    pub fn pixel_offset(&self, x: usize, y: usize) -> usize {
+        debug_assert!(x < self.format.width && y < self.format.height);
+        self.offset + y * self.format.stride + x * self.format.bytes_per_pixel
+    }
+
+    /// Copies row `y` into `dst` (at most `hsize` bytes).
+    ///
+    /// # Safety
+    ///
+    /// The underlying buffer must be mapped and `y` must be less than `height()`.
+    pub unsafe fn read_row_u8(&self, y: usize, dst: &mut [u8]) {
+        let len = core::cmp::min(dst.len(), self.format.hsize());
+        self.buf
+            .copy_to_u8(self.pixel_offset(0, y), dst.as_mut_ptr(), len);
+    }
+
+    /// Copies `src` (at most `hsize` bytes) into row `y`.
+    ///
+    /// # Safety
+    ///
+    /// The underlying buffer must be mapped and `y` must be less than `height()`.
+    /// Writing a frame the engine is currently reading tears the output.
+    pub unsafe fn write_row_u8(&self, y: usize, src: &[u8]) {
+        let len = core::cmp::min(src.len(), self.format.hsize());
+        self.buf
+            .copy_from_u8(src.as_ptr(), self.pixel_offset(0, y), len);
+    }
+
+    /// Copies the whole frame to a packed (stride = hsize) image.
+    ///
+    /// # Safety
+    ///
+    /// The underlying buffer must be mapped.
+    pub unsafe fn read_u8(&self, dst: &mut [u8]) {
+        let hsize = self.format.hsize();
+        for (y, row) in dst.chunks_mut(hsize).take(self.format.height).enumerate() {
+            self.read_row_u8(y, row);
+        }
+    }
+
+    /// Copies a packed (stride = hsize) image into the frame.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`write_row_u8`](Self::write_row_u8).
+    pub unsafe fn write_u8(&self, src: &[u8]) {
+        let hsize = self.format.hsize();
+        for (y, row) in src.chunks(hsize).take(self.format.height).enumerate() {
+            self.write_row_u8(y, row);
+        }
+    }
+}
+
+impl<B: MemAccess + MemAccessSync> FrameView<'_, B> {
+    /// # Safety
+    ///
+    /// The underlying buffer must be mapped.
+    pub unsafe fn sync_for_cpu(&self) {
+        self.buf
+            .sync_for_cpu_with_range(self.offset, self.format.frame_size(), 0, 1);
+    }
+
+    /// # Safety
+    ///
+    /// The underlying buffer must be mapped.
+    pub unsafe fn sync_for_device(&self) {
+        self.buf
+            .sync_for_device_with_range(self.offset, self.format.frame_size(), 0, 1);
+    }
+}
+
+// ---------------------------------
+//  Driver
+// ---------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxiVdmaMode {
+    // loop over all frame stores
+    Circular,
+    // stay on one frame store
+    Park(usize),
+}
+
+/// AXI VDMA register block.
+///
+/// Every register access is `unsafe`: the caller guarantees that `regs` maps the
+/// AXI VDMA and that the frame stores handed to it stay valid while a
+/// channel runs.
+#[derive(Debug)]
+pub struct AxiVdma<T> {
+    regs: T,
+    addr64: bool,
+}
+
+impl<T: MemAccess> AxiVdma<T> {
+    pub fn new(regs: T) -> Self {
+        AxiVdma {
+            regs,
+            addr64: false,
+        }
+    }
+
+    // for IP configured with address width > 32 (two registers per start address)
+    pub fn new_64bit(regs: T) -> Self {
+        AxiVdma { regs, addr64: true }
+    }
+
+    pub fn regs(&self) -> &T {
+        &self.regs
+    }
+
+    fn base(ch: AxiDmaChannel) -> usize {
+        match ch {
+            AxiDmaChannel::Mm2s => AXI_VDMA_MM2S_VDMACR,
+            AxiDmaChannel::S2mm => AXI_VDMA_S2MM_VDMACR,
+        }
+    }
+
+    fn vsize_reg(ch: AxiDmaChannel) -> usize {
+        match ch {
+            AxiDmaChannel::Mm2s => AXI_VDMA_MM2S_VSIZE,
+            AxiDmaChannel::S2mm => AXI_VDMA_S2MM_VSIZE,
+        }
+    }
+
+    /// Reads VDMACR of `ch`.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    pub unsafe fn control(&self, ch: AxiDmaChannel) -> u32 {
+        self.regs.read_mem_u32(Self::base(ch))
+    }
+
+    /// Writes VDMACR of `ch`.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    /// Setting RS starts the channel on the programmed frame stores.
+    pub unsafe fn set_control(&self, ch: AxiDmaChannel, data: u32) {
+        self.regs.write_mem_u32(Self::base(ch), data);
+    }
+
+    /// Reads VDMASR of `ch`.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    pub unsafe fn status(&self, ch: AxiDmaChannel) -> u32 {
+        self.regs.read_mem_u32(Self::base(ch) + 4)
+    }
+
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    pub unsafe fn is_halted(&self, ch: AxiDmaChannel) -> bool {
+        self.status(ch) & AXI_VDMA_VDMASR_HALTED != 0
+    }
+
+    /// Soft resets `ch`, polling `max_polls` times.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    /// A frame in progress is aborted.
+    pub unsafe fn reset(&self, ch: AxiDmaChannel, max_polls: usize) -> Result<(), AxiVdmaError> {
+        self.set_control(ch, AXI_VDMA_VDMACR_RESET);
+        for _ in 0..max_polls {
+            if self.control(ch) & AXI_VDMA_VDMACR_RESET == 0 {
+                return Ok(());
+            }
+            core::hint::spin_loop();
+        }
+        Err(AxiVdmaError::Timeout)
+    }
+
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    pub unsafe fn stop(&self, ch: AxiDmaChannel) {
+        self.set_control(ch, self.control(ch) & !AXI_VDMA_VDMACR_RS);
+    }
+
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    pub unsafe fn set_irq_enable(&self, ch: AxiDmaChannel, enable: bool) {
+        let control = self.control(ch) & !AXI_VDMA_VDMACR_ALL_IRQ_EN;
+        if enable {
+            self.set_control(ch, control | AXI_VDMA_VDMACR_ALL_IRQ_EN);
+        } else {
+            self.set_control(ch, control);
+        }
+    }
+
+    /// Clears (write 1 to clear) pending interrupts and returns the status before clearing.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    pub unsafe fn ack_irq(&self, ch: AxiDmaChannel) -> u32 {
+        let status = self.status(ch);
+        self.regs
+            .write_mem_u32(Self::base(ch) + 4, status & AXI_VDMA_VDMASR_ALL_IRQ);
+        status
+    }
+
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    pub unsafe fn check_error(&self, ch: AxiDmaChannel) -> Result<(), AxiVdmaError> {
+        let status = self.status(ch);
+        if status & AXI_VDMA_VDMASR_DEC_ERR != 0 {
+            Err(AxiVdmaError::DecodeError(status))
+        } else if status & AXI_VDMA_VDMASR_SLV_ERR != 0 {
+            Err(AxiVdmaError::SlaveError(status))
+        } else if status & AXI_VDMA_VDMASR_INT_ERR != 0 {
+            Err(AxiVdmaError::InternalError(status))
+        } else if status
+            & (AXI_VDMA_VDMASR_SOF_EARLY_ERR
+                | AXI_VDMA_VDMASR_EOL_EARLY_ERR
+                | AXI_VDMA_VDMASR_SOF_LATE_ERR
+                | AXI_VDMA_VDMASR_EOL_LATE_ERR)
+            != 0
+        {
+            Err(AxiVdmaError::SyncError(status))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Programs the start address of frame store `index`.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    /// `index` must be below the number of frame stores the IP was built with, and
+    /// `phys_addr` must point at a DMA-able frame that outlives the channel run.
+    pub unsafe fn set_frame_addr(&self, ch: AxiDmaChannel, index: usize, phys_addr: usize) {
+        let base = Self::vsize_reg(ch) + 0x0c;
+        if self.addr64 {
+            self.regs.write_mem_u32(base + index * 8, phys_addr as u32);
+            self.regs
+                .write_mem_u32(base + index * 8 + 4, (phys_addr as u64 >> 32) as u32);
+        } else {
+            self.regs.write_mem_u32(base + index * 4, phys_addr as u32);
+        }
+    }
+
+    /// Frame store currently used by the channel.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    pub unsafe fn current_frame(&self, ch: AxiDmaChannel) -> usize {
+        let park = self.regs.read_mem_u32(AXI_VDMA_PARK_PTR_REG);
+        let shift = match ch {
+            AxiDmaChannel::Mm2s => 16,
+            AxiDmaChannel::S2mm => 24,
+        };
+        ((park >> shift) & 0x1f) as usize
+    }
+
+    /// Parks `ch` on frame store `frame`.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    pub unsafe fn park(&self, ch: AxiDmaChannel, frame: usize) {
+        let shift = match ch {
+            AxiDmaChannel::Mm2s => 0,
+            AxiDmaChannel::S2mm => 8,
+        };
+        let park = self.regs.read_mem_u32(AXI_VDMA_PARK_PTR_REG) & !(0x1f << shift);
+        self.regs.write_mem_u32(
+            AXI_VDMA_PARK_PTR_REG,
+            park | ((frame as u32 & 0x1f) << shift),
+        );
+        self.set_control(ch, self.control(ch) & !AXI_VDMA_VDMACR_CIRCULAR_PARK);
+    }
+
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    pub unsafe fn set_circular(&self, ch: AxiDmaChannel) {
+        self.set_control(ch, self.control(ch) | AXI_VDMA_VDMACR_CIRCULAR_PARK);
+    }
+
+    /// Programs format and frame stores, then starts the channel (writing VSIZE starts it).
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    /// Every address in `frame_addrs` must point at a DMA-able frame of
+    /// `format.frame_size()` bytes that outlives the channel run.
+    pub unsafe fn start(
+        &self,
+        ch: AxiDmaChannel,
+        format: &VideoFormat,
+        frame_addrs: &[usize],
+        mode: AxiVdmaMode,
+    ) -> Result<(), AxiVdmaError> {
+        if frame_addrs.is_empty() || frame_addrs.len() > AXI_VDMA_MAX_FRAMES {
+            return Err(AxiVdmaError::InvalidFrameCount(frame_addrs.len()));
+        }
+        let hsize = format.hsize();
+        if hsize == 0 || hsize > 0xffff || format.stride < hsize || format.stride > 0xffff {
+            return Err(AxiVdmaError::InvalidFormat);
+        }
+        if format.height == 0 || format.height > 0x1fff {
+            return Err(AxiVdmaError::InvalidFormat);
+        }
+        if let AxiVdmaMode::Park(frame) = mode {
+            if frame >= frame_addrs.len() {
+                return Err(AxiVdmaError::InvalidFrame(frame));
+            }
+        }
+
+        let mut control = self.control(ch) | AXI_VDMA_VDMACR_RS;
+        match mode {
+            AxiVdmaMode::Circular => control |= AXI_VDMA_VDMACR_CIRCULAR_PARK,
+            AxiVdmaMode::Park(frame) => {
+                self.park(ch, frame);
+                control &= !AXI_VDMA_VDMACR_CIRCULAR_PARK;
+            }
+        }
+        self.set_control(ch, control);
+
+        for (index, &addr) in frame_addrs.iter().enumerate() {
+            self.set_frame_addr(ch, index, addr);
+        }
+
+        let vsize = Self::vsize_reg(ch);
+        let frmdly_stride = self.regs.read_mem_u32(vsize + 8) & 0xff00_0000;
+        self.regs
+            .write_mem_u32(vsize + 8, frmdly_stride | format.stride as u32);
+        self.regs.write_mem_u32(vsize + 4, hsize as u32);
+        self.regs.write_mem_u32(vsize, format.height as u32);
+        Ok(())
+    }
+
+    /// `start` with the frame stores of `frames`.
+    ///
+    /// # Safety
+    ///
+    /// `regs` must map the register block of an AXI VDMA.
+    /// `frames` must stay alive (and mapped) while the channel runs.
+    pub unsafe fn start_with_frames<B: MemAccess>(
+        &self,
+        ch: AxiDmaChannel,
+        frames: &FrameBuffers<B>,
+        mode: AxiVdmaMode,
+    ) -> Result<(), AxiVdmaError> {
+        let mut addrs = [0usize; AXI_VDMA_MAX_FRAMES];
+        for (index, addr) in addrs.iter_mut().enumerate().take(frames.count()) {
+            *addr = frames.frame_phys_addr(index);
+        }
+        self.start(ch, frames.format(), &addrs[..frames.count()], mode)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<U> AxiVdma<UioAccessor<U>> {
+    // enable the UIO interrupt, block until it fires, then acknowledge the VDMA
+    pub fn wait_irq(
+        &mut self,
+        ch: AxiDmaChannel,
+    ) -> Result<u32, std::boxed::Box<dyn std::error::Error>> {
+        unsafe {
+            self.set_irq_enable(ch, true);
+        }
+        self.regs.set_irq_enable(true)?;
+        self.regs.wait_irq()?;
+        let status = unsafe { self.ack_irq(ch) };
+        unsafe { self.check_error(ch)? };
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_circular() {
+        let mut regs = [0u32; 64];
+        let mut mem = [0u64; 512];
+        let vdma = AxiVdma::new(MmioAccessor::<u32>::new(regs.as_mut_ptr() as usize, 0x100));
+        let buf = MmioAccessor::<u32>::new(mem.as_mut_ptr() as usize, 4096);
+
+        let format = VideoFormat::new(10, 8, 3, 16);
+        assert_eq!(format.stride, 32);
+        let frames = FrameBuffers::new(buf, format, 3, 64).unwrap();
+
+        unsafe {
+            vdma.start_with_frames(AxiDmaChannel::S2mm, &frames, AxiVdmaMode::Circular)
+                .unwrap();
+        }
+
+        let cr = regs[AXI_VDMA_S2MM_VDMACR / 4];
+        assert_eq!(cr & AXI_VDMA_VDMACR_RS, AXI_VDMA_VDMACR_RS);
+        assert_eq!(
+            cr & AXI_VDMA_VDMACR_CIRCULAR_PARK,
+            AXI_VDMA_VDMACR_CIRCULAR_PARK
+        );
+        assert_eq!(regs[AXI_VDMA_S2MM_VSIZE / 4], 8);
+        assert_eq!(regs[AXI_VDMA_S2MM_HSIZE / 4], 30);
+        assert_eq!(regs[AXI_VDMA_S2MM_FRMDLY_STRIDE / 4], 32);
+        for i in 0..3 {
+            assert_eq!(
+                regs[AXI_VDMA_S2MM_START_ADDRESS / 4 + i],
+                frames.frame_phys_addr(i) as u32
+            );
+        }
+        assert_eq!(frames.frame_offset(1), 256);
+        assert_eq!(regs[AXI_VDMA_MM2S_VSIZE / 4], 0);
+    }
+
+    #[test]
+    fn start_park() {
+        let mut regs = [0u32; 64];
+        let vdma = AxiVdma::new_64bit(MmioAccessor::<u32>::new(regs.as_mut_ptr() as usize, 0x100));
+        let format = VideoFormat::new(16, 4, 2, 8);
+
+        unsafe {
+            assert_eq!(
+                vdma.start(
+                    AxiDmaChannel::Mm2s,
+                    &format,
+                    &[0x1000, 0x2000],
+                    AxiVdmaMode::Park(2)
+                ),
+                Err(AxiVdmaError::InvalidFrame(2))
+            );
+            vdma.start(
+                AxiDmaChannel::Mm2s,
+                &format,
+                &[0x1_0000_1000, 0x2000],
+                AxiVdmaMode::Park(1),
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            regs[AXI_VDMA_MM2S_VDMACR / 4] & AXI_VDMA_VDMACR_CIRCULAR_PARK,
+            0
+        );
+        assert_eq!(regs[AXI_VDMA_PARK_PTR_REG / 4] & 0x1f, 1);
+        assert_eq!(regs[AXI_VDMA_MM2S_START_ADDRESS / 4], 0x1000);
+        assert_eq!(regs[AXI_VDMA_MM2S_START_ADDRESS / 4 + 1], 1);
+        assert_eq!(regs[AXI_VDMA_MM2S_START_ADDRESS / 4 + 2], 0x2000);
+    }
+
+    #[test]
+    fn frame_view_uses_stride() {
+        let mut mem = [0u64; 64];
+        let buf = MmioAccessor::<u32>::new(mem.as_mut_ptr() as usize, 512);
+        let format = VideoFormat::new(3, 4, 1, 8);
+        let frames = FrameBuffers::new(buf, format, 2, 8).unwrap();
+        let frame = frames.frame(1);
+
+        let src: [u8; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let mut dst = [0u8; 12];
+        unsafe {
+            frame.write_u8(&src);
+            frame.read_u8(&mut dst);
+            assert_eq!(frames.buffer().read_mem_u8(32 + 8), 4);
+            assert_eq!(frames.buffer().read_mem_u8(32 + 3), 0);
+        }
+        assert_eq!(src, dst);
+        assert_eq!(frame.pixel_offset(2, 3), 32 + 24 + 2);
+    }
+}
@@ -1,15 +1,76 @@
 #![allow(dead_code)]
 
+use core::cell::{Ref, RefCell, RefMut};
 use core::fmt;
 use core::marker::PhantomData;
+use core::ops::Range;
 
-use crate::{MemAccess, MemAccessTryError};
+use crate::mem_accessor::subregion_size;
+use crate::{MemAccess, MemAccessTryError, StrbMode};
 
 pub trait Bus<A, D, S> {
     type Error;
 
     fn write(&mut self, addr: A, data: D, strb: S) -> Result<(), Self::Error>;
     fn read(&mut self, addr: A) -> Result<D, Self::Error>;
+
+    // Incrementing burst of consecutive words starting at `addr` (beat i is at
+    // `addr + i * D::BYTES`).
+    // The caller must make sure every beat address is representable in `A`
+    // (`BusAccessor` checks the last beat before issuing a burst). The default
+    // bodies check the whole burst before the first beat and panic on a violation,
+    // so a bad burst is never issued partially.
+    fn write_burst(&mut self, addr: A, data: &[D], strb: &[S]) -> Result<(), Self::Error>
+    where
+        A: BusAddress,
+        D: BusWord,
+        S: Copy,
+    {
+        debug_assert_eq!(data.len(), strb.len());
+        let base = addr.to_usize();
+        expect_burst_addrs::<A>(base, data.len(), D::BYTES);
+        for (i, (&data, &strb)) in data.iter().zip(strb).enumerate() {
+            self.write(expect_burst_addr(base, i, D::BYTES), data, strb)?;
+        }
+        Ok(())
+    }
+
+    fn read_burst(&mut self, addr: A, data: &mut [D]) -> Result<(), Self::Error>
+    where
+        A: BusAddress,
+        D: BusWord,
+    {
+        let base = addr.to_usize();
+        expect_burst_addrs::<A>(base, data.len(), D::BYTES);
+        for (i, data) in data.iter_mut().enumerate() {
+            *data = self.read(expect_burst_addr(base, i, D::BYTES))?;
+        }
+        Ok(())
+    }
+}
+
+// address of beat `index` of a burst starting at byte address `base`,
+// None when it overflows or does not fit in `A`
+pub fn burst_addr<A: BusAddress>(base: usize, index: usize, word_bytes: usize) -> Option<A> {
+    index
+        .checked_mul(word_bytes)
+        .and_then(|offset| base.checked_add(offset))
+        .and_then(A::try_from_usize)
+}
+
+fn expect_burst_addr<A: BusAddress>(base: usize, index: usize, word_bytes: usize) -> A {
+    burst_addr(base, index, word_bytes).unwrap_or_else(|| {
+        panic!(
+            "burst beat {} from 0x{:x} is not a valid bus address (caller must pre-validate)",
+            index, base
+        )
+    })
+}
+
+fn expect_burst_addrs<A: BusAddress>(base: usize, len: usize, word_bytes: usize) {
+    if len > 0 {
+        expect_burst_addr::<A>(base, len - 1, word_bytes);
+    }
 }
 
 pub trait BusAddress: Copy {
@@ -17,12 +78,26 @@ pub trait BusAddress: Copy {
     fn try_from_usize(value: usize) -> Option<Self>;
 }
 
+// Data and strobe words are handled per byte lane (lane i = bits [8i+7:8i]), so
+// words wider than 128 bits ([u8; 32], [u8; 64], ...) work like the integer ones.
+// As a strobe, bit i enables byte lane i of the data word.
 pub trait BusWord: Copy {
     const BYTES: usize;
     const BITS: usize;
 
-    fn to_u128(self) -> u128;
-    fn from_u128(value: u128) -> Self;
+    fn zero() -> Self;
+    fn lane(&self, index: usize) -> u8;
+    fn set_lane(&mut self, index: usize, value: u8);
+
+    fn bit(&self, index: usize) -> bool {
+        (self.lane(index / 8) >> (index % 8)) & 1 == 1
+    }
+
+    fn set_bit(&mut self, index: usize, value: bool) {
+        let mask = 1u8 << (index % 8);
+        let byte = self.lane(index / 8);
+        self.set_lane(index / 8, if value { byte | mask } else { byte & !mask });
+    }
 }
 
 pub trait BusValue: Copy {
@@ -63,12 +138,112 @@ impl Endianness for BigEndian {
     }
 }
 
+// upper limit of words in one burst (AXI4 INCR)
+pub const BUS_MAX_BURST_LEN: usize = 256;
+
+// unit of the address passed to Bus::write/read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressUnit {
+    // address N is byte N (AXI, AHB, ...)
+    Byte,
+    // address N is bus word N (Wishbone word addressing, legacy buses)
+    Word,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusAccessorConfig {
+    // words per burst (1..=BUS_MAX_BURST_LEN); bulk copies additionally split bursts
+    // at BURST_STAGE_WORDS since they are staged on the stack
+    pub max_burst_len: usize,
+    // bursts never cross a multiple of this many bytes (0 = no limit)
+    pub burst_boundary: usize,
+    // how partial-word writes are issued; ReadModifyWrite also works without strobes
+    pub strb_mode: StrbMode,
+    // reads have side effects (FIFO pop, clear-on-read), so ReadModifyWrite is refused
+    pub read_side_effects: bool,
+    // offsets, bounds and subclones stay in bytes; only the bus address is converted
+    pub address_unit: AddressUnit,
+}
+
+impl BusAccessorConfig {
+    pub const fn new() -> Self {
+        Self {
+            max_burst_len: BUS_MAX_BURST_LEN,
+            burst_boundary: 4096,
+            strb_mode: StrbMode::ByteLanes,
+            read_side_effects: false,
+            address_unit: AddressUnit::Byte,
+        }
+    }
+
+    pub const fn with_max_burst_len(mut self, max_burst_len: usize) -> Self {
+        self.max_burst_len = max_burst_len;
+        self
+    }
+
+    pub const fn with_burst_boundary(mut self, burst_boundary: usize) -> Self {
+        self.burst_boundary = burst_boundary;
+        self
+    }
+
+    pub const fn with_strb_mode(mut self, strb_mode: StrbMode) -> Self {
+        self.strb_mode = strb_mode;
+        self
+    }
+
+    pub const fn with_read_side_effects(mut self, read_side_effects: bool) -> Self {
+        self.read_side_effects = read_side_effects;
+        self
+    }
+
+    pub const fn with_address_unit(mut self, address_unit: AddressUnit) -> Self {
+        self.address_unit = address_unit;
+        self
+    }
+
+    // bus address of the word at byte address `byte_addr`
+    fn bus_addr<A: BusAddress, BE>(
+        &self,
+        byte_addr: usize,
+        word_bytes: usize,
+    ) -> Result<A, BusAccessorError<BE>> {
+        let addr = match self.address_unit {
+            AddressUnit::Byte => byte_addr,
+            AddressUnit::Word => byte_addr / word_bytes,
+        };
+        A::try_from_usize(addr).ok_or(BusAccessorError::AddressOutOfRange)
+    }
+
+    // number of words of the next burst starting at `addr`
+    fn burst_words(&self, addr: usize, words: usize, word_bytes: usize) -> usize {
+        // Bus::write_burst/read_burst step addresses in bytes
+        if self.address_unit == AddressUnit::Word {
+            return 1;
+        }
+        let max_len = self.max_burst_len.clamp(1, BUS_MAX_BURST_LEN);
+        let mut len = core::cmp::min(words, max_len);
+        if self.burst_boundary != 0 {
+            let to_boundary = (self.burst_boundary - addr % self.burst_boundary) / word_bytes;
+            len = core::cmp::min(len, core::cmp::max(to_boundary, 1));
+        }
+        len
+    }
+}
+
+impl Default for BusAccessorConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BusAccessorError<E> {
     AddressOverflow,
     AddressOutOfRange,
     OutOfBounds,
     StrbTooNarrow,
+    ReadSideEffects,
+    Busy,
     Bus(E),
 }
 
@@ -79,6 +254,10 @@ impl<E: fmt::Display> fmt::Display for BusAccessorError<E> {
             Self::AddressOutOfRange => write!(f, "address is out of representable range"),
             Self::OutOfBounds => write!(f, "access exceeds region bounds"),
             Self::StrbTooNarrow => write!(f, "strb width is smaller than data byte lanes"),
+            Self::ReadSideEffects => {
+                write!(f, "read-modify-write refused on a target with read side effects")
+            }
+            Self::Busy => write!(f, "bus is already borrowed by another access"),
             Self::Bus(err) => write!(f, "bus access failed: {err}"),
         }
     }
@@ -87,12 +266,16 @@ impl<E: fmt::Display> fmt::Display for BusAccessorError<E> {
 #[cfg(feature = "std")]
 impl<E> std::error::Error for BusAccessorError<E> where E: std::error::Error + 'static {}
 
+// The bus sits in a RefCell so that accesses only need &self (MemAccess is &self).
+// A re-entrant access (e.g. from inside the bus implementation) fails with Busy
+// instead of aliasing the bus.
 #[derive(Debug)]
 pub struct BusAccessor<B, A, D, S, E>
 where
     B: Bus<A, D, S>,
 {
-    bus: B,
+    bus: RefCell<B>,
+    config: BusAccessorConfig,
     _phantom: PhantomData<(A, D, S, E)>,
 }
 
@@ -105,151 +288,490 @@ where
     E: Endianness,
 {
     pub fn new(bus: B) -> Self {
+        Self::with_config(bus, BusAccessorConfig::default())
+    }
+
+    pub fn with_config(bus: B, config: BusAccessorConfig) -> Self {
         Self {
-            bus,
+            bus: RefCell::new(bus),
+            config,
             _phantom: PhantomData,
         }
     }
 
-    pub fn bus(&self) -> &B {
-        &self.bus
+    pub fn config(&self) -> &BusAccessorConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: BusAccessorConfig) {
+        self.config = config;
+    }
+
+    pub fn bus(&self) -> Ref<'_, B> {
+        self.bus.borrow()
     }
 
     pub fn bus_mut(&mut self) -> &mut B {
-        &mut self.bus
+        self.bus.get_mut()
     }
 
     pub fn into_inner(self) -> B {
-        self.bus
+        self.bus.into_inner()
+    }
+
+    fn borrow_bus(&self) -> Result<RefMut<'_, B>, BusAccessorError<B::Error>> {
+        self.bus.try_borrow_mut().map_err(|_| BusAccessorError::Busy)
     }
 
     pub fn write_value<V: BusValue>(
-        &mut self,
+        &self,
         addr: A,
         value: V,
     ) -> Result<(), BusAccessorError<B::Error>> {
-        if S::BITS < D::BYTES {
-            return Err(BusAccessorError::StrbTooNarrow);
-        }
-
-        let word_bytes = D::BYTES;
-        let total_bytes = V::BYTES;
-        let mut processed = 0usize;
-        let base_addr = addr.to_usize();
-        let value_bits = value.to_u128();
-
-        while processed < total_bytes {
-            let cur_addr = base_addr
-                .checked_add(processed)
-                .ok_or(BusAccessorError::AddressOverflow)?;
-            let word_addr = (cur_addr / word_bytes) * word_bytes;
-            let lane_offset = cur_addr - word_addr;
-            let chunk_bytes = core::cmp::min(word_bytes - lane_offset, total_bytes - processed);
-
-            let mut data_word = 0u128;
-            let mut strb_word = 0u128;
-
-            for i in 0..chunk_bytes {
-                let value_mem_offset = processed + i;
-                let value_byte_index = E::value_byte_index(total_bytes, value_mem_offset);
-                let value_byte = ((value_bits >> (value_byte_index * 8)) & 0xFF) as u8;
-
-                let lane = E::lane_byte_index(word_bytes, lane_offset + i);
-                data_word |= (value_byte as u128) << (lane * 8);
-                strb_word |= 1u128 << lane;
-            }
-
-            let write_addr = A::try_from_usize(word_addr).ok_or(BusAccessorError::AddressOutOfRange)?;
-            self.bus
-                .write(write_addr, D::from_u128(data_word), S::from_u128(strb_word))
-                .map_err(BusAccessorError::Bus)?;
-
-            processed += chunk_bytes;
-        }
-
-        Ok(())
+        let mut bus = self.borrow_bus()?;
+        write_value_lanes::<B, A, D, S, E, V>(&mut bus, &self.config, addr.to_usize(), value)
     }
 
-    pub fn read_value<V: BusValue>(&mut self, addr: A) -> Result<V, BusAccessorError<B::Error>> {
-        if S::BITS < D::BYTES {
-            return Err(BusAccessorError::StrbTooNarrow);
-        }
-
-        let word_bytes = D::BYTES;
-        let total_bytes = V::BYTES;
-        let mut processed = 0usize;
-        let base_addr = addr.to_usize();
-        let mut value_bits = 0u128;
-
-        while processed < total_bytes {
-            let cur_addr = base_addr
-                .checked_add(processed)
-                .ok_or(BusAccessorError::AddressOverflow)?;
-            let word_addr = (cur_addr / word_bytes) * word_bytes;
-            let lane_offset = cur_addr - word_addr;
-            let chunk_bytes = core::cmp::min(word_bytes - lane_offset, total_bytes - processed);
-
-            let read_addr = A::try_from_usize(word_addr).ok_or(BusAccessorError::AddressOutOfRange)?;
-            let word = self.bus.read(read_addr).map_err(BusAccessorError::Bus)?;
-            let word_bits = word.to_u128();
-
-            for i in 0..chunk_bytes {
-                let lane = E::lane_byte_index(word_bytes, lane_offset + i);
-                let value_mem_offset = processed + i;
-                let value_byte_index = E::value_byte_index(total_bytes, value_mem_offset);
-                let lane_byte = ((word_bits >> (lane * 8)) & 0xFF) as u8;
-                value_bits |= (lane_byte as u128) << (value_byte_index * 8);
-            }
-
-            processed += chunk_bytes;
-        }
-
-        Ok(V::from_u128(value_bits))
+    pub fn read_value<V: BusValue>(&self, addr: A) -> Result<V, BusAccessorError<B::Error>> {
+        let mut bus = self.borrow_bus()?;
+        read_value_lanes::<B, A, D, S, E, V>(&mut bus, &self.config, addr.to_usize())
     }
 
-    pub fn write_u8(&mut self, addr: A, value: u8) -> Result<(), BusAccessorError<B::Error>> {
+    pub fn write_u8(&self, addr: A, value: u8) -> Result<(), BusAccessorError<B::Error>> {
         self.write_value(addr, value)
     }
 
-    pub fn write_u16(&mut self, addr: A, value: u16) -> Result<(), BusAccessorError<B::Error>> {
+    pub fn write_u16(&self, addr: A, value: u16) -> Result<(), BusAccessorError<B::Error>> {
         self.write_value(addr, value)
     }
 
-    pub fn write_u32(&mut self, addr: A, value: u32) -> Result<(), BusAccessorError<B::Error>> {
+    pub fn write_u32(&self, addr: A, value: u32) -> Result<(), BusAccessorError<B::Error>> {
         self.write_value(addr, value)
     }
 
-    pub fn write_u64(&mut self, addr: A, value: u64) -> Result<(), BusAccessorError<B::Error>> {
+    pub fn write_u64(&self, addr: A, value: u64) -> Result<(), BusAccessorError<B::Error>> {
         self.write_value(addr, value)
     }
 
     pub fn write_usize(
-        &mut self,
+        &self,
         addr: A,
         value: usize,
     ) -> Result<(), BusAccessorError<B::Error>> {
         self.write_value(addr, value)
     }
 
-    pub fn read_u8(&mut self, addr: A) -> Result<u8, BusAccessorError<B::Error>> {
+    pub fn read_u8(&self, addr: A) -> Result<u8, BusAccessorError<B::Error>> {
         self.read_value(addr)
     }
 
-    pub fn read_u16(&mut self, addr: A) -> Result<u16, BusAccessorError<B::Error>> {
+    pub fn read_u16(&self, addr: A) -> Result<u16, BusAccessorError<B::Error>> {
         self.read_value(addr)
     }
 
-    pub fn read_u32(&mut self, addr: A) -> Result<u32, BusAccessorError<B::Error>> {
+    pub fn read_u32(&self, addr: A) -> Result<u32, BusAccessorError<B::Error>> {
         self.read_value(addr)
     }
 
-    pub fn read_u64(&mut self, addr: A) -> Result<u64, BusAccessorError<B::Error>> {
+    pub fn read_u64(&self, addr: A) -> Result<u64, BusAccessorError<B::Error>> {
         self.read_value(addr)
     }
 
-    pub fn read_usize(&mut self, addr: A) -> Result<usize, BusAccessorError<B::Error>> {
+    pub fn read_usize(&self, addr: A) -> Result<usize, BusAccessorError<B::Error>> {
         self.read_value(addr)
     }
+
+    // bulk copies, coalesced into bursts
+    pub fn write_slice<V: BusValue>(
+        &self,
+        addr: A,
+        src: &[V],
+    ) -> Result<(), BusAccessorError<B::Error>> {
+        write_slice_burst::<B, A, D, S, E, V>(&mut *self.borrow_bus()?, &self.config, addr.to_usize(), src)
+    }
+
+    pub fn read_slice<V: BusValue>(
+        &self,
+        addr: A,
+        dst: &mut [V],
+    ) -> Result<(), BusAccessorError<B::Error>> {
+        read_slice_burst::<B, A, D, S, E, V>(&mut *self.borrow_bus()?, &self.config, addr.to_usize(), dst)
+    }
+
+    unsafe fn write_raw<V: BusValue>(&self, dst_adr: usize, src_ptr: *const V, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let addr = A::try_from_usize(dst_adr).ok_or(MemAccessTryError::AddressOutOfRange).unwrap();
+        let src = unsafe { core::slice::from_raw_parts(src_ptr, count) };
+        self.write_slice(addr, src).map_err(map_bus_err).unwrap();
+    }
+
+    unsafe fn read_raw<V: BusValue>(&self, src_adr: usize, dst_ptr: *mut V, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let addr = A::try_from_usize(src_adr).ok_or(MemAccessTryError::AddressOutOfRange).unwrap();
+        let dst = unsafe { core::slice::from_raw_parts_mut(dst_ptr, count) };
+        self.read_slice(addr, dst).map_err(map_bus_err).unwrap();
+    }
+}
+
+// ---------- //  Subregion (shared with SharedBusAccessor / LockedBusAccessor)
+
+// (base, size) of the subregion at `offset`. A parent size of 0 is unbounded;
+// a bounded parent follows the same rule as the memory accessors (`subregion_size`).
+pub(crate) fn subregion_range<E>(
+    base: usize,
+    size: usize,
+    offset: usize,
+    sub_size: usize,
+) -> Result<(usize, usize), BusAccessorError<E>> {
+    let new_base = base
+        .checked_add(offset)
+        .ok_or(BusAccessorError::AddressOverflow)?;
+    if size == 0 {
+        new_base
+            .checked_add(sub_size)
+            .ok_or(BusAccessorError::AddressOverflow)?;
+        return Ok((new_base, sub_size));
+    }
+    let new_size = subregion_size(size, offset, sub_size).map_err(|err| match err {
+        MemAccessTryError::AddressOverflow => BusAccessorError::AddressOverflow,
+        _ => BusAccessorError::OutOfBounds,
+    })?;
+    Ok((new_base, new_size))
+}
+
+// ---------- //  Single value access (shared with SharedBusAccessor)
+
+// one value, split into strobed word accesses at `addr` (absolute bus byte address)
+pub(crate) fn write_value_lanes<B, A, D, S, E, V>(
+    bus: &mut B,
+    config: &BusAccessorConfig,
+    addr: usize,
+    value: V,
+) -> Result<(), BusAccessorError<B::Error>>
+where
+    B: Bus<A, D, S>,
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+    E: Endianness,
+    V: BusValue,
+{
+    check_strb::<D, S, B::Error>(config)?;
+
+    let word_bytes = D::BYTES;
+    let total_bytes = V::BYTES;
+    let mut processed = 0usize;
+    let value_bits = value.to_u128();
+
+    while processed < total_bytes {
+        let cur_addr = addr
+            .checked_add(processed)
+            .ok_or(BusAccessorError::AddressOverflow)?;
+        let word_addr = (cur_addr / word_bytes) * word_bytes;
+        let lane_offset = cur_addr - word_addr;
+        let chunk_bytes = core::cmp::min(word_bytes - lane_offset, total_bytes - processed);
+
+        let mut data_word = D::zero();
+        let mut lanes = word_bytes..0;
+
+        for i in 0..chunk_bytes {
+            let value_mem_offset = processed + i;
+            let value_byte_index = E::value_byte_index(total_bytes, value_mem_offset);
+            let value_byte = ((value_bits >> (value_byte_index * 8)) & 0xFF) as u8;
+
+            let lane = E::lane_byte_index(word_bytes, lane_offset + i);
+            data_word.set_lane(lane, value_byte);
+            lanes = lanes.start.min(lane)..lanes.end.max(lane + 1);
+        }
+
+        write_word::<B, A, D, S>(bus, config, word_addr, data_word, lanes)?;
+
+        processed += chunk_bytes;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn read_value_lanes<B, A, D, S, E, V>(
+    bus: &mut B,
+    config: &BusAccessorConfig,
+    addr: usize,
+) -> Result<V, BusAccessorError<B::Error>>
+where
+    B: Bus<A, D, S>,
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+    E: Endianness,
+    V: BusValue,
+{
+    let word_bytes = D::BYTES;
+    let total_bytes = V::BYTES;
+    let mut processed = 0usize;
+    let mut value_bits = 0u128;
+
+    while processed < total_bytes {
+        let cur_addr = addr
+            .checked_add(processed)
+            .ok_or(BusAccessorError::AddressOverflow)?;
+        let word_addr = (cur_addr / word_bytes) * word_bytes;
+        let lane_offset = cur_addr - word_addr;
+        let chunk_bytes = core::cmp::min(word_bytes - lane_offset, total_bytes - processed);
+
+        let read_addr = config.bus_addr::<A, B::Error>(word_addr, word_bytes)?;
+        let word = bus.read(read_addr).map_err(BusAccessorError::Bus)?;
+
+        for i in 0..chunk_bytes {
+            let lane = E::lane_byte_index(word_bytes, lane_offset + i);
+            let value_mem_offset = processed + i;
+            let value_byte_index = E::value_byte_index(total_bytes, value_mem_offset);
+            value_bits |= (word.lane(lane) as u128) << (value_byte_index * 8);
+        }
+
+        processed += chunk_bytes;
+    }
+
+    Ok(V::from_u128(value_bits))
+}
+
+// partial-word writes need either a byte strobe wide enough or read-modify-write
+fn check_strb<D: BusWord, S: BusWord, BE>(
+    config: &BusAccessorConfig,
+) -> Result<(), BusAccessorError<BE>> {
+    if config.strb_mode == StrbMode::ByteLanes && S::BITS < D::BYTES {
+        return Err(BusAccessorError::StrbTooNarrow);
+    }
+    Ok(())
+}
+
+// strobe with the byte lanes in `lanes` enabled (as far as the strobe is wide)
+fn strb_for<S: BusWord>(lanes: Range<usize>) -> S {
+    let mut strb = S::zero();
+    for lane in lanes.start..lanes.end.min(S::BITS) {
+        strb.set_bit(lane, true);
+    }
+    strb
+}
+
+// one word write with the contiguous byte lanes `lanes` enabled
+fn write_word<B, A, D, S>(
+    bus: &mut B,
+    config: &BusAccessorConfig,
+    word_addr: usize,
+    data: D,
+    lanes: Range<usize>,
+) -> Result<(), BusAccessorError<B::Error>>
+where
+    B: Bus<A, D, S>,
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+{
+    let addr = config.bus_addr::<A, B::Error>(word_addr, D::BYTES)?;
+    if lanes.len() == D::BYTES || config.strb_mode == StrbMode::ByteLanes {
+        return bus
+            .write(addr, data, strb_for(lanes))
+            .map_err(BusAccessorError::Bus);
+    }
+
+    // read the whole word, merge the enabled bytes, write back with all lanes enabled
+    // (the caller holds the bus for the whole sequence)
+    if config.read_side_effects {
+        return Err(BusAccessorError::ReadSideEffects);
+    }
+    let mut merged = bus.read(addr).map_err(BusAccessorError::Bus)?;
+    for lane in lanes {
+        merged.set_lane(lane, data.lane(lane));
+    }
+    bus.write(addr, merged, strb_for(0..D::BYTES))
+        .map_err(BusAccessorError::Bus)
+}
+
+// ---------- //  Burst streaming (shared with SharedBusAccessor)
+
+// Bursts are staged directly in bus words on the stack, so one call issues bursts
+// of at most BURST_STAGE_WORDS words (128 bytes of stack for u32, 2 KiB for
+// 512-bit words); longer runs are split into several bursts.
+const BURST_STAGE_WORDS: usize = 32;
+
+// Streams `len` bytes to the bus starting at byte address `addr`; byte `pos` of the
+// stream is `byte_at(pos)`.
+fn write_stream<B, A, D, S, E>(
+    bus: &mut B,
+    config: &BusAccessorConfig,
+    addr: usize,
+    len: usize,
+    byte_at: impl Fn(usize) -> u8,
+) -> Result<(), BusAccessorError<B::Error>>
+where
+    B: Bus<A, D, S>,
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+    E: Endianness,
+{
+    check_strb::<D, S, B::Error>(config)?;
+
+    let word_bytes = D::BYTES;
+    let mut words = [D::zero(); BURST_STAGE_WORDS];
+    let strbs = [strb_for::<S>(0..word_bytes); BURST_STAGE_WORDS];
+    let mut pos = 0usize;
+
+    while pos < len {
+        let cur_addr = addr.checked_add(pos).ok_or(BusAccessorError::AddressOverflow)?;
+        let word_addr = (cur_addr / word_bytes) * word_bytes;
+        let lane_offset = cur_addr - word_addr;
+        let remain = len - pos;
+
+        // unaligned head / short tail: single strobed (or read-modify-write) write
+        if lane_offset != 0 || remain < word_bytes {
+            let chunk_bytes = core::cmp::min(word_bytes - lane_offset, remain);
+            let mut data_word = D::zero();
+            let mut lanes = word_bytes..0;
+            for i in 0..chunk_bytes {
+                let lane = E::lane_byte_index(word_bytes, lane_offset + i);
+                data_word.set_lane(lane, byte_at(pos + i));
+                lanes = lanes.start.min(lane)..lanes.end.max(lane + 1);
+            }
+            write_word::<B, A, D, S>(bus, config, word_addr, data_word, lanes)?;
+            pos += chunk_bytes;
+            continue;
+        }
+
+        let burst_len = config
+            .burst_words(cur_addr, remain / word_bytes, word_bytes)
+            .min(BURST_STAGE_WORDS);
+        for (j, word) in words[..burst_len].iter_mut().enumerate() {
+            for i in 0..word_bytes {
+                let lane = E::lane_byte_index(word_bytes, i);
+                word.set_lane(lane, byte_at(pos + j * word_bytes + i));
+            }
+        }
+        let last_addr = cur_addr
+            .checked_add((burst_len - 1) * word_bytes)
+            .ok_or(BusAccessorError::AddressOverflow)?;
+        config.bus_addr::<A, B::Error>(last_addr, word_bytes)?;
+        let write_addr = config.bus_addr::<A, B::Error>(cur_addr, word_bytes)?;
+        bus.write_burst(write_addr, &words[..burst_len], &strbs[..burst_len])
+            .map_err(BusAccessorError::Bus)?;
+        pos += burst_len * word_bytes;
+    }
+
+    Ok(())
+}
+
+// Streams `len` bytes from the bus starting at byte address `addr`; `put` receives
+// every byte of the stream in order.
+fn read_stream<B, A, D, S, E>(
+    bus: &mut B,
+    config: &BusAccessorConfig,
+    addr: usize,
+    len: usize,
+    mut put: impl FnMut(u8),
+) -> Result<(), BusAccessorError<B::Error>>
+where
+    B: Bus<A, D, S>,
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+    E: Endianness,
+{
+    let word_bytes = D::BYTES;
+    let mut words = [D::zero(); BURST_STAGE_WORDS];
+    let mut pos = 0usize;
+
+    while pos < len {
+        let cur_addr = addr.checked_add(pos).ok_or(BusAccessorError::AddressOverflow)?;
+        let word_addr = (cur_addr / word_bytes) * word_bytes;
+        let lane_offset = cur_addr - word_addr;
+        let remain = len - pos;
+
+        if lane_offset != 0 || remain < word_bytes {
+            let chunk_bytes = core::cmp::min(word_bytes - lane_offset, remain);
+            let read_addr = config.bus_addr::<A, B::Error>(word_addr, word_bytes)?;
+            let word = bus.read(read_addr).map_err(BusAccessorError::Bus)?;
+            for i in 0..chunk_bytes {
+                put(word.lane(E::lane_byte_index(word_bytes, lane_offset + i)));
+            }
+            pos += chunk_bytes;
+            continue;
+        }
+
+        let burst_len = config
+            .burst_words(cur_addr, remain / word_bytes, word_bytes)
+            .min(BURST_STAGE_WORDS);
+        let last_addr = cur_addr
+            .checked_add((burst_len - 1) * word_bytes)
+            .ok_or(BusAccessorError::AddressOverflow)?;
+        config.bus_addr::<A, B::Error>(last_addr, word_bytes)?;
+        let read_addr = config.bus_addr::<A, B::Error>(cur_addr, word_bytes)?;
+        bus.read_burst(read_addr, &mut words[..burst_len])
+            .map_err(BusAccessorError::Bus)?;
+        for word in &words[..burst_len] {
+            for i in 0..word_bytes {
+                put(word.lane(E::lane_byte_index(word_bytes, i)));
+            }
+        }
+        pos += burst_len * word_bytes;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn write_slice_burst<B, A, D, S, E, V>(
+    bus: &mut B,
+    config: &BusAccessorConfig,
+    addr: usize,
+    src: &[V],
+) -> Result<(), BusAccessorError<B::Error>>
+where
+    B: Bus<A, D, S>,
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+    E: Endianness,
+    V: BusValue,
+{
+    write_stream::<B, A, D, S, E>(bus, config, addr, src.len() * V::BYTES, |pos| {
+        let index = E::value_byte_index(V::BYTES, pos % V::BYTES);
+        (src[pos / V::BYTES].to_u128() >> (index * 8)) as u8
+    })
+}
+
+pub(crate) fn read_slice_burst<B, A, D, S, E, V>(
+    bus: &mut B,
+    config: &BusAccessorConfig,
+    addr: usize,
+    dst: &mut [V],
+) -> Result<(), BusAccessorError<B::Error>>
+where
+    B: Bus<A, D, S>,
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+    E: Endianness,
+    V: BusValue,
+{
+    // bytes arrive in order, so each value is assembled and stored once complete
+    let len = dst.len() * V::BYTES;
+    let mut values = dst.iter_mut();
+    let mut bits = 0u128;
+    let mut i = 0usize;
+    read_stream::<B, A, D, S, E>(bus, config, addr, len, |byte| {
+        bits |= (byte as u128) << (E::value_byte_index(V::BYTES, i) * 8);
+        i += 1;
+        if i == V::BYTES {
+            if let Some(value) = values.next() {
+                *value = V::from_u128(bits);
+            }
+            bits = 0;
+            i = 0;
+        }
+    })
 }
 
 macro_rules! impl_bus_address {
@@ -290,12 +812,17 @@ macro_rules! impl_bus_word {
             const BYTES: usize = core::mem::size_of::<$t>();
             const BITS: usize = core::mem::size_of::<$t>() * 8;
 
-            fn to_u128(self) -> u128 {
-                self as u128
+            fn zero() -> Self {
+                0
             }
 
-            fn from_u128(value: u128) -> Self {
-                value as $t
+            fn lane(&self, index: usize) -> u8 {
+                (*self >> (index * 8)) as u8
+            }
+
+            fn set_lane(&mut self, index: usize, value: u8) {
+                let shift = index * 8;
+                *self = (*self & !((0xff as $t) << shift)) | ((value as $t) << shift);
             }
         }
     };
@@ -308,6 +835,38 @@ impl_bus_word!(u64);
 impl_bus_word!(u128);
 impl_bus_word!(usize);
 
+// wide buses (256/512 bit AXI ...); element i is byte lane i, or strobe bits [8i+7:8i]
+impl<const N: usize> BusWord for [u8; N] {
+    const BYTES: usize = N;
+    const BITS: usize = N * 8;
+
+    fn zero() -> Self {
+        [0; N]
+    }
+
+    fn lane(&self, index: usize) -> u8 {
+        self[index]
+    }
+
+    fn set_lane(&mut self, index: usize, value: u8) {
+        self[index] = value;
+    }
+}
+
+// no strobe signal (APB3, simple register buses); use StrbMode::ReadModifyWrite
+impl BusWord for () {
+    const BYTES: usize = 0;
+    const BITS: usize = 0;
+
+    fn zero() -> Self {}
+
+    fn lane(&self, _index: usize) -> u8 {
+        0
+    }
+
+    fn set_lane(&mut self, _index: usize, _value: u8) {}
+}
+
 macro_rules! impl_bus_value_unsigned {
     ($t:ty) => {
         impl BusValue for $t {
@@ -384,6 +943,8 @@ fn map_bus_err<E>(err: BusAccessorError<E>) -> MemAccessTryError {
         BusAccessorError::AddressOutOfRange => MemAccessTryError::AddressOutOfRange,
         BusAccessorError::OutOfBounds => MemAccessTryError::OutOfBounds,
         BusAccessorError::StrbTooNarrow => MemAccessTryError::StrbTooNarrow,
+        BusAccessorError::ReadSideEffects => MemAccessTryError::ReadSideEffects,
+        BusAccessorError::Busy => MemAccessTryError::Busy,
         BusAccessorError::Bus(_) => MemAccessTryError::AccessFault,
     }
 }
@@ -401,149 +962,77 @@ where
     fn phys_addr(&self) -> usize { 0 }
 
     unsafe fn copy_to_usize(&self, src_adr: usize, dst_ptr: *mut usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { self.read_mem_usize(src_adr + i * core::mem::size_of::<usize>()) };
-            unsafe { core::ptr::write(dst_ptr.add(i), v) };
-        }
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
     }
     unsafe fn copy_to_u8(&self, src_adr: usize, dst_ptr: *mut u8, count: usize) {
-        for i in 0..count {
-            let v = unsafe { self.read_mem_u8(src_adr + i) };
-            unsafe { core::ptr::write(dst_ptr.add(i), v) };
-        }
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
     }
     unsafe fn copy_to_u16(&self, src_adr: usize, dst_ptr: *mut u16, count: usize) {
-        for i in 0..count {
-            let v = unsafe { self.read_mem_u16(src_adr + i * core::mem::size_of::<u16>()) };
-            unsafe { core::ptr::write(dst_ptr.add(i), v) };
-        }
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
     }
     unsafe fn copy_to_u32(&self, src_adr: usize, dst_ptr: *mut u32, count: usize) {
-        for i in 0..count {
-            let v = unsafe { self.read_mem_u32(src_adr + i * core::mem::size_of::<u32>()) };
-            unsafe { core::ptr::write(dst_ptr.add(i), v) };
-        }
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
     }
     unsafe fn copy_to_u64(&self, src_adr: usize, dst_ptr: *mut u64, count: usize) {
-        for i in 0..count {
-            let v = unsafe { self.read_mem_u64(src_adr + i * core::mem::size_of::<u64>()) };
-            unsafe { core::ptr::write(dst_ptr.add(i), v) };
-        }
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
     }
     unsafe fn copy_to_isize(&self, src_adr: usize, dst_ptr: *mut isize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { self.read_mem_isize(src_adr + i * core::mem::size_of::<isize>()) };
-            unsafe { core::ptr::write(dst_ptr.add(i), v) };
-        }
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
     }
     unsafe fn copy_to_i8(&self, src_adr: usize, dst_ptr: *mut i8, count: usize) {
-        for i in 0..count {
-            let v = unsafe { self.read_mem_i8(src_adr + i) };
-            unsafe { core::ptr::write(dst_ptr.add(i), v) };
-        }
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
     }
     unsafe fn copy_to_i16(&self, src_adr: usize, dst_ptr: *mut i16, count: usize) {
-        for i in 0..count {
-            let v = unsafe { self.read_mem_i16(src_adr + i * core::mem::size_of::<i16>()) };
-            unsafe { core::ptr::write(dst_ptr.add(i), v) };
-        }
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
     }
     unsafe fn copy_to_i32(&self, src_adr: usize, dst_ptr: *mut i32, count: usize) {
-        for i in 0..count {
-            let v = unsafe { self.read_mem_i32(src_adr + i * core::mem::size_of::<i32>()) };
-            unsafe { core::ptr::write(dst_ptr.add(i), v) };
-        }
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
     }
     unsafe fn copy_to_i64(&self, src_adr: usize, dst_ptr: *mut i64, count: usize) {
-        for i in 0..count {
-            let v = unsafe { self.read_mem_i64(src_adr + i * core::mem::size_of::<i64>()) };
-            unsafe { core::ptr::write(dst_ptr.add(i), v) };
-        }
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
     }
     unsafe fn copy_to_f32(&self, src_adr: usize, dst_ptr: *mut f32, count: usize) {
-        for i in 0..count {
-            let v = unsafe { self.read_mem_f32(src_adr + i * core::mem::size_of::<f32>()) };
-            unsafe { core::ptr::write(dst_ptr.add(i), v) };
-        }
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
     }
     unsafe fn copy_to_f64(&self, src_adr: usize, dst_ptr: *mut f64, count: usize) {
-        for i in 0..count {
-            let v = unsafe { self.read_mem_f64(src_adr + i * core::mem::size_of::<f64>()) };
-            unsafe { core::ptr::write(dst_ptr.add(i), v) };
-        }
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
     }
 
     unsafe fn copy_from_usize(&self, src_ptr: *const usize, dst_adr: usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { core::ptr::read(src_ptr.add(i)) };
-            unsafe { self.write_mem_usize(dst_adr + i * core::mem::size_of::<usize>(), v) };
-        }
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
     }
     unsafe fn copy_from_u8(&self, src_ptr: *const u8, dst_adr: usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { core::ptr::read(src_ptr.add(i)) };
-            unsafe { self.write_mem_u8(dst_adr + i, v) };
-        }
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
     }
     unsafe fn copy_from_u16(&self, src_ptr: *const u16, dst_adr: usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { core::ptr::read(src_ptr.add(i)) };
-            unsafe { self.write_mem_u16(dst_adr + i * core::mem::size_of::<u16>(), v) };
-        }
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
     }
     unsafe fn copy_from_u32(&self, src_ptr: *const u32, dst_adr: usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { core::ptr::read(src_ptr.add(i)) };
-            unsafe { self.write_mem_u32(dst_adr + i * core::mem::size_of::<u32>(), v) };
-        }
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
     }
     unsafe fn copy_from_u64(&self, src_ptr: *const u64, dst_adr: usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { core::ptr::read(src_ptr.add(i)) };
-            unsafe { self.write_mem_u64(dst_adr + i * core::mem::size_of::<u64>(), v) };
-        }
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
     }
     unsafe fn copy_from_isize(&self, src_ptr: *const isize, dst_adr: usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { core::ptr::read(src_ptr.add(i)) };
-            unsafe { self.write_mem_isize(dst_adr + i * core::mem::size_of::<isize>(), v) };
-        }
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
     }
     unsafe fn copy_from_i8(&self, src_ptr: *const i8, dst_adr: usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { core::ptr::read(src_ptr.add(i)) };
-            unsafe { self.write_mem_i8(dst_adr + i, v) };
-        }
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
     }
     unsafe fn copy_from_i16(&self, src_ptr: *const i16, dst_adr: usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { core::ptr::read(src_ptr.add(i)) };
-            unsafe { self.write_mem_i16(dst_adr + i * core::mem::size_of::<i16>(), v) };
-        }
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
     }
     unsafe fn copy_from_i32(&self, src_ptr: *const i32, dst_adr: usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { core::ptr::read(src_ptr.add(i)) };
-            unsafe { self.write_mem_i32(dst_adr + i * core::mem::size_of::<i32>(), v) };
-        }
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
     }
     unsafe fn copy_from_i64(&self, src_ptr: *const i64, dst_adr: usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { core::ptr::read(src_ptr.add(i)) };
-            unsafe { self.write_mem_i64(dst_adr + i * core::mem::size_of::<i64>(), v) };
-        }
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
     }
     unsafe fn copy_from_f32(&self, src_ptr: *const f32, dst_adr: usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { core::ptr::read(src_ptr.add(i)) };
-            unsafe { self.write_mem_f32(dst_adr + i * core::mem::size_of::<f32>(), v) };
-        }
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
     }
     unsafe fn copy_from_f64(&self, src_ptr: *const f64, dst_adr: usize, count: usize) {
-        for i in 0..count {
-            let v = unsafe { core::ptr::read(src_ptr.add(i)) };
-            unsafe { self.write_mem_f64(dst_adr + i * core::mem::size_of::<f64>(), v) };
-        }
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
     }
 
     unsafe fn write_mem(&self, offset: usize, data: usize) { unsafe { self.try_write_mem(offset, data) }.unwrap(); }
@@ -603,135 +1092,109 @@ where
     unsafe fn read_reg_f64(&self, reg: usize) -> f64 { unsafe { self.read_mem_f64(reg * D::BYTES) } }
 
     unsafe fn try_write_mem(&self, offset: usize, data: usize) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
     unsafe fn try_write_mem_usize(&self, offset: usize, data: usize) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
     unsafe fn try_write_mem_u8(&self, offset: usize, data: u8) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
     unsafe fn try_write_mem_u16(&self, offset: usize, data: u16) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
     unsafe fn try_write_mem_u32(&self, offset: usize, data: u32) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
     unsafe fn try_write_mem_u64(&self, offset: usize, data: u64) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
     unsafe fn try_write_mem_isize(&self, offset: usize, data: isize) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
     unsafe fn try_write_mem_i8(&self, offset: usize, data: i8) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
     unsafe fn try_write_mem_i16(&self, offset: usize, data: i16) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
     unsafe fn try_write_mem_i32(&self, offset: usize, data: i32) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
     unsafe fn try_write_mem_i64(&self, offset: usize, data: i64) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
     unsafe fn try_write_mem_f32(&self, offset: usize, data: f32) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
     unsafe fn try_write_mem_f64(&self, offset: usize, data: f64) -> Result<(), MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).write_value(addr, data) }.map_err(map_bus_err)
+        self.write_value(addr, data).map_err(map_bus_err)
     }
 
     unsafe fn try_read_mem(&self, offset: usize) -> Result<usize, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
     unsafe fn try_read_mem_usize(&self, offset: usize) -> Result<usize, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
     unsafe fn try_read_mem_u8(&self, offset: usize) -> Result<u8, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
     unsafe fn try_read_mem_u16(&self, offset: usize) -> Result<u16, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
     unsafe fn try_read_mem_u32(&self, offset: usize) -> Result<u32, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
     unsafe fn try_read_mem_u64(&self, offset: usize) -> Result<u64, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
     unsafe fn try_read_mem_isize(&self, offset: usize) -> Result<isize, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
     unsafe fn try_read_mem_i8(&self, offset: usize) -> Result<i8, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
     unsafe fn try_read_mem_i16(&self, offset: usize) -> Result<i16, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
     unsafe fn try_read_mem_i32(&self, offset: usize) -> Result<i32, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
     unsafe fn try_read_mem_i64(&self, offset: usize) -> Result<i64, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
     unsafe fn try_read_mem_f32(&self, offset: usize) -> Result<f32, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
     unsafe fn try_read_mem_f64(&self, offset: usize) -> Result<f64, MemAccessTryError> {
-        let this = self as *const Self as *mut Self;
         let addr = A::try_from_usize(offset).ok_or(MemAccessTryError::AddressOutOfRange)?;
-        unsafe { (*this).read_value(addr) }.map_err(map_bus_err)
+        self.read_value(addr).map_err(map_bus_err)
     }
 
     unsafe fn try_write_reg(&self, reg: usize, data: usize) -> Result<(), MemAccessTryError> {
@@ -891,7 +1354,7 @@ mod tests {
     #[test]
     fn little_endian_subword_access() {
         let bus = MockBus::new();
-        let mut accessor = BusAccessor::<_, usize, u32, u8, LittleEndian>::new(bus);
+        let accessor = BusAccessor::<_, usize, u32, u8, LittleEndian>::new(bus);
 
         accessor.write_u16(1, 0xABCD).unwrap();
         let bus = accessor.into_inner();
@@ -906,7 +1369,7 @@ mod tests {
     #[test]
     fn big_endian_subword_access() {
         let bus = MockBus::new();
-        let mut accessor = BusAccessor::<_, usize, u32, u8, BigEndian>::new(bus);
+        let accessor = BusAccessor::<_, usize, u32, u8, BigEndian>::new(bus);
 
         accessor.write_u16(0, 0xABCD).unwrap();
         let bus = accessor.into_inner();
@@ -919,7 +1382,7 @@ mod tests {
     #[test]
     fn little_endian_larger_than_bus_word() {
         let bus = MockBus::new();
-        let mut accessor = BusAccessor::<_, usize, u32, u8, LittleEndian>::new(bus);
+        let accessor = BusAccessor::<_, usize, u32, u8, LittleEndian>::new(bus);
 
         accessor.write_u64(2, 0x0123_4567_89AB_CDEF).unwrap();
         let value = accessor.read_u64(2).unwrap();
@@ -929,10 +1392,341 @@ mod tests {
     #[test]
     fn big_endian_larger_than_bus_word() {
         let bus = MockBus::new();
-        let mut accessor = BusAccessor::<_, usize, u32, u8, BigEndian>::new(bus);
+        let accessor = BusAccessor::<_, usize, u32, u8, BigEndian>::new(bus);
 
         accessor.write_u64(2, 0x0123_4567_89AB_CDEF).unwrap();
         let value = accessor.read_u64(2).unwrap();
         assert_eq!(value, 0x0123_4567_89AB_CDEF);
     }
+
+    #[test]
+    fn access_while_bus_borrowed_is_busy() {
+        let accessor = BusAccessor::<_, usize, u32, u8, LittleEndian>::new(MockBus::new());
+
+        let bus = accessor.bus();
+        assert_eq!(accessor.write_u32(0, 1), Err(BusAccessorError::Busy));
+        assert_eq!(
+            unsafe { accessor.try_read_mem_u32(0) },
+            Err(MemAccessTryError::Busy)
+        );
+        drop(bus);
+
+        accessor.write_u32(0, 1).unwrap();
+        assert_eq!(accessor.read_u32(0).unwrap(), 1);
+    }
+
+    // register bus without byte strobes
+    #[derive(Debug, Default)]
+    struct ApbBus {
+        regs: [u32; 8],
+        reads: usize,
+    }
+
+    impl Bus<usize, u32, ()> for ApbBus {
+        type Error = ();
+
+        fn write(&mut self, addr: usize, data: u32, _strb: ()) -> Result<(), Self::Error> {
+            self.regs[addr / 4] = data;
+            Ok(())
+        }
+
+        fn read(&mut self, addr: usize) -> Result<u32, Self::Error> {
+            self.reads += 1;
+            Ok(self.regs[addr / 4])
+        }
+    }
+
+    #[test]
+    fn read_modify_write_without_strobe() {
+        let bus = ApbBus {
+            regs: [0x1111_1111; 8],
+            reads: 0,
+        };
+        let mut accessor = BusAccessor::<_, usize, u32, (), LittleEndian>::new(bus);
+        assert_eq!(
+            accessor.write_u8(1, 0xAB),
+            Err(BusAccessorError::StrbTooNarrow)
+        );
+
+        accessor.set_config(BusAccessorConfig::new().with_strb_mode(StrbMode::ReadModifyWrite));
+        accessor.write_u8(1, 0xAB).unwrap();
+        accessor.write_u16(7, 0xCDEF).unwrap();
+        accessor.write_u32(12, 0x1234_5678).unwrap();
+        assert_eq!(accessor.bus().reads, 3);
+        assert_eq!(
+            accessor.bus().regs[..4],
+            [0x1111_AB11, 0xEF11_1111, 0x1111_11CD, 0x1234_5678]
+        );
+
+        let src = [0x5Au8; 6];
+        unsafe { accessor.copy_from_u8(src.as_ptr(), 17, src.len()) };
+        assert_eq!(accessor.bus().regs[4..6], [0x5A5A_5A11, 0x115A_5A5A]);
+        assert_eq!(accessor.read_u32(4).unwrap(), 0xEF11_1111);
+    }
+
+    #[test]
+    fn read_modify_write_refused_on_read_side_effects() {
+        let config = BusAccessorConfig::new()
+            .with_strb_mode(StrbMode::ReadModifyWrite)
+            .with_read_side_effects(true);
+        let accessor =
+            BusAccessor::<_, usize, u32, (), LittleEndian>::with_config(ApbBus::default(), config);
+
+        assert_eq!(
+            accessor.write_u16(2, 0xBEEF),
+            Err(BusAccessorError::ReadSideEffects)
+        );
+        assert_eq!(
+            unsafe { accessor.try_write_mem_u8(0, 1) },
+            Err(MemAccessTryError::ReadSideEffects)
+        );
+        accessor.write_u32(4, 0xCAFE_F00D).unwrap();
+        assert_eq!(accessor.bus().reads, 0);
+        assert_eq!(accessor.bus().regs[1], 0xCAFE_F00D);
+    }
+
+    // 256-bit (u32 strobe) and 512-bit ([u8; 8] strobe) data bus over the same memory
+    struct WideBus {
+        mem: [u8; 256],
+        writes: usize,
+    }
+
+    impl<const N: usize, S: BusWord> Bus<usize, [u8; N], S> for WideBus {
+        type Error = ();
+
+        fn write(&mut self, addr: usize, data: [u8; N], strb: S) -> Result<(), Self::Error> {
+            assert_eq!(addr % N, 0);
+            self.writes += 1;
+            for (lane, byte) in data.iter().enumerate() {
+                if strb.bit(lane) {
+                    self.mem[addr + lane] = *byte;
+                }
+            }
+            Ok(())
+        }
+
+        fn read(&mut self, addr: usize) -> Result<[u8; N], Self::Error> {
+            Ok(self.mem[addr..addr + N].try_into().unwrap())
+        }
+    }
+
+    #[test]
+    fn wide_bus_words() {
+        let mut strb = [0u8; 8];
+        strb.set_bit(9, true);
+        assert_eq!(strb, [0, 2, 0, 0, 0, 0, 0, 0]);
+        let mut word = 0u32;
+        word.set_lane(2, 0xAB);
+        assert_eq!((word, word.lane(2), word.bit(17)), (0x00AB_0000, 0xAB, true));
+
+        let bus = WideBus {
+            mem: [0; 256],
+            writes: 0,
+        };
+        let accessor = BusAccessor::<_, usize, [u8; 32], u32, LittleEndian>::new(bus);
+        accessor.write_u64(28, 0x0123_4567_89AB_CDEF).unwrap();
+        assert_eq!(accessor.read_u64(28).unwrap(), 0x0123_4567_89AB_CDEF);
+        assert_eq!(
+            accessor.bus().mem[28..36],
+            0x0123_4567_89AB_CDEFu64.to_le_bytes()
+        );
+        assert_eq!(accessor.bus().writes, 2);
+
+        let bus = accessor.into_inner();
+        let accessor = BusAccessor::<_, usize, [u8; 64], [u8; 8], BigEndian>::new(bus);
+        let src: [u32; 40] = core::array::from_fn(|i| 0x1000_0000 + i as u32);
+        let mut dst = [0u32; 40];
+        unsafe {
+            accessor.copy_from_u32(src.as_ptr(), 4, src.len());
+            accessor.copy_to_u32(4, dst.as_mut_ptr(), dst.len());
+        }
+        assert_eq!(src, dst);
+        // byte 4 is lane 59 of the first 512-bit word, with the MSB of the first value
+        assert_eq!(accessor.bus().mem[59], 0x10);
+    }
+
+    // word-addressed bus: address N is 32-bit word N
+    #[derive(Debug, Default)]
+    struct WordBus {
+        regs: [u32; 16],
+    }
+
+    impl Bus<u8, u32, u8> for WordBus {
+        type Error = ();
+
+        fn write(&mut self, addr: u8, data: u32, strb: u8) -> Result<(), Self::Error> {
+            let reg = &mut self.regs[addr as usize];
+            for lane in 0..4 {
+                if (strb >> lane) & 1 == 1 {
+                    *reg = (*reg & !(0xff << (lane * 8))) | (data & (0xff << (lane * 8)));
+                }
+            }
+            Ok(())
+        }
+
+        fn read(&mut self, addr: u8) -> Result<u32, Self::Error> {
+            Ok(self.regs[addr as usize])
+        }
+    }
+
+    #[test]
+    fn word_addressed_bus() {
+        let config = BusAccessorConfig::new().with_address_unit(AddressUnit::Word);
+        let accessor =
+            BusAccessor::<_, u8, u32, u8, LittleEndian>::with_config(WordBus::default(), config);
+
+        accessor.write_u32(4, 0x1234_5678).unwrap();
+        accessor.write_u16(10, 0xABCD).unwrap();
+        assert_eq!(accessor.bus().regs[..3], [0, 0x1234_5678, 0xABCD_0000]);
+        assert_eq!(accessor.read_u16(6).unwrap(), 0x1234);
+
+        let src: [u8; 10] = core::array::from_fn(|i| i as u8);
+        let mut dst = [0u8; 10];
+        unsafe {
+            accessor.copy_from_u8(src.as_ptr(), 18, src.len());
+            accessor.copy_to_u8(18, dst.as_mut_ptr(), dst.len());
+        }
+        assert_eq!(src, dst);
+        assert_eq!(accessor.bus().regs[5], 0x0504_0302);
+    }
+
+    #[test]
+    fn burst_addr_checked() {
+        assert_eq!(burst_addr::<u8>(0xf0, 3, 4), Some(0xfc));
+        assert_eq!(burst_addr::<u8>(0xf0, 4, 4), None);
+        assert_eq!(burst_addr::<usize>(usize::MAX - 3, 1, 4), None);
+        assert_eq!(burst_addr::<usize>(0, usize::MAX, 4), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "pre-validate")]
+    fn default_burst_rejects_whole_burst_up_front() {
+        // the last beat (0x100) does not fit in u8; the check fires before
+        // the first beat would index past WordBus::regs
+        let mut bus = WordBus::default();
+        let _ = bus.write_burst(0xf8, &[1, 2, 3], &[0xf; 3]);
+    }
+
+    #[derive(Debug)]
+    struct BurstBus {
+        mem: [u8; 8192],
+        singles: usize,
+        bursts: usize,
+        max_len: usize,
+        last_burst_addr: usize,
+    }
+
+    impl BurstBus {
+        fn new() -> Self {
+            Self {
+                mem: [0; 8192],
+                singles: 0,
+                bursts: 0,
+                max_len: 0,
+                last_burst_addr: 0,
+            }
+        }
+
+        fn burst(&mut self, addr: usize, len: usize) {
+            self.bursts += 1;
+            self.max_len = core::cmp::max(self.max_len, len);
+            self.last_burst_addr = addr;
+            // never cross a 4 KiB boundary
+            assert_eq!(addr / 4096, (addr + len * 4 - 1) / 4096);
+        }
+    }
+
+    impl Bus<usize, u32, u8> for BurstBus {
+        type Error = ();
+
+        fn write(&mut self, addr: usize, data: u32, strb: u8) -> Result<(), Self::Error> {
+            self.singles += 1;
+            for lane in 0..4 {
+                if ((strb >> lane) & 1) == 1 {
+                    self.mem[addr + lane] = ((data >> (lane * 8)) & 0xFF) as u8;
+                }
+            }
+            Ok(())
+        }
+
+        fn read(&mut self, addr: usize) -> Result<u32, Self::Error> {
+            self.singles += 1;
+            let mut data = 0u32;
+            for lane in 0..4 {
+                data |= (self.mem[addr + lane] as u32) << (lane * 8);
+            }
+            Ok(data)
+        }
+
+        fn write_burst(&mut self, addr: usize, data: &[u32], _strb: &[u8]) -> Result<(), ()> {
+            self.burst(addr, data.len());
+            for (i, word) in data.iter().enumerate() {
+                self.mem[addr + i * 4..addr + i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+            }
+            Ok(())
+        }
+
+        fn read_burst(&mut self, addr: usize, data: &mut [u32]) -> Result<(), ()> {
+            self.burst(addr, data.len());
+            for (i, word) in data.iter_mut().enumerate() {
+                *word = u32::from_le_bytes(self.mem[addr + i * 4..addr + i * 4 + 4].try_into().unwrap());
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn bulk_copy_uses_bursts() {
+        let config = BusAccessorConfig::new().with_max_burst_len(16);
+        let accessor = BusAccessor::<_, usize, u32, u8, LittleEndian>::with_config(BurstBus::new(), config);
+
+        let mut src = [0u8; 301];
+        for (i, b) in src.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let mut dst = [0u8; 301];
+        unsafe {
+            accessor.copy_from_u8(src.as_ptr(), 4090, src.len());
+            accessor.copy_to_u8(4090, dst.as_mut_ptr(), dst.len());
+        }
+        assert_eq!(src, dst);
+
+        let bus = accessor.into_inner();
+        assert_eq!(bus.max_len, 16);
+        // head (2 bytes), 4 KiB split, tail (3 bytes) are the only single accesses
+        assert_eq!(bus.singles, 4);
+        assert_eq!(bus.mem[4090], 0);
+        assert_eq!(bus.mem[4096], 6);
+    }
+
+    #[test]
+    fn long_copy_is_staged_in_words() {
+        let accessor = BusAccessor::<_, usize, u32, u8, LittleEndian>::new(BurstBus::new());
+
+        let src: [u8; 6000] = core::array::from_fn(|i| (i * 7) as u8);
+        let mut dst = [0u8; 6000];
+        unsafe {
+            accessor.copy_from_u8(src.as_ptr(), 2, src.len());
+            accessor.copy_to_u8(2, dst.as_mut_ptr(), dst.len());
+        }
+        assert_eq!(src, dst);
+
+        let bus = accessor.into_inner();
+        assert_eq!(bus.max_len, BURST_STAGE_WORDS);
+        // only the unaligned head and the short tail in each direction
+        assert_eq!(bus.singles, 4);
+    }
+
+    #[test]
+    fn bulk_copy_big_endian_values() {
+        let accessor = BusAccessor::<_, usize, u32, u8, BigEndian>::new(BurstBus::new());
+
+        let src = [0x0102_0304u32, 0x0506_0708, 0x090A_0B0C];
+        let mut dst = [0u32; 3];
+        accessor.write_slice(8, &src).unwrap();
+        accessor.read_slice(8, &mut dst).unwrap();
+        assert_eq!(src, dst);
+        assert_eq!(accessor.read_u32(12).unwrap(), 0x0506_0708);
+        assert_eq!(accessor.bus().mem[12], 0x08);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,107 @@
+#![allow(dead_code)]
+
+use core::cell::RefCell;
+#[cfg(target_has_atomic = "8")]
+use core::cell::UnsafeCell;
+#[cfg(target_has_atomic = "8")]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// `LockedBusAccessor` が共有バスを排他するためのロック。
+///
+/// - `with_lock` はロック中に `f` を 1 回だけ呼ぶ
+/// - ロックを取れない場合（単一コア `RefCell` の再入など）は `None` を返す
+/// - critical-section 等の他の方式は、このトレイトを実装すれば差し替えられる
+pub trait BusLock<T> {
+    fn new(value: T) -> Self;
+    fn with_lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R>;
+    fn into_inner(self) -> T;
+}
+
+/// 単一コア・割り込みなし前提のロック。再入時は `None`。
+impl<T> BusLock<T> for RefCell<T> {
+    fn new(value: T) -> Self {
+        RefCell::new(value)
+    }
+
+    fn with_lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let mut value = self.try_borrow_mut().ok()?;
+        Some(f(&mut value))
+    }
+
+    fn into_inner(self) -> T {
+        RefCell::into_inner(self)
+    }
+}
+
+/// マルチコア向けのスピンロック。
+///
+/// 再入はデッドロックになるので、ロック中に同じバスへアクセスしないこと。
+#[cfg(target_has_atomic = "8")]
+#[derive(Debug, Default)]
+pub struct SpinLock<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+// ロックで排他するので T: Send なら共有できる
+#[cfg(target_has_atomic = "8")]
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+#[cfg(target_has_atomic = "8")]
+impl<T> SpinLock<T> {
+    pub const fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+}
+
+#[cfg(target_has_atomic = "8")]
+impl<T> BusLock<T> for SpinLock<T> {
+    fn new(value: T) -> Self {
+        SpinLock::new(value)
+    }
+
+    fn with_lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+
+        // パニック時もロックを解放する
+        struct Unlock<'a>(&'a AtomicBool);
+        impl Drop for Unlock<'_> {
+            fn drop(&mut self) {
+                self.0.store(false, Ordering::Release);
+            }
+        }
+        let _unlock = Unlock(&self.locked);
+
+        Some(f(unsafe { &mut *self.value.get() }))
+    }
+
+    fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+/// std 環境向け。poison は無視して中身を使う（`SharedBusAccessor::into_inner` と同じ扱い）。
+#[cfg(feature = "std")]
+impl<T> BusLock<T> for std::sync::Mutex<T> {
+    fn new(value: T) -> Self {
+        std::sync::Mutex::new(value)
+    }
+
+    fn with_lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let mut value = self.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        Some(f(&mut value))
+    }
+
+    fn into_inner(self) -> T {
+        std::sync::Mutex::into_inner(self).unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
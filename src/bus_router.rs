@@ -0,0 +1,311 @@
+#![allow(dead_code)]
+
+use core::fmt;
+use std::boxed::Box;
+use std::vec::Vec;
+
+use super::bus_accessor::{burst_addr, Bus, BusAddress, BusWord};
+
+/// `BusRouter` のエラー。
+///
+/// - `DecodeError`: どのターゲットにもマップされていないアドレス（AXI の DECERR 相当）
+/// - `Overlap`/`ZeroSize`/`AddressOverflow`: `add` 時の範囲チェック違反
+/// - `AddressOutOfRange`: ターゲット相対アドレスが `A` で表現できない
+/// - `Target`: 子バスが返したエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BusRouterError<E> {
+    DecodeError(usize),
+    Overlap { base: usize, size: usize },
+    ZeroSize,
+    AddressOverflow,
+    AddressOutOfRange,
+    Target(E),
+}
+
+impl<E: fmt::Display> fmt::Display for BusRouterError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DecodeError(addr) => write!(f, "no target mapped at address 0x{addr:x}"),
+            Self::Overlap { base, size } => {
+                write!(
+                    f,
+                    "range 0x{base:x} (size 0x{size:x}) overlaps an existing target"
+                )
+            }
+            Self::ZeroSize => write!(f, "target size must not be zero"),
+            Self::AddressOverflow => write!(f, "address arithmetic overflow"),
+            Self::AddressOutOfRange => write!(f, "address is out of representable range"),
+            Self::Target(err) => write!(f, "target access failed: {err}"),
+        }
+    }
+}
+
+impl<E> std::error::Error for BusRouterError<E> where E: std::error::Error + 'static {}
+
+/// ルーティング先となる子バス（エラー型は全ターゲットで共通）。
+pub type BoxedBus<A, D, S, E> = Box<dyn Bus<A, D, S, Error = E> + Send>;
+
+struct BusRoute<A, D, S, E> {
+    base: usize,
+    size: usize,
+    bus: BoxedBus<A, D, S, E>,
+}
+
+/// アドレスデコードを行うインターコネクト。
+///
+/// - `add` で `[base, base + size)` に子バスをマップする（重なりはエラー）
+/// - アクセスはターゲット相対アドレス（`addr - base`）に変換して子バスへ転送
+/// - どこにもマップされていないアドレスは `BusRouterError::DecodeError`
+pub struct BusRouter<A, D, S, E> {
+    routes: Vec<BusRoute<A, D, S, E>>,
+}
+
+impl<A, D, S, E> fmt::Debug for BusRouter<A, D, S, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.routes.iter().map(|r| (r.base, r.size)))
+            .finish()
+    }
+}
+
+impl<A, D, S, E> Default for BusRouter<A, D, S, E>
+where
+    A: BusAddress,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A, D, S, E> BusRouter<A, D, S, E>
+where
+    A: BusAddress,
+{
+    /// 空のルータを作る。
+    pub fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+
+    /// `bus` を `[base, base + size)` にマップする。既存の範囲と重なる場合は失敗する。
+    pub fn add<B>(&mut self, base: usize, size: usize, bus: B) -> Result<(), BusRouterError<E>>
+    where
+        B: Bus<A, D, S, Error = E> + Send + 'static,
+    {
+        self.add_boxed(base, size, Box::new(bus))
+    }
+
+    /// `add` のビルダー版。
+    pub fn with<B>(mut self, base: usize, size: usize, bus: B) -> Result<Self, BusRouterError<E>>
+    where
+        B: Bus<A, D, S, Error = E> + Send + 'static,
+    {
+        self.add(base, size, bus)?;
+        Ok(self)
+    }
+
+    /// Box 済みの子バスをマップする。
+    pub fn add_boxed(
+        &mut self,
+        base: usize,
+        size: usize,
+        bus: BoxedBus<A, D, S, E>,
+    ) -> Result<(), BusRouterError<E>> {
+        if size == 0 {
+            return Err(BusRouterError::ZeroSize);
+        }
+        let end = base
+            .checked_add(size - 1)
+            .ok_or(BusRouterError::AddressOverflow)?;
+
+        // base 昇順を保つ
+        let pos = self.routes.partition_point(|r| r.base < base);
+        if pos > 0 {
+            let prev = &self.routes[pos - 1];
+            if prev.base + (prev.size - 1) >= base {
+                return Err(BusRouterError::Overlap { base, size });
+            }
+        }
+        if let Some(next) = self.routes.get(pos) {
+            if next.base <= end {
+                return Err(BusRouterError::Overlap { base, size });
+            }
+        }
+
+        self.routes.insert(pos, BusRoute { base, size, bus });
+        Ok(())
+    }
+
+    /// マップ済みの範囲 `(base, size)` を base 昇順で返す。
+    pub fn ranges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.routes.iter().map(|r| (r.base, r.size))
+    }
+
+    /// `addr` をデコードしてルート番号とターゲット相対アドレスを返す。
+    fn decode(&self, addr: A) -> Result<(usize, A), BusRouterError<E>> {
+        let addr = addr.to_usize();
+        let pos = self.routes.partition_point(|r| r.base <= addr);
+        if pos == 0 {
+            return Err(BusRouterError::DecodeError(addr));
+        }
+        let route = &self.routes[pos - 1];
+        let offset = addr - route.base;
+        if offset >= route.size {
+            return Err(BusRouterError::DecodeError(addr));
+        }
+        let local = A::try_from_usize(offset).ok_or(BusRouterError::AddressOutOfRange)?;
+        Ok((pos - 1, local))
+    }
+}
+
+impl<A, D, S, E> Bus<A, D, S> for BusRouter<A, D, S, E>
+where
+    A: BusAddress,
+{
+    type Error = BusRouterError<E>;
+
+    fn write(&mut self, addr: A, data: D, strb: S) -> Result<(), Self::Error> {
+        let (index, local) = self.decode(addr)?;
+        self.routes[index]
+            .bus
+            .write(local, data, strb)
+            .map_err(BusRouterError::Target)
+    }
+
+    fn read(&mut self, addr: A) -> Result<D, Self::Error> {
+        let (index, local) = self.decode(addr)?;
+        self.routes[index]
+            .bus
+            .read(local)
+            .map_err(BusRouterError::Target)
+    }
+
+    /// バースト全体が 1 つのターゲットに収まる場合はそのまま転送し、
+    /// ターゲット境界をまたぐ場合はワード単位に分解する。
+    fn write_burst(&mut self, addr: A, data: &[D], strb: &[S]) -> Result<(), Self::Error>
+    where
+        A: BusAddress,
+        D: BusWord,
+        S: Copy,
+    {
+        let (index, local) = self.decode(addr)?;
+        let route = &mut self.routes[index];
+        if burst_fits(local.to_usize(), data.len(), D::BYTES, route.size) {
+            return route
+                .bus
+                .write_burst(local, data, strb)
+                .map_err(BusRouterError::Target);
+        }
+
+        let base = addr.to_usize();
+        for (i, (&data, &strb)) in data.iter().zip(strb).enumerate() {
+            let addr = burst_addr(base, i, D::BYTES).ok_or(BusRouterError::AddressOutOfRange)?;
+            self.write(addr, data, strb)?;
+        }
+        Ok(())
+    }
+
+    fn read_burst(&mut self, addr: A, data: &mut [D]) -> Result<(), Self::Error>
+    where
+        A: BusAddress,
+        D: BusWord,
+    {
+        let (index, local) = self.decode(addr)?;
+        let route = &mut self.routes[index];
+        if burst_fits(local.to_usize(), data.len(), D::BYTES, route.size) {
+            return route
+                .bus
+                .read_burst(local, data)
+                .map_err(BusRouterError::Target);
+        }
+
+        let base = addr.to_usize();
+        for (i, data) in data.iter_mut().enumerate() {
+            let addr = burst_addr(base, i, D::BYTES).ok_or(BusRouterError::AddressOutOfRange)?;
+            *data = self.read(addr)?;
+        }
+        Ok(())
+    }
+}
+
+// whether `len` words from `local` stay inside a target of `size` bytes
+fn burst_fits(local: usize, len: usize, word_bytes: usize, size: usize) -> bool {
+    len.checked_mul(word_bytes)
+        .and_then(|bytes| local.checked_add(bytes))
+        .is_some_and(|end| end <= size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus_accessor::LittleEndian;
+    use crate::ram_bus::{RamBus, RamBusError};
+    use crate::shared_bus_accessor::SharedBusAccessor;
+
+    fn router() -> BusRouter<usize, u32, u8, RamBusError> {
+        BusRouter::new()
+            .with(0x1000, 0x40, RamBus::new(0x40))
+            .unwrap()
+            .with(0x2000, 0x40, RamBus::new(0x40))
+            .unwrap()
+    }
+
+    #[test]
+    fn routes_to_target_relative_address() {
+        let mut router = router();
+        router.write(0x2004, 0xDEAD_BEEF, 0xF).unwrap();
+        assert_eq!(router.read(0x2004).unwrap(), 0xDEAD_BEEF);
+        assert_eq!(router.read(0x1004).unwrap(), 0);
+    }
+
+    #[test]
+    fn unmapped_address_is_decode_error() {
+        let mut router = router();
+        assert_eq!(
+            router.read(0x1040),
+            Err(BusRouterError::DecodeError(0x1040))
+        );
+        assert_eq!(router.read(0x0), Err(BusRouterError::DecodeError(0x0)));
+        assert_eq!(
+            router.write(0x3000, 0, 0xF),
+            Err(BusRouterError::DecodeError(0x3000))
+        );
+    }
+
+    #[test]
+    fn overlapping_ranges_are_rejected() {
+        let mut router = router();
+        assert_eq!(
+            router.add(0x103C, 0x10, RamBus::new(0x40)),
+            Err(BusRouterError::Overlap {
+                base: 0x103C,
+                size: 0x10
+            })
+        );
+        assert_eq!(
+            router.add(0x0F00, 0x101, RamBus::new(0x40)),
+            Err(BusRouterError::Overlap {
+                base: 0x0F00,
+                size: 0x101
+            })
+        );
+        assert_eq!(
+            router.add(0x1800, 0, RamBus::new(0x40)),
+            Err(BusRouterError::ZeroSize)
+        );
+        router.add(0x1040, 0x40, RamBus::new(0x40)).unwrap();
+        assert_eq!(
+            router.ranges().collect::<Vec<_>>(),
+            [(0x1000, 0x40), (0x1040, 0x40), (0x2000, 0x40)]
+        );
+    }
+
+    #[test]
+    fn shared_accessor_over_router() {
+        let accessor: SharedBusAccessor<_, usize, u32, u8, LittleEndian> =
+            SharedBusAccessor::new(router());
+        let ip1 = accessor.subclone(0x2000, 0x40);
+        ip1.write_u16(0x02, 0xABCD).unwrap();
+        assert_eq!(accessor.read_u16(0x2002).unwrap(), 0xABCD);
+        assert!(accessor.read_u32(0x1800).is_err());
+    }
+}
@@ -0,0 +1,444 @@
+#![allow(dead_code)]
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{fence, Ordering};
+
+use super::*;
+
+// Descriptor ring for scatter-gather DMA engines.
+//
+// Descriptors are laid out back to back (rounded up to `align`) from offset 0
+// of the accessor and chained through physical addresses.
+// The CPU produces descriptors at `head` and hands them to the hardware,
+// the hardware gives them back by clearing its ownership bit and the CPU
+// consumes them at `tail`.
+
+/// A DMA descriptor that `DescRing` copies to and from device memory as raw
+/// `u32` words.
+///
+/// # Safety
+///
+/// The implementing type must be `#[repr(C)]` plain data:
+/// - no padding bytes,
+/// - a size that is a non-zero multiple of 4 bytes,
+/// - every bit pattern is a valid value (integer fields only; no `bool`,
+///   enums, references or pointers),
+///
+/// and `OWNER_WORD` must be the index of the `u32` word that holds the
+/// ownership bit.
+pub unsafe trait Descriptor: Copy {
+    // written last (after a release fence) by `write_desc` and read first by
+    // `read_desc`, so the hardware never sees a half written descriptor
+    const OWNER_WORD: usize;
+
+    fn set_next(&mut self, next_phys_addr: usize);
+    fn is_hw_owned(&self) -> bool;
+    fn set_hw_owned(&mut self, owned: bool);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DescRingError {
+    InvalidDescriptorSize,
+    InvalidAlign,
+    Misaligned,
+    TooSmall,
+    Full,
+}
+
+impl fmt::Display for DescRingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDescriptorSize => {
+                write!(
+                    f,
+                    "descriptor must be whole u32 words and contain its owner word"
+                )
+            }
+            Self::InvalidAlign => write!(f, "alignment must be a power of two"),
+            Self::Misaligned => write!(f, "ring memory is not aligned"),
+            Self::TooSmall => write!(f, "ring memory is too small"),
+            Self::Full => write!(f, "descriptor ring is full"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DescRingError {}
+
+#[derive(Debug)]
+pub struct DescRing<T, D> {
+    accessor: T,
+    len: usize,
+    stride: usize,
+    head: usize,
+    tail: usize,
+    count: usize,
+    _phantom: PhantomData<D>,
+}
+
+impl<T: MemAccess, D: Descriptor> DescRing<T, D> {
+    pub fn new(accessor: T, len: usize, align: usize) -> Result<Self, DescRingError> {
+        let desc_size = core::mem::size_of::<D>();
+        if desc_size == 0 || desc_size & 3 != 0 || D::OWNER_WORD >= desc_size / 4 {
+            return Err(DescRingError::InvalidDescriptorSize);
+        }
+        if !align.is_power_of_two() {
+            return Err(DescRingError::InvalidAlign);
+        }
+        if accessor.phys_addr() & (align - 1) != 0 {
+            return Err(DescRingError::Misaligned);
+        }
+        let stride = (desc_size + align - 1) & !(align - 1);
+        match stride.checked_mul(len) {
+            Some(total) if len > 0 && total <= accessor.size() => {}
+            _ => return Err(DescRingError::TooSmall),
+        }
+
+        Ok(DescRing {
+            accessor,
+            len,
+            stride,
+            head: 0,
+            tail: 0,
+            count: 0,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn accessor(&self) -> &T {
+        &self.accessor
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn head(&self) -> usize {
+        self.head
+    }
+
+    pub fn tail(&self) -> usize {
+        self.tail
+    }
+
+    // number of descriptors handed to the hardware and not yet consumed
+    pub fn in_flight(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.count == self.len
+    }
+
+    pub fn desc_offset(&self, index: usize) -> usize {
+        debug_assert!(index < self.len);
+        index * self.stride
+    }
+
+    pub fn desc_phys_addr(&self, index: usize) -> usize {
+        self.accessor.phys_addr() + self.desc_offset(index)
+    }
+
+    pub fn next_index(&self, index: usize) -> usize {
+        if index + 1 == self.len {
+            0
+        } else {
+            index + 1
+        }
+    }
+
+    /// Reads the descriptor in slot `index`.
+    ///
+    /// # Safety
+    ///
+    /// The accessor must map the ring memory and `index` must be less than `len()`.
+    pub unsafe fn read_desc(&self, index: usize) -> D {
+        let offset = self.desc_offset(index);
+        let mut desc = MaybeUninit::<D>::uninit();
+        let ptr = desc.as_mut_ptr() as *mut u32;
+        let owner = D::OWNER_WORD;
+        ptr.add(owner)
+            .write_unaligned(self.accessor.read_mem_u32(offset + owner * 4));
+        fence(Ordering::Acquire);
+        for i in (0..core::mem::size_of::<D>() / 4).filter(|&i| i != owner) {
+            ptr.add(i)
+                .write_unaligned(self.accessor.read_mem_u32(offset + i * 4));
+        }
+        desc.assume_init()
+    }
+
+    /// Writes `desc` to slot `index`, storing its owner word last.
+    ///
+    /// # Safety
+    ///
+    /// The accessor must map the ring memory and `index` must be less than `len()`.
+    /// Overwriting a slot the hardware currently owns corrupts the transfer.
+    pub unsafe fn write_desc(&self, index: usize, desc: &D) {
+        let offset = self.desc_offset(index);
+        let ptr = desc as *const D as *const u32;
+        let owner = D::OWNER_WORD;
+        for i in (0..core::mem::size_of::<D>() / 4).filter(|&i| i != owner) {
+            self.accessor
+                .write_mem_u32(offset + i * 4, ptr.add(i).read_unaligned());
+        }
+        fence(Ordering::Release);
+        self.accessor
+            .write_mem_u32(offset + owner * 4, ptr.add(owner).read_unaligned());
+    }
+
+    /// Writes `template` to every slot, chained in a circle and owned by the CPU.
+    ///
+    /// # Safety
+    ///
+    /// The accessor must map the ring memory, and the engine must be halted
+    /// so it does not walk the ring while it is rewritten.
+    pub unsafe fn init(&mut self, template: D) {
+        for index in 0..self.len {
+            let mut desc = template;
+            desc.set_next(self.desc_phys_addr(self.next_index(index)));
+            desc.set_hw_owned(false);
+            self.write_desc(index, &desc);
+        }
+        self.head = 0;
+        self.tail = 0;
+        self.count = 0;
+    }
+
+    /// Hands `desc` to the hardware and returns the slot index it was written to.
+    ///
+    /// # Safety
+    ///
+    /// The ring must have been set up with `init`, and any buffer the descriptor
+    /// points at must stay valid until the slot is consumed.
+    pub unsafe fn produce(&mut self, desc: D) -> Result<usize, DescRingError> {
+        let index = self.prepare_produce(desc)?;
+        self.finish_produce();
+        Ok(index)
+    }
+
+    /// Takes back the oldest descriptor once the hardware has released it.
+    ///
+    /// # Safety
+    ///
+    /// The accessor must map the ring memory.
+    pub unsafe fn consume(&mut self) -> Option<(usize, D)> {
+        if self.count == 0 {
+            return None;
+        }
+        let index = self.tail;
+        let desc = self.read_desc(index);
+        if desc.is_hw_owned() {
+            return None;
+        }
+        self.tail = self.next_index(index);
+        self.count -= 1;
+        Some((index, desc))
+    }
+
+    unsafe fn prepare_produce(&mut self, mut desc: D) -> Result<usize, DescRingError> {
+        if self.is_full() {
+            return Err(DescRingError::Full);
+        }
+        let index = self.head;
+        desc.set_next(self.desc_phys_addr(self.next_index(index)));
+        desc.set_hw_owned(true);
+        self.write_desc(index, &desc);
+        Ok(index)
+    }
+
+    fn finish_produce(&mut self) {
+        self.head = self.next_index(self.head);
+        self.count += 1;
+    }
+}
+
+impl<T: MemAccess + MemAccessSync, D: Descriptor> DescRing<T, D> {
+    /// Flushes slot `index` so the device sees the CPU's writes.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `len()`.
+    pub unsafe fn sync_desc_for_device(&self, index: usize) {
+        self.accessor
+            .sync_for_device_with_range(self.desc_offset(index), self.stride, 0, 1);
+    }
+
+    /// Invalidates slot `index` so the CPU sees the device's writes.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `len()`.
+    pub unsafe fn sync_desc_for_cpu(&self, index: usize) {
+        self.accessor
+            .sync_for_cpu_with_range(self.desc_offset(index), self.stride, 0, 1);
+    }
+
+    /// `init` followed by a flush of the whole ring.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`init`](Self::init).
+    pub unsafe fn init_sync(&mut self, template: D) {
+        self.init(template);
+        self.accessor
+            .sync_for_device_with_range(0, self.stride * self.len, 0, 1);
+    }
+
+    /// `produce` with the slot flushed before it is counted as in flight.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`produce`](Self::produce).
+    pub unsafe fn produce_sync(&mut self, desc: D) -> Result<usize, DescRingError> {
+        let index = self.prepare_produce(desc)?;
+        self.sync_desc_for_device(index);
+        self.finish_produce();
+        Ok(index)
+    }
+
+    /// `consume` with the tail slot invalidated first.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`consume`](Self::consume).
+    pub unsafe fn consume_sync(&mut self) -> Option<(usize, D)> {
+        if self.count == 0 {
+            return None;
+        }
+        self.sync_desc_for_cpu(self.tail);
+        self.consume()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    struct TestDesc {
+        next: u32,
+        buf_addr: u32,
+        control: u32,
+        status: u32,
+    }
+
+    const STATUS_CMPLT: u32 = 0x8000_0000;
+
+    #[repr(align(64))]
+    struct Aligned64([u8; 256]);
+
+    unsafe impl Descriptor for TestDesc {
+        const OWNER_WORD: usize = 3;
+
+        fn set_next(&mut self, next_phys_addr: usize) {
+            self.next = next_phys_addr as u32;
+        }
+
+        fn is_hw_owned(&self) -> bool {
+            self.status & STATUS_CMPLT == 0
+        }
+
+        fn set_hw_owned(&mut self, owned: bool) {
+            if owned {
+                self.status &= !STATUS_CMPLT;
+            } else {
+                self.status |= STATUS_CMPLT;
+            }
+        }
+    }
+
+    #[test]
+    fn chain_produce_consume() {
+        let mut buf = Aligned64([0u8; 256]);
+        let mmio = MmioAccessor::<u32>::new(buf.0.as_mut_ptr() as usize, 256);
+        let mut ring = DescRing::<_, TestDesc>::new(mmio.clone(), 4, 64).unwrap();
+        let stride = ring.stride();
+        assert_eq!(stride, 64);
+        assert_eq!(ring.desc_offset(3), 0xc0);
+
+        unsafe {
+            ring.init(TestDesc::default());
+            for i in 0..4 {
+                let desc = ring.read_desc(i);
+                assert_eq!(desc.next, ring.desc_phys_addr((i + 1) % 4) as u32);
+                assert!(!desc.is_hw_owned());
+            }
+
+            let desc = TestDesc {
+                buf_addr: 0x1000,
+                control: 0x100,
+                ..Default::default()
+            };
+            assert_eq!(ring.produce(desc).unwrap(), 0);
+            assert_eq!(ring.produce(desc).unwrap(), 1);
+            assert_eq!(ring.in_flight(), 2);
+
+            // hardware has not completed yet
+            assert!(ring.consume().is_none());
+
+            // hardware completes the first descriptor
+            let status = mmio.read_mem_u32(12);
+            mmio.write_mem_u32(12, status | STATUS_CMPLT);
+            let (index, done) = ring.consume().unwrap();
+            assert_eq!(index, 0);
+            assert_eq!(done.buf_addr, 0x1000);
+            assert_eq!(ring.tail(), 1);
+            assert!(ring.consume().is_none());
+
+            assert_eq!(mmio.read_mem_u32(stride + 4), 0x1000);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn owner_word_written_last() {
+        let mut buf = [0u64; 8];
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 64);
+        let ring_buf = TraceRing::new(16);
+        let acc = TracingAccessor::new(mmio, ring_buf.clone());
+        let mut ring = DescRing::<_, TestDesc>::new(acc, 2, 4).unwrap();
+        unsafe {
+            ring.produce(TestDesc::default()).unwrap();
+        }
+        let offsets: std::vec::Vec<usize> = ring_buf.take().iter().map(|r| r.offset).collect();
+        assert_eq!(offsets, [0, 4, 8, 12]);
+
+        unsafe {
+            ring.read_desc(0);
+        }
+        let offsets: std::vec::Vec<usize> = ring_buf.take().iter().map(|r| r.offset).collect();
+        assert_eq!(offsets, [12, 0, 4, 8]);
+    }
+
+    #[test]
+    fn full_ring() {
+        let mut buf = [0u64; 8];
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 64);
+        let mut ring = DescRing::<_, TestDesc>::new(mmio, 2, 4).unwrap();
+        unsafe {
+            ring.init(TestDesc::default());
+            ring.produce(TestDesc::default()).unwrap();
+            ring.produce(TestDesc::default()).unwrap();
+            assert_eq!(
+                ring.produce(TestDesc::default()).unwrap_err(),
+                DescRingError::Full
+            );
+        }
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 64);
+        assert_eq!(
+            DescRing::<_, TestDesc>::new(mmio, 5, 4).unwrap_err(),
+            DescRingError::TooSmall
+        );
+    }
+}
@@ -0,0 +1,351 @@
+#![allow(dead_code)]
+
+use super::*;
+use core::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::vec::Vec;
+use thiserror::Error;
+
+// Sub-allocator that carves aligned DMA buffers out of one large accessor
+// (typically UdmabufAccessor). Alignment and boundaries are applied to the
+// physical address.
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum DmaPoolError {
+    #[error("DmaPoolError: zero size allocation")]
+    ZeroSize,
+    #[error("DmaPoolError: alignment must be a power of two")]
+    InvalidAlign,
+    #[error("DmaPoolError: boundary must be a power of two")]
+    InvalidBoundary,
+    #[error("DmaPoolError: size exceeds boundary")]
+    TooLargeForBoundary,
+    #[error("DmaPoolError: out of memory")]
+    OutOfMemory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmaPoolStrategy {
+    Bump,
+    FreeList,
+}
+
+#[derive(Debug)]
+struct DmaPoolState {
+    strategy: DmaPoolStrategy,
+    phys_addr: usize,
+    size: usize,
+    next: usize,
+    live: usize,
+    free_list: Vec<(usize, usize)>,
+}
+
+impl DmaPoolState {
+    fn new(strategy: DmaPoolStrategy, phys_addr: usize, size: usize) -> Self {
+        let mut free_list = Vec::new();
+        if size > 0 {
+            free_list.push((0, size));
+        }
+        DmaPoolState {
+            strategy,
+            phys_addr,
+            size,
+            next: 0,
+            live: 0,
+            free_list,
+        }
+    }
+
+    // first offset at or after `start` that satisfies align and boundary
+    fn fit(&self, start: usize, size: usize, align: usize, boundary: usize) -> Option<usize> {
+        let phys = self.phys_addr.checked_add(start)?;
+        let mut aligned = phys.checked_add(align - 1)? & !(align - 1);
+        if boundary != 0 {
+            let mask = !(boundary - 1);
+            let last = aligned.checked_add(size - 1)?;
+            if aligned & mask != last & mask {
+                aligned = last & mask;
+            }
+        }
+        Some(aligned - self.phys_addr)
+    }
+
+    fn alloc(&mut self, size: usize, align: usize, boundary: usize) -> Option<usize> {
+        match self.strategy {
+            DmaPoolStrategy::Bump => {
+                let offset = self.fit(self.next, size, align, boundary)?;
+                let end = offset.checked_add(size)?;
+                if end > self.size {
+                    return None;
+                }
+                self.next = end;
+                self.live += 1;
+                Some(offset)
+            }
+            DmaPoolStrategy::FreeList => {
+                for i in 0..self.free_list.len() {
+                    let (block_offset, block_size) = self.free_list[i];
+                    let block_end = block_offset + block_size;
+                    let offset = match self.fit(block_offset, size, align, boundary) {
+                        Some(offset) => offset,
+                        None => continue,
+                    };
+                    let end = match offset.checked_add(size) {
+                        Some(end) if end <= block_end => end,
+                        _ => continue,
+                    };
+
+                    self.free_list.remove(i);
+                    if end < block_end {
+                        self.free_list.insert(i, (end, block_end - end));
+                    }
+                    if block_offset < offset {
+                        self.free_list
+                            .insert(i, (block_offset, offset - block_offset));
+                    }
+                    self.live += 1;
+                    return Some(offset);
+                }
+                None
+            }
+        }
+    }
+
+    fn free(&mut self, offset: usize, size: usize) {
+        self.live -= 1;
+        match self.strategy {
+            DmaPoolStrategy::Bump => {
+                if self.live == 0 {
+                    self.next = 0;
+                } else if offset + size == self.next {
+                    self.next = offset;
+                }
+            }
+            DmaPoolStrategy::FreeList => {
+                let pos = self
+                    .free_list
+                    .iter()
+                    .position(|&(o, _)| o > offset)
+                    .unwrap_or(self.free_list.len());
+                self.free_list.insert(pos, (offset, size));
+
+                // merge with next block
+                if pos + 1 < self.free_list.len() {
+                    let (next_offset, next_size) = self.free_list[pos + 1];
+                    if offset + size == next_offset {
+                        self.free_list[pos].1 += next_size;
+                        self.free_list.remove(pos + 1);
+                    }
+                }
+                // merge with previous block
+                if pos > 0 {
+                    let (prev_offset, prev_size) = self.free_list[pos - 1];
+                    if prev_offset + prev_size == offset {
+                        self.free_list[pos - 1].1 += self.free_list[pos].1;
+                        self.free_list.remove(pos);
+                    }
+                }
+            }
+        }
+    }
+
+    fn available(&self) -> usize {
+        match self.strategy {
+            DmaPoolStrategy::Bump => self.size - self.next,
+            DmaPoolStrategy::FreeList => self.free_list.iter().map(|&(_, size)| size).sum(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DmaPool<T> {
+    accessor: T,
+    state: Arc<Mutex<DmaPoolState>>,
+}
+
+impl<T: MemAccessBase + MemAccess> DmaPool<T> {
+    pub fn new(accessor: T, strategy: DmaPoolStrategy) -> Self {
+        let state = DmaPoolState::new(strategy, accessor.phys_addr(), accessor.size());
+        DmaPool {
+            accessor,
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    pub fn accessor(&self) -> &T {
+        &self.accessor
+    }
+
+    pub fn strategy(&self) -> DmaPoolStrategy {
+        self.state.lock().unwrap().strategy
+    }
+
+    pub fn available(&self) -> usize {
+        self.state.lock().unwrap().available()
+    }
+
+    pub fn alloc(&self, size: usize, align: usize) -> Result<DmaBuffer<T>, DmaPoolError> {
+        self.alloc_with_boundary(size, align, 0)
+    }
+
+    // boundary: the buffer never crosses a multiple of `boundary` (0 = no limit)
+    pub fn alloc_with_boundary(
+        &self,
+        size: usize,
+        align: usize,
+        boundary: usize,
+    ) -> Result<DmaBuffer<T>, DmaPoolError> {
+        if size == 0 {
+            return Err(DmaPoolError::ZeroSize);
+        }
+        if !align.is_power_of_two() {
+            return Err(DmaPoolError::InvalidAlign);
+        }
+        if boundary != 0 {
+            if !boundary.is_power_of_two() {
+                return Err(DmaPoolError::InvalidBoundary);
+            }
+            if size > boundary {
+                return Err(DmaPoolError::TooLargeForBoundary);
+            }
+        }
+
+        let offset = self
+            .state
+            .lock()
+            .unwrap()
+            .alloc(size, align, boundary)
+            .ok_or(DmaPoolError::OutOfMemory)?;
+
+        Ok(DmaBuffer {
+            accessor: self.accessor.subclone(offset, size),
+            offset,
+            size,
+            state: self.state.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct DmaBuffer<T> {
+    accessor: T,
+    offset: usize,
+    size: usize,
+    state: Arc<Mutex<DmaPoolState>>,
+}
+
+impl<T: MemAccess> DmaBuffer<T> {
+    pub fn accessor(&self) -> &T {
+        &self.accessor
+    }
+
+    // offset from the top of the pool
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn phys_addr(&self) -> usize {
+        self.accessor.phys_addr()
+    }
+}
+
+impl<T> Deref for DmaBuffer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.accessor
+    }
+}
+
+impl<T> Drop for DmaBuffer<T> {
+    fn drop(&mut self) {
+        self.state
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .free(self.offset, self.size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(buf: &mut [u64], strategy: DmaPoolStrategy) -> DmaPool<MmioAccessor<u32>> {
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, buf.len() * 8);
+        DmaPool::new(mmio, strategy)
+    }
+
+    #[test]
+    fn bump_alignment_and_no_overlap() {
+        let mut buf = [0u64; 512];
+        let pool = pool(&mut buf, DmaPoolStrategy::Bump);
+
+        let a = pool.alloc(10, 64).unwrap();
+        let b = pool.alloc(100, 256).unwrap();
+        assert_eq!(a.phys_addr() % 64, 0);
+        assert_eq!(b.phys_addr() % 256, 0);
+        assert!(a.phys_addr() + a.size() <= b.phys_addr());
+        assert_eq!(b.size(), 100);
+
+        unsafe {
+            a.write_mem_u32(0, 0x1234_5678);
+            b.write_mem_u32(0, 0x9abc_def0);
+            assert_eq!(pool.accessor().read_mem_u32(a.offset()), 0x1234_5678);
+            assert_eq!(pool.accessor().read_mem_u32(b.offset()), 0x9abc_def0);
+        }
+
+        drop(a);
+        drop(b);
+        assert_eq!(pool.available(), 4096);
+    }
+
+    #[test]
+    fn free_list_reuses_released_block() {
+        let mut buf = [0u64; 512];
+        let pool = pool(&mut buf, DmaPoolStrategy::FreeList);
+
+        let a = pool.alloc(1024, 8).unwrap();
+        let b = pool.alloc(1024, 8).unwrap();
+        let a_offset = a.offset();
+        drop(a);
+
+        let c = pool.alloc(512, 8).unwrap();
+        assert_eq!(c.offset(), a_offset);
+
+        drop(b);
+        drop(c);
+        assert_eq!(pool.available(), 4096);
+        assert!(pool.alloc(4096, 8).is_ok());
+    }
+
+    #[test]
+    fn boundary_is_not_crossed() {
+        let mut buf = [0u64; 512];
+        let pool = pool(&mut buf, DmaPoolStrategy::FreeList);
+
+        let _a = pool.alloc(1000, 8).unwrap();
+        let b = pool.alloc_with_boundary(200, 8, 1024).unwrap();
+        let first = b.phys_addr();
+        let last = first + b.size() - 1;
+        assert_eq!(first / 1024, last / 1024);
+
+        assert_eq!(
+            pool.alloc_with_boundary(2048, 8, 1024).unwrap_err(),
+            DmaPoolError::TooLargeForBoundary
+        );
+    }
+
+    #[test]
+    fn out_of_memory() {
+        let mut buf = [0u64; 64];
+        let pool = pool(&mut buf, DmaPoolStrategy::Bump);
+
+        let _a = pool.alloc(256, 8).unwrap();
+        assert_eq!(pool.alloc(512, 8).unwrap_err(), DmaPoolError::OutOfMemory);
+        assert_eq!(pool.alloc(16, 3).unwrap_err(), DmaPoolError::InvalidAlign);
+    }
+}
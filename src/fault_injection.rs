@@ -0,0 +1,556 @@
+#![allow(dead_code)]
+
+use core::fmt;
+use core::ops::Range;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::vec;
+use std::vec::Vec;
+
+use super::bus_accessor::{burst_addr, Bus, BusAddress, BusWord};
+use super::hooked_accessor::{Access, AccessHook, AccessValue, HookedAccessor, TransparentHook};
+use super::mem_accessor::{MemAccess, MemAccessBase, MemAccessTryError};
+
+/// ルールに一致したアクセスに与える障害。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultAction {
+    /// アクセスを失敗させる（`try_*` は `AccessFault`、それ以外は panic、バスは `FaultBusError::Injected`）
+    Error,
+    /// 指定ビットを 1 に固定
+    StuckHigh(u64),
+    /// 指定ビットを 0 に固定
+    StuckLow(u64),
+    /// 指定ビットを反転
+    Flip(u64),
+}
+
+/// ルールが発火する条件（回数は一致したアクセスを 1 から数える）。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FaultTrigger {
+    Always,
+    /// n 回目だけ
+    Nth(usize),
+    /// n 回ごと
+    Every(usize),
+    /// 確率 p（`FaultInjector` の seed で再現可能）
+    Probability(f64),
+}
+
+/// 障害注入ルール。
+///
+/// `range` はバイトオフセット（`FaultInjectingAccessor` を作った時点の先頭から、
+/// `FaultInjectingBus` ではバスアドレス）で、アクセス範囲が重なれば一致とする。
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaultRule {
+    pub range: Range<usize>,
+    pub reads: bool,
+    pub writes: bool,
+    pub trigger: FaultTrigger,
+    pub action: FaultAction,
+}
+
+impl FaultRule {
+    /// 全範囲・読み書き両方・毎回発火するルール。
+    pub fn new(action: FaultAction) -> Self {
+        Self {
+            range: 0..usize::MAX,
+            reads: true,
+            writes: true,
+            trigger: FaultTrigger::Always,
+            action,
+        }
+    }
+
+    pub fn error() -> Self {
+        Self::new(FaultAction::Error)
+    }
+
+    pub fn stuck_high(mask: u64) -> Self {
+        Self::new(FaultAction::StuckHigh(mask))
+    }
+
+    pub fn stuck_low(mask: u64) -> Self {
+        Self::new(FaultAction::StuckLow(mask))
+    }
+
+    pub fn flip(mask: u64) -> Self {
+        Self::new(FaultAction::Flip(mask))
+    }
+
+    pub fn with_range(mut self, range: Range<usize>) -> Self {
+        self.range = range;
+        self
+    }
+
+    pub fn reads_only(mut self) -> Self {
+        self.reads = true;
+        self.writes = false;
+        self
+    }
+
+    pub fn writes_only(mut self) -> Self {
+        self.reads = false;
+        self.writes = true;
+        self
+    }
+
+    pub fn nth(mut self, n: usize) -> Self {
+        self.trigger = FaultTrigger::Nth(n);
+        self
+    }
+
+    pub fn every(mut self, n: usize) -> Self {
+        self.trigger = FaultTrigger::Every(n);
+        self
+    }
+
+    pub fn with_probability(mut self, p: f64) -> Self {
+        self.trigger = FaultTrigger::Probability(p);
+        self
+    }
+}
+
+/// 1 回のアクセスに適用する障害（複数ルールの合成）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Fault {
+    error: bool,
+    set: u64,
+    clear: u64,
+    flip: u64,
+}
+
+impl Fault {
+    fn corrupts(&self) -> bool {
+        self.set | self.clear | self.flip != 0
+    }
+
+    fn apply_bits(&self, bits: u64) -> u64 {
+        ((bits | self.set) & !self.clear) ^ self.flip
+    }
+
+    fn apply<V: AccessValue>(&self, value: V) -> V {
+        if self.corrupts() {
+            V::from_u64(self.apply_bits(value.to_u64()))
+        } else {
+            value
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FaultState {
+    rules: Vec<(FaultRule, usize)>,
+    rng: u64,
+    injected: usize,
+}
+
+// xorshift64* で [0, 1) の乱数
+fn next_f64(rng: &mut u64) -> f64 {
+    *rng ^= *rng >> 12;
+    *rng ^= *rng << 25;
+    *rng ^= *rng >> 27;
+    (rng.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// ルールと乱数状態。clone したハンドル（と、それを使うラッパー）は同じ状態を共有する。
+#[derive(Debug, Clone)]
+pub struct FaultInjector {
+    state: Arc<Mutex<FaultState>>,
+}
+
+impl FaultInjector {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(FaultState {
+                rules: Vec::new(),
+                // xorshift は状態 0 だと止まる
+                rng: if seed == 0 {
+                    0x9e37_79b9_7f4a_7c15
+                } else {
+                    seed
+                },
+                injected: 0,
+            })),
+        }
+    }
+
+    pub fn add_rule(&self, rule: FaultRule) {
+        self.lock().rules.push((rule, 0));
+    }
+
+    pub fn with_rule(self, rule: FaultRule) -> Self {
+        self.add_rule(rule);
+        self
+    }
+
+    pub fn clear_rules(&self) {
+        self.lock().rules.clear();
+    }
+
+    /// 障害を与えたアクセスの数。
+    pub fn injected(&self) -> usize {
+        self.lock().injected
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FaultState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn check(&self, write: bool, offset: usize, len: usize) -> Fault {
+        let mut state = self.lock();
+        let state = &mut *state;
+        let end = offset.saturating_add(len.max(1));
+        let mut fault = Fault::default();
+        let mut fired = false;
+        for (rule, hits) in state.rules.iter_mut() {
+            let op = if write { rule.writes } else { rule.reads };
+            if !op || offset >= rule.range.end || end <= rule.range.start {
+                continue;
+            }
+            *hits += 1;
+            let fire = match rule.trigger {
+                FaultTrigger::Always => true,
+                FaultTrigger::Nth(n) => *hits == n,
+                FaultTrigger::Every(n) => n != 0 && *hits % n == 0,
+                FaultTrigger::Probability(p) => next_f64(&mut state.rng) < p,
+            };
+            if !fire {
+                continue;
+            }
+            fired = true;
+            match rule.action {
+                FaultAction::Error => fault.error = true,
+                FaultAction::StuckHigh(mask) => fault.set |= mask,
+                FaultAction::StuckLow(mask) => fault.clear |= mask,
+                FaultAction::Flip(mask) => fault.flip ^= mask,
+            }
+        }
+        if fired {
+            state.injected += 1;
+        }
+        fault
+    }
+}
+
+impl AccessHook for FaultInjector {
+    // 要素ごとの障害
+    type Guard = Vec<Fault>;
+
+    fn enter(&self, access: &Access) -> Result<Vec<Fault>, MemAccessTryError> {
+        // 換算できないオフセットはどのルールの範囲にも入らない
+        let Some(bytes) = access.bytes() else {
+            return Ok(vec![Fault::default(); access.count]);
+        };
+        let faults: Vec<Fault> = (0..access.count)
+            .map(|i| {
+                let offset = bytes.start + i * access.width;
+                self.check(access.is_write(), offset, access.width)
+            })
+            .collect();
+        if faults.iter().any(|fault| fault.error) {
+            Err(MemAccessTryError::AccessFault)
+        } else {
+            Ok(faults)
+        }
+    }
+
+    fn modifies(&self, faults: &Vec<Fault>) -> bool {
+        faults.iter().any(|fault| fault.corrupts())
+    }
+
+    fn write_value<V: AccessValue>(&self, faults: &Vec<Fault>, index: usize, value: V) -> V {
+        faults[index].apply(value)
+    }
+
+    fn read_value<V: AccessValue>(&self, faults: &Vec<Fault>, index: usize, value: V) -> V {
+        faults[index].apply(value)
+    }
+
+    fn refused(&self, access: &Access, _error: MemAccessTryError) -> ! {
+        panic!("injected access fault at offset 0x{:x}", access.offset);
+    }
+}
+
+impl TransparentHook for FaultInjector {}
+
+/// 任意の `MemAccess` を包み、`FaultInjector` のルールに従ってエラーや値の化けを起こす。
+///
+/// - エラーになったアクセスは内側に発行しない
+/// - 値の化けは読み出しでは返す値に、書き込みでは内側へ書く値に適用する
+/// - コピーは要素ごとに 1 アクセスとして照合し、1 要素でもエラーならコピー全体を失敗させる
+/// - レジスタアクセスは `reg * reg_size` バイト目として照合する（`reg_size` は内側の
+///   `MemAccessBase::reg_size()`、`new_with_reg_size` では明示した値）
+pub type FaultInjectingAccessor<A> = HookedAccessor<A, FaultInjector>;
+
+impl<A: MemAccess> HookedAccessor<A, FaultInjector> {
+    pub fn new(inner: A, injector: FaultInjector) -> Self
+    where
+        A: MemAccessBase,
+    {
+        Self::with_hook(inner, injector)
+    }
+
+    /// `MemAccessBase` を持たないアクセサ（`BusAccessor` 等）は 1 レジスタのバイト数を明示する。
+    pub fn new_with_reg_size(inner: A, injector: FaultInjector, reg_size: usize) -> Self {
+        Self::with_hook_and_reg_size(inner, injector, reg_size)
+    }
+
+    pub fn injector(&self) -> &FaultInjector {
+        self.hook()
+    }
+}
+
+/// `FaultInjectingBus` のエラー。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultBusError<E> {
+    /// ルールで注入したエラー（バスアドレス）
+    Injected(usize),
+    /// バーストのビートアドレスが表現できない（バースト先頭アドレス）
+    AddressOutOfRange(usize),
+    Bus(E),
+}
+
+impl<E: fmt::Display> fmt::Display for FaultBusError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Injected(addr) => write!(f, "injected bus fault at address 0x{:x}", addr),
+            Self::AddressOutOfRange(addr) => {
+                write!(f, "burst from 0x{:x} is out of the address range", addr)
+            }
+            Self::Bus(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for FaultBusError<E> {}
+
+/// 任意の `Bus` を包み、ビートごとに `FaultInjector` のルールを適用する。
+///
+/// 値の化けはワードの下位 64bit にだけ適用する。エラーになる書き込みバーストは内側に発行しない。
+#[derive(Debug)]
+pub struct FaultInjectingBus<B> {
+    inner: B,
+    injector: FaultInjector,
+}
+
+impl<B> FaultInjectingBus<B> {
+    pub fn new(inner: B, injector: FaultInjector) -> Self {
+        Self { inner, injector }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    pub fn injector(&self) -> &FaultInjector {
+        &self.injector
+    }
+}
+
+fn corrupt_word<D: BusWord>(data: &mut D, fault: Fault) {
+    if !fault.corrupts() {
+        return;
+    }
+    let lanes = D::BYTES.min(8);
+    let mut bits = 0u64;
+    for lane in 0..lanes {
+        bits |= (data.lane(lane) as u64) << (lane * 8);
+    }
+    let bits = fault.apply_bits(bits);
+    for lane in 0..lanes {
+        data.set_lane(lane, (bits >> (lane * 8)) as u8);
+    }
+}
+
+impl<B, A, D, S> Bus<A, D, S> for FaultInjectingBus<B>
+where
+    B: Bus<A, D, S>,
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+{
+    type Error = FaultBusError<B::Error>;
+
+    fn write(&mut self, addr: A, mut data: D, strb: S) -> Result<(), Self::Error> {
+        let fault = self.injector.check(true, addr.to_usize(), D::BYTES);
+        if fault.error {
+            return Err(FaultBusError::Injected(addr.to_usize()));
+        }
+        corrupt_word(&mut data, fault);
+        self.inner
+            .write(addr, data, strb)
+            .map_err(FaultBusError::Bus)
+    }
+
+    fn read(&mut self, addr: A) -> Result<D, Self::Error> {
+        let fault = self.injector.check(false, addr.to_usize(), D::BYTES);
+        if fault.error {
+            return Err(FaultBusError::Injected(addr.to_usize()));
+        }
+        let mut data = self.inner.read(addr).map_err(FaultBusError::Bus)?;
+        corrupt_word(&mut data, fault);
+        Ok(data)
+    }
+
+    fn write_burst(&mut self, addr: A, data: &[D], strb: &[S]) -> Result<(), Self::Error>
+    where
+        A: BusAddress,
+        D: BusWord,
+        S: Copy,
+    {
+        let base = addr.to_usize();
+        let mut beats = data.to_vec();
+        for (i, beat) in beats.iter_mut().enumerate() {
+            let beat_addr = burst_addr::<A>(base, i, D::BYTES)
+                .ok_or(FaultBusError::AddressOutOfRange(base))?
+                .to_usize();
+            let fault = self.injector.check(true, beat_addr, D::BYTES);
+            if fault.error {
+                return Err(FaultBusError::Injected(beat_addr));
+            }
+            corrupt_word(beat, fault);
+        }
+        self.inner
+            .write_burst(addr, &beats, strb)
+            .map_err(FaultBusError::Bus)
+    }
+
+    fn read_burst(&mut self, addr: A, data: &mut [D]) -> Result<(), Self::Error>
+    where
+        A: BusAddress,
+        D: BusWord,
+    {
+        self.inner
+            .read_burst(addr, data)
+            .map_err(FaultBusError::Bus)?;
+        let base = addr.to_usize();
+        for (i, beat) in data.iter_mut().enumerate() {
+            let beat_addr = burst_addr::<A>(base, i, D::BYTES)
+                .ok_or(FaultBusError::AddressOutOfRange(base))?
+                .to_usize();
+            let fault = self.injector.check(false, beat_addr, D::BYTES);
+            if fault.error {
+                return Err(FaultBusError::Injected(beat_addr));
+            }
+            corrupt_word(beat, fault);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus_accessor::{BusAccessor, LittleEndian};
+    use crate::mmio_accessor::MmioAccessor;
+    use crate::ram_bus::RamBus;
+
+    #[test]
+    fn nth_error_in_range() {
+        let mut mem = [0u32; 8];
+        let acc = MmioAccessor::<u32>::new(mem.as_mut_ptr() as usize, 32);
+        let injector = FaultInjector::new(1)
+            .with_rule(FaultRule::error().with_range(0x8..0xc).writes_only().nth(2));
+        let acc = FaultInjectingAccessor::new(acc, injector.clone());
+
+        unsafe {
+            assert_eq!(acc.try_write_reg_u32(2, 1), Ok(()));
+            assert_eq!(acc.try_write_mem_u32(0x0, 5), Ok(()));
+            assert_eq!(
+                acc.try_write_mem_u16(0xa, 2),
+                Err(MemAccessTryError::AccessFault)
+            );
+            assert_eq!(acc.try_write_reg_u32(2, 3), Ok(()));
+            assert_eq!(acc.try_read_mem_u32(0x8), Ok(3));
+        }
+        assert_eq!(injector.injected(), 1);
+        assert_eq!(mem[..3], [5, 0, 3]);
+    }
+
+    #[test]
+    fn stuck_bits_and_subclone() {
+        let mut mem = [0u32; 8];
+        let acc = MmioAccessor::<u32>::new(mem.as_mut_ptr() as usize, 32);
+        let injector = FaultInjector::new(1)
+            .with_rule(
+                FaultRule::stuck_high(0x8000_0000)
+                    .with_range(0x10..0x14)
+                    .reads_only(),
+            )
+            .with_rule(
+                FaultRule::stuck_low(0xff)
+                    .with_range(0x18..0x20)
+                    .writes_only(),
+            );
+        let acc = FaultInjectingAccessor::new(acc, injector);
+        let sub = acc.subclone(0x10, 0x10);
+
+        unsafe {
+            sub.write_mem_u32(0, 0x1234);
+            assert_eq!(sub.read_mem_u32(0), 0x8000_1234);
+            assert_eq!(acc.read_mem_u32(0x10), 0x8000_1234);
+
+            let src = [0x1111u32, 0x2222];
+            sub.copy_from_u32(src.as_ptr(), 0x8, 2);
+            let mut dst = [0u32; 4];
+            acc.copy_to_u32(0x10, dst.as_mut_ptr(), 4);
+            assert_eq!(dst, [0x8000_1234, 0, 0x1100, 0x2200]);
+        }
+    }
+
+    #[test]
+    fn seeded_probability_is_reproducible() {
+        let run = |seed| {
+            let injector =
+                FaultInjector::new(seed).with_rule(FaultRule::error().with_probability(0.5));
+            let bus = FaultInjectingBus::new(RamBus::new(64), injector);
+            let acc = BusAccessor::<_, usize, u32, u8, LittleEndian>::new(bus);
+            (0..32)
+                .map(|i| unsafe { acc.try_read_mem_u32((i % 16) * 4) }.is_err())
+                .collect::<Vec<_>>()
+        };
+        let a = run(42);
+        assert_eq!(a, run(42));
+        assert!(a.iter().any(|&e| e) && a.iter().any(|&e| !e));
+    }
+
+    #[test]
+    fn bus_burst_corruption() {
+        let injector = FaultInjector::new(1).with_rule(FaultRule::flip(0x1).with_range(0x4..0x8));
+        let bus = FaultInjectingBus::new(RamBus::new(64), injector);
+        let acc = BusAccessor::<_, usize, u32, u8, LittleEndian>::new(bus);
+
+        let src = [0x10u32, 0x20, 0x30];
+        let mut dst = [0u32; 3];
+        unsafe {
+            acc.copy_from_u32(src.as_ptr(), 0, 3);
+            acc.copy_to_u32(0, dst.as_mut_ptr(), 3);
+        }
+        // 書き込みと読み出しで 2 回反転する
+        assert_eq!(dst, src);
+        let mut raw = [0u8; 12];
+        acc.bus().inner().dump(0, &mut raw).unwrap();
+        assert_eq!(raw[4], 0x21);
+        assert_eq!(acc.bus().injector().injected(), 2);
+    }
+
+    #[test]
+    fn bus_burst_address_overflow() {
+        let mut bus = FaultInjectingBus::new(RamBus::new(64), FaultInjector::new(1));
+        let base = usize::MAX - 3;
+        assert_eq!(
+            Bus::<usize, u32, u8>::write_burst(&mut bus, base, &[1, 2], &[0xf, 0xf]),
+            Err(FaultBusError::AddressOutOfRange(base))
+        );
+        assert_eq!(bus.injector().injected(), 0);
+    }
+}
@@ -0,0 +1,303 @@
+use super::*;
+use delegate::delegate;
+
+use super::slice_accessor::{
+    check_access, checked_base_fns, checked_copy_fns, checked_offset, checked_rw_fns, expect_access,
+};
+use core::ptr::NonNull;
+use std::alloc::{alloc_zeroed, dealloc, handle_alloc_error, Layout};
+use std::sync::Arc;
+
+#[derive(Debug)]
+struct HeapBuffer {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+unsafe impl Sync for HeapBuffer {}
+unsafe impl Send for HeapBuffer {}
+
+impl HeapBuffer {
+    fn new(size: usize, align: usize) -> Self {
+        // a zero-sized allocation is not allowed, so always reserve at least 1 byte
+        let layout =
+            Layout::from_size_align(size.max(1), align).expect("align must be a power of two");
+        let ptr = unsafe { alloc_zeroed(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout));
+        HeapBuffer { ptr, layout }
+    }
+}
+
+impl Drop for HeapBuffer {
+    fn drop(&mut self) {
+        unsafe { dealloc(self.ptr.as_ptr(), self.layout) };
+    }
+}
+
+// Owned, zero-initialized and aligned heap memory (for tests and host simulation)
+#[derive(Debug)]
+pub struct VecRegion {
+    buf: Arc<HeapBuffer>,
+    addr: usize,
+    size: usize,
+}
+
+impl VecRegion {
+    pub const DEFAULT_ALIGN: usize = 4096;
+
+    pub fn new(size: usize) -> Self {
+        Self::with_align(size, Self::DEFAULT_ALIGN)
+    }
+
+    pub fn with_align(size: usize, align: usize) -> Self {
+        let buf = HeapBuffer::new(size, align);
+        VecRegion {
+            addr: buf.ptr.as_ptr() as usize,
+            size,
+            buf: Arc::new(buf),
+        }
+    }
+}
+
+impl MemRegion for VecRegion {
+    fn subclone(&self, offset: usize, size: usize) -> Self {
+        expect_subclone(self.try_subclone(offset, size), offset, size)
+    }
+
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        let new_size = subregion_size(self.size, offset, size)?;
+        Ok(VecRegion {
+            buf: self.buf.clone(),
+            addr: self.addr + offset,
+            size: new_size,
+        })
+    }
+
+    fn addr(&self) -> usize {
+        self.addr
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn phys_addr(&self) -> usize {
+        self.addr()
+    }
+}
+
+impl Clone for VecRegion {
+    fn clone(&self) -> Self {
+        self.subclone(0, 0)
+    }
+}
+
+#[derive(Debug)]
+pub struct HeapAccessor<U> {
+    mem_accessor: MemAccessor<VecRegion, U>,
+}
+
+impl<U> From<HeapAccessor<U>> for MemAccessor<VecRegion, U> {
+    fn from(from: HeapAccessor<U>) -> MemAccessor<VecRegion, U> {
+        from.mem_accessor
+    }
+}
+
+impl<U> HeapAccessor<U> {
+    pub fn new(size: usize) -> Self {
+        Self {
+            mem_accessor: MemAccessor::<VecRegion, U>::new(VecRegion::new(size)),
+        }
+    }
+
+    pub fn with_align(size: usize, align: usize) -> Self {
+        Self {
+            mem_accessor: MemAccessor::<VecRegion, U>::new(VecRegion::with_align(size, align)),
+        }
+    }
+
+    pub fn subclone_<NewU>(&self, offset: usize, size: usize) -> HeapAccessor<NewU> {
+        HeapAccessor::<NewU> {
+            mem_accessor: MemAccessor::<VecRegion, NewU>::new(
+                self.mem_accessor.region().subclone(offset, size),
+            ),
+        }
+    }
+
+    pub fn try_subclone_<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<HeapAccessor<NewU>, MemAccessTryError> {
+        Ok(HeapAccessor::<NewU> {
+            mem_accessor: MemAccessor::<VecRegion, NewU>::new(
+                self.mem_accessor.region().try_subclone(offset, size)?,
+            ),
+        })
+    }
+
+    pub fn subclone8(&self, offset: usize, size: usize) -> HeapAccessor<u8> {
+        self.subclone_::<u8>(offset, size)
+    }
+
+    pub fn subclone16(&self, offset: usize, size: usize) -> HeapAccessor<u16> {
+        self.subclone_::<u16>(offset, size)
+    }
+
+    pub fn subclone32(&self, offset: usize, size: usize) -> HeapAccessor<u32> {
+        self.subclone_::<u32>(offset, size)
+    }
+
+    pub fn subclone64(&self, offset: usize, size: usize) -> HeapAccessor<u64> {
+        self.subclone_::<u64>(offset, size)
+    }
+
+    pub fn try_subclone8(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<HeapAccessor<u8>, MemAccessTryError> {
+        self.try_subclone_::<u8>(offset, size)
+    }
+
+    pub fn try_subclone16(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<HeapAccessor<u16>, MemAccessTryError> {
+        self.try_subclone_::<u16>(offset, size)
+    }
+
+    pub fn try_subclone32(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<HeapAccessor<u32>, MemAccessTryError> {
+        self.try_subclone_::<u32>(offset, size)
+    }
+
+    pub fn try_subclone64(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<HeapAccessor<u64>, MemAccessTryError> {
+        self.try_subclone_::<u64>(offset, size)
+    }
+
+    delegate! {
+        to self.mem_accessor.region() {
+            pub fn addr(&self) -> usize;
+            pub fn size(&self) -> usize;
+        }
+    }
+}
+
+impl<U> Clone for HeapAccessor<U> {
+    fn clone(&self) -> Self {
+        self.subclone_::<U>(0, 0)
+    }
+}
+
+impl<U> SubcloneAs for HeapAccessor<U> {
+    type As<NewU> = HeapAccessor<NewU>;
+
+    fn subclone_as<NewU>(&self, offset: usize, size: usize) -> Self::As<NewU> {
+        self.subclone_::<NewU>(offset, size)
+    }
+
+    fn try_subclone_as<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<Self::As<NewU>, MemAccessTryError> {
+        self.try_subclone_::<NewU>(offset, size)
+    }
+}
+
+impl<U> MemAccessBase for HeapAccessor<U> {
+    fn reg_size() -> usize {
+        core::mem::size_of::<U>()
+    }
+
+    fn subclone(&self, offset: usize, size: usize) -> Self {
+        self.subclone_::<U>(offset, size)
+    }
+
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        self.try_subclone_::<U>(offset, size)
+    }
+
+    checked_base_fns!();
+}
+
+impl<U> HeapAccessor<U> {
+    fn check<V>(&self, offset: usize, count: usize) -> Result<(), MemAccessTryError> {
+        check_access::<V>(self.addr(), self.size(), offset, count)
+    }
+}
+
+impl<U> MemAccess for HeapAccessor<U> {
+    fn addr(&self) -> usize {
+        self.mem_accessor.addr()
+    }
+    fn size(&self) -> usize {
+        self.mem_accessor.size()
+    }
+    fn phys_addr(&self) -> usize {
+        self.mem_accessor.phys_addr()
+    }
+
+    for_each_access_type!(checked_copy_fns, checked_rw_fns);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heap_access() {
+        let acc = HeapAccessor::<u32>::new(0x100);
+        assert_eq!(acc.addr() % VecRegion::DEFAULT_ALIGN, 0);
+        unsafe {
+            assert_eq!(acc.read_mem_u64(0xf8), 0);
+            acc.write_reg_u32(0x10, 0xdead_beef);
+
+            let sub = acc.subclone32(0x40, 0x40);
+            assert_eq!(sub.read_reg_u32(0), 0xdead_beef);
+            assert_eq!(
+                sub.try_read_reg_u32(0x10),
+                Err(MemAccessTryError::OutOfBounds)
+            );
+            assert_eq!(
+                acc.try_subclone8(0x80, 0x100).unwrap_err(),
+                MemAccessTryError::OutOfBounds
+            );
+
+            // subclone keeps the allocation alive after the original accessor is dropped
+            drop(acc);
+            let dst = &mut [0u32; 2];
+            sub.copy_to_u32(0, dst.as_mut_ptr(), 2);
+            assert_eq!(dst, &[0xdead_beef, 0]);
+        }
+    }
+
+    #[test]
+    fn heap_with_align() {
+        let acc = HeapAccessor::<u8>::with_align(3, 16);
+        assert_eq!(acc.addr() % 16, 0);
+        assert_eq!(acc.size(), 3);
+        unsafe {
+            acc.write_mem_u8(2, 0x7f);
+            assert_eq!(acc.read_mem_u8(2), 0x7f);
+            assert_eq!(acc.try_read_mem_u16(2), Err(MemAccessTryError::OutOfBounds));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "OutOfBounds")]
+    fn generic_copy_is_checked() {
+        let acc = HeapAccessor::<u8>::new(8);
+        let src = [0u8; 16];
+        unsafe { MemAccessBase::copy_from_::<u8>(&acc, src.as_ptr(), 0, src.len()) };
+    }
+}
@@ -0,0 +1,611 @@
+use core::fmt;
+use core::mem::size_of;
+use core::ops::Range;
+
+use super::mem_accessor::{
+    for_each_access_type, MemAccess, MemAccessBase, MemAccessTryError, SubcloneAs,
+};
+
+/// アクセスの種類。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    Read,
+    Write,
+    /// `read_reg_*`（`offset` はレジスタ番号）
+    ReadReg,
+    /// `write_reg_*`（`offset` はレジスタ番号）
+    WriteReg,
+    /// `copy_to_*`（デバイス → ホスト）
+    CopyTo,
+    /// `copy_from_*`（ホスト → デバイス）
+    CopyFrom,
+}
+
+impl AccessKind {
+    pub fn is_write(self) -> bool {
+        matches!(self, Self::Write | Self::WriteReg | Self::CopyFrom)
+    }
+}
+
+impl fmt::Display for AccessKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::ReadReg => "read_reg",
+            Self::WriteReg => "write_reg",
+            Self::CopyTo => "copy_to",
+            Self::CopyFrom => "copy_from",
+        };
+        f.write_str(name)
+    }
+}
+
+/// フックに渡す値のビット列変換（符号付きはゼロ拡張、浮動小数点は `to_bits`）。
+pub trait AccessValue: Copy {
+    fn to_u64(self) -> u64;
+    fn from_u64(bits: u64) -> Self;
+}
+
+macro_rules! impl_access_value {
+    ($($t:ty => $u:ty),* $(,)?) => {
+        $(
+            impl AccessValue for $t {
+                fn to_u64(self) -> u64 {
+                    self as $u as u64
+                }
+                fn from_u64(bits: u64) -> Self {
+                    bits as $u as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_access_value!(
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => usize,
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize,
+);
+
+impl AccessValue for f32 {
+    fn to_u64(self) -> u64 {
+        self.to_bits() as u64
+    }
+    fn from_u64(bits: u64) -> Self {
+        f32::from_bits(bits as u32)
+    }
+}
+
+impl AccessValue for f64 {
+    fn to_u64(self) -> u64 {
+        self.to_bits()
+    }
+    fn from_u64(bits: u64) -> Self {
+        f64::from_bits(bits)
+    }
+}
+
+/// フックから見た 1 回のアクセス。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Access {
+    pub kind: AccessKind,
+    /// 呼び出し側が渡したオフセット（レジスタアクセスはレジスタ番号）
+    pub offset: usize,
+    /// 1 要素のバイト数
+    pub width: usize,
+    /// 要素数（コピー以外は 1）
+    pub count: usize,
+    /// 内側のアクセサの `phys_addr()`
+    pub phys_addr: usize,
+    /// ラップした時点の先頭から、このアクセサの先頭までのバイト数
+    pub base: usize,
+    /// レジスタ番号をバイトオフセットに換算するときの 1 レジスタのバイト数（不明なら `None`）
+    pub reg_size: Option<usize>,
+}
+
+impl Access {
+    pub fn is_write(&self) -> bool {
+        self.kind.is_write()
+    }
+
+    /// アクセスするバイト範囲（ラップした時点の先頭基準）。
+    ///
+    /// 換算がオーバーフローする場合と、レジスタアクセスで `reg_size` が不明な場合は `None`。
+    pub fn bytes(&self) -> Option<Range<usize>> {
+        let offset = match self.kind {
+            AccessKind::ReadReg | AccessKind::WriteReg => {
+                self.offset.checked_mul(self.reg_size?)?
+            }
+            _ => self.offset,
+        };
+        let start = self.base.checked_add(offset)?;
+        let end = start.checked_add(self.width.checked_mul(self.count)?)?;
+        Some(start..end)
+    }
+}
+
+/// `HookedAccessor` がアクセスの前後に呼ぶフック。
+///
+/// 記録・計測・障害注入・書き込み保護などのラッパーはこのトレイトだけを実装する。
+pub trait AccessHook {
+    /// `enter` から `exit` まで持ち回す状態
+    type Guard;
+
+    /// アクセスの前に呼ぶ。`Err` なら内側へは一切アクセスせず、
+    /// `try_*` はそのエラーを返し、それ以外は `refused` を呼ぶ。
+    fn enter(&self, access: &Access) -> Result<Self::Guard, MemAccessTryError>;
+
+    /// 内側へのアクセスの後に呼ぶ。
+    ///
+    /// `value` は読み書きした値（コピーは 0）、`data` は成功したコピーの転送データ。
+    fn exit(&self, access: &Access, guard: Self::Guard, value: u64, data: &[u8], ok: bool) {
+        let _ = (access, guard, value, data, ok);
+    }
+
+    /// `true` なら書き込む値・読み出した値を `write_value`/`read_value` に通す。
+    fn modifies(&self, guard: &Self::Guard) -> bool {
+        let _ = guard;
+        false
+    }
+
+    /// `index` 番目の要素として書き込む値。
+    fn write_value<V: AccessValue>(&self, guard: &Self::Guard, index: usize, value: V) -> V {
+        let _ = (guard, index);
+        value
+    }
+
+    /// `index` 番目の要素として返す値。
+    fn read_value<V: AccessValue>(&self, guard: &Self::Guard, index: usize, value: V) -> V {
+        let _ = (guard, index);
+        value
+    }
+
+    /// `try_*` 以外のアクセスを `enter` が拒否したとき。
+    fn refused(&self, access: &Access, error: MemAccessTryError) -> ! {
+        panic!(
+            "{} refused with {:?} (offset: 0x{:x})",
+            access.kind, error, access.offset
+        )
+    }
+}
+
+/// アクセスを制限しないフック。このフックの `HookedAccessor` は内側のアクセサを取り出せる。
+pub trait TransparentHook: AccessHook {}
+
+// 書き込む値を変えるフックで copy_from を小分けにする要素数
+const COPY_STAGE_LEN: usize = 16;
+
+/// 任意の `MemAccess` を包み、すべての読み書き・コピーを `AccessHook` に通すアクセサ。
+///
+/// - `addr`/`size`/`phys_addr` は内側の値を返す
+/// - `subclone`/`subclone8` 等はオフセットを積算し、同じフックを共有する
+/// - `reg_size` は内側の `MemAccessBase::reg_size()`、またはバス等では明示した値
+#[derive(Debug, Clone)]
+pub struct HookedAccessor<A, H> {
+    inner: A,
+    hook: H,
+    base: usize,
+    reg_size: Option<usize>,
+}
+
+impl<A: MemAccess, H: AccessHook> HookedAccessor<A, H> {
+    pub fn with_hook(inner: A, hook: H) -> Self
+    where
+        A: MemAccessBase,
+    {
+        Self::from_parts(inner, hook, Some(A::reg_size()))
+    }
+
+    /// `reg_size` を明示する（`MemAccessBase` を持たないバスアクセサ等）。
+    pub fn with_hook_and_reg_size(inner: A, hook: H, reg_size: usize) -> Self {
+        Self::from_parts(inner, hook, Some(reg_size))
+    }
+
+    pub(crate) fn from_parts(inner: A, hook: H, reg_size: Option<usize>) -> Self {
+        Self {
+            inner,
+            hook,
+            base: 0,
+            reg_size,
+        }
+    }
+
+    pub fn hook(&self) -> &H {
+        &self.hook
+    }
+
+    pub fn reg_size(&self) -> Option<usize> {
+        self.reg_size
+    }
+
+    pub(crate) fn base(&self) -> usize {
+        self.base
+    }
+
+    pub(crate) fn inner_ref(&self) -> &A {
+        &self.inner
+    }
+
+    fn access<V>(&self, kind: AccessKind, offset: usize, count: usize) -> Access {
+        Access {
+            kind,
+            offset,
+            width: size_of::<V>(),
+            count,
+            phys_addr: self.inner.phys_addr(),
+            base: self.base,
+            reg_size: self.reg_size,
+        }
+    }
+
+    fn enter(&self, access: &Access) -> H::Guard {
+        self.hook
+            .enter(access)
+            .unwrap_or_else(|error| self.hook.refused(access, error))
+    }
+
+    unsafe fn read_values<V: AccessValue>(&self, guard: &H::Guard, ptr: *mut V, count: usize) {
+        if self.hook.modifies(guard) {
+            for index in 0..count {
+                let ptr = unsafe { ptr.add(index) };
+                let value = self
+                    .hook
+                    .read_value(guard, index, unsafe { ptr.read_unaligned() });
+                unsafe { ptr.write_unaligned(value) };
+            }
+        }
+    }
+
+    // 値を変えるフックでは、変えた値を小分けにスタックへ写してから内側へ渡す
+    unsafe fn write_values<V: AccessValue>(
+        &self,
+        guard: &H::Guard,
+        src_ptr: *const V,
+        dst_adr: usize,
+        count: usize,
+        mut copy: impl FnMut(*const V, usize, usize) -> Result<(), MemAccessTryError>,
+    ) -> Result<(), MemAccessTryError> {
+        if !self.hook.modifies(guard) {
+            return copy(src_ptr, dst_adr, count);
+        }
+        let mut stage = [V::from_u64(0); COPY_STAGE_LEN];
+        let mut done = 0;
+        while done < count {
+            let len = COPY_STAGE_LEN.min(count - done);
+            for (i, value) in stage[..len].iter_mut().enumerate() {
+                let src = unsafe { src_ptr.add(done + i).read_unaligned() };
+                *value = self.hook.write_value(guard, done + i, src);
+            }
+            copy(stage.as_ptr(), dst_adr + done * size_of::<V>(), len)?;
+            done += len;
+        }
+        Ok(())
+    }
+
+    fn wrap<B>(&self, offset: usize, inner: B, reg_size: Option<usize>) -> HookedAccessor<B, H>
+    where
+        H: Clone,
+    {
+        HookedAccessor {
+            inner,
+            hook: self.hook.clone(),
+            base: self.base + offset,
+            reg_size,
+        }
+    }
+}
+
+impl<A: MemAccess, H: TransparentHook> HookedAccessor<A, H> {
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+}
+
+impl<A: MemAccess + MemAccessBase, H: AccessHook + Clone> HookedAccessor<A, H> {
+    pub fn subclone(&self, offset: usize, size: usize) -> Self {
+        self.wrap(offset, self.inner.subclone(offset, size), self.reg_size)
+    }
+
+    pub fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        Ok(self.wrap(
+            offset,
+            self.inner.try_subclone(offset, size)?,
+            self.reg_size,
+        ))
+    }
+}
+
+impl<A: MemAccess + SubcloneAs, H: AccessHook + Clone> HookedAccessor<A, H> {
+    pub fn subclone_<NewU>(&self, offset: usize, size: usize) -> HookedAccessor<A::As<NewU>, H> {
+        let reg_size = self
+            .reg_size
+            .map(|_| <A::As<NewU> as MemAccessBase>::reg_size());
+        self.wrap(
+            offset,
+            self.inner.subclone_as::<NewU>(offset, size),
+            reg_size,
+        )
+    }
+
+    pub fn try_subclone_<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<HookedAccessor<A::As<NewU>, H>, MemAccessTryError> {
+        let reg_size = self
+            .reg_size
+            .map(|_| <A::As<NewU> as MemAccessBase>::reg_size());
+        let inner = self.inner.try_subclone_as::<NewU>(offset, size)?;
+        Ok(self.wrap(offset, inner, reg_size))
+    }
+
+    pub fn subclone8(&self, offset: usize, size: usize) -> HookedAccessor<A::As<u8>, H> {
+        self.subclone_::<u8>(offset, size)
+    }
+
+    pub fn subclone16(&self, offset: usize, size: usize) -> HookedAccessor<A::As<u16>, H> {
+        self.subclone_::<u16>(offset, size)
+    }
+
+    pub fn subclone32(&self, offset: usize, size: usize) -> HookedAccessor<A::As<u32>, H> {
+        self.subclone_::<u32>(offset, size)
+    }
+
+    pub fn subclone64(&self, offset: usize, size: usize) -> HookedAccessor<A::As<u64>, H> {
+        self.subclone_::<u64>(offset, size)
+    }
+
+    pub fn try_subclone8(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<HookedAccessor<A::As<u8>, H>, MemAccessTryError> {
+        self.try_subclone_::<u8>(offset, size)
+    }
+
+    pub fn try_subclone16(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<HookedAccessor<A::As<u16>, H>, MemAccessTryError> {
+        self.try_subclone_::<u16>(offset, size)
+    }
+
+    pub fn try_subclone32(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<HookedAccessor<A::As<u32>, H>, MemAccessTryError> {
+        self.try_subclone_::<u32>(offset, size)
+    }
+
+    pub fn try_subclone64(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<HookedAccessor<A::As<u64>, H>, MemAccessTryError> {
+        self.try_subclone_::<u64>(offset, size)
+    }
+}
+
+// 成功したコピーの転送データ
+unsafe fn copy_bytes<'a, V>(ptr: *const V, count: usize) -> &'a [u8] {
+    if count == 0 {
+        &[]
+    } else {
+        unsafe { core::slice::from_raw_parts(ptr as *const u8, count * size_of::<V>()) }
+    }
+}
+
+macro_rules! hooked_copy_fns {
+    ($t:ty, $copy_to:ident, $try_copy_to:ident, $copy_from:ident, $try_copy_from:ident) => {
+        unsafe fn $copy_to(&self, src_adr: usize, dst_ptr: *mut $t, count: usize) {
+            let access = self.access::<$t>(AccessKind::CopyTo, src_adr, count);
+            let guard = self.enter(&access);
+            unsafe {
+                self.inner.$copy_to(src_adr, dst_ptr, count);
+                self.read_values(&guard, dst_ptr, count);
+                let data = copy_bytes(dst_ptr, count);
+                self.hook.exit(&access, guard, 0, data, true);
+            }
+        }
+        unsafe fn $try_copy_to(
+            &self,
+            src_adr: usize,
+            dst_ptr: *mut $t,
+            count: usize,
+        ) -> Result<(), MemAccessTryError> {
+            let access = self.access::<$t>(AccessKind::CopyTo, src_adr, count);
+            let guard = self.hook.enter(&access)?;
+            let result = unsafe { self.inner.$try_copy_to(src_adr, dst_ptr, count) };
+            let data = match result {
+                Ok(()) => unsafe {
+                    self.read_values(&guard, dst_ptr, count);
+                    copy_bytes(dst_ptr, count)
+                },
+                Err(_) => &[],
+            };
+            self.hook.exit(&access, guard, 0, data, result.is_ok());
+            result
+        }
+        unsafe fn $copy_from(&self, src_ptr: *const $t, dst_adr: usize, count: usize) {
+            let access = self.access::<$t>(AccessKind::CopyFrom, dst_adr, count);
+            let guard = self.enter(&access);
+            unsafe {
+                let _ = self.write_values(&guard, src_ptr, dst_adr, count, |src, dst, len| {
+                    self.inner.$copy_from(src, dst, len);
+                    Ok(())
+                });
+                let data = copy_bytes(src_ptr, count);
+                self.hook.exit(&access, guard, 0, data, true);
+            }
+        }
+        unsafe fn $try_copy_from(
+            &self,
+            src_ptr: *const $t,
+            dst_adr: usize,
+            count: usize,
+        ) -> Result<(), MemAccessTryError> {
+            let access = self.access::<$t>(AccessKind::CopyFrom, dst_adr, count);
+            let guard = self.hook.enter(&access)?;
+            let result = unsafe {
+                self.write_values(&guard, src_ptr, dst_adr, count, |src, dst, len| {
+                    self.inner.$try_copy_from(src, dst, len)
+                })
+            };
+            let data = match result {
+                Ok(()) => unsafe { copy_bytes(src_ptr, count) },
+                Err(_) => &[],
+            };
+            self.hook.exit(&access, guard, 0, data, result.is_ok());
+            result
+        }
+    };
+}
+
+macro_rules! hooked_rw_fns {
+    ($t:ty, $write_kind:ident, $write:ident, $try_write:ident, $read_kind:ident, $read:ident, $try_read:ident) => {
+        unsafe fn $write(&self, offset: usize, data: $t) {
+            let access = self.access::<$t>(AccessKind::$write_kind, offset, 1);
+            let guard = self.enter(&access);
+            let data = self.hook.write_value(&guard, 0, data);
+            unsafe { self.inner.$write(offset, data) };
+            self.hook.exit(&access, guard, data.to_u64(), &[], true);
+        }
+        unsafe fn $try_write(&self, offset: usize, data: $t) -> Result<(), MemAccessTryError> {
+            let access = self.access::<$t>(AccessKind::$write_kind, offset, 1);
+            let guard = self.hook.enter(&access)?;
+            let data = self.hook.write_value(&guard, 0, data);
+            let result = unsafe { self.inner.$try_write(offset, data) };
+            self.hook
+                .exit(&access, guard, data.to_u64(), &[], result.is_ok());
+            result
+        }
+        unsafe fn $read(&self, offset: usize) -> $t {
+            let access = self.access::<$t>(AccessKind::$read_kind, offset, 1);
+            let guard = self.enter(&access);
+            let data = self
+                .hook
+                .read_value(&guard, 0, unsafe { self.inner.$read(offset) });
+            self.hook.exit(&access, guard, data.to_u64(), &[], true);
+            data
+        }
+        unsafe fn $try_read(&self, offset: usize) -> Result<$t, MemAccessTryError> {
+            let access = self.access::<$t>(AccessKind::$read_kind, offset, 1);
+            let guard = self.hook.enter(&access)?;
+            let result = unsafe { self.inner.$try_read(offset) }
+                .map(|data| self.hook.read_value(&guard, 0, data));
+            let value = result.map_or(0, |data| data.to_u64());
+            self.hook.exit(&access, guard, value, &[], result.is_ok());
+            result
+        }
+    };
+}
+
+impl<A: MemAccess, H: AccessHook> MemAccess for HookedAccessor<A, H> {
+    fn addr(&self) -> usize {
+        self.inner.addr()
+    }
+    fn size(&self) -> usize {
+        self.inner.size()
+    }
+    fn phys_addr(&self) -> usize {
+        self.inner.phys_addr()
+    }
+
+    for_each_access_type!(hooked_copy_fns, hooked_rw_fns);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MmioAccessor;
+
+    #[test]
+    fn reg_bytes_are_checked() {
+        let access = Access {
+            kind: AccessKind::WriteReg,
+            offset: usize::MAX / 2,
+            width: 4,
+            count: 1,
+            phys_addr: 0,
+            base: 0,
+            reg_size: Some(4),
+        };
+        assert_eq!(access.bytes(), None);
+        let access = Access {
+            offset: 3,
+            base: 0x10,
+            ..access
+        };
+        assert_eq!(access.bytes(), Some(0x1c..0x20));
+        assert_eq!(
+            Access {
+                reg_size: None,
+                ..access
+            }
+            .bytes(),
+            None
+        );
+    }
+
+    // 書き込みは値を反転し、読み出しは 1 を足すフック
+    #[derive(Debug, Clone)]
+    struct Twiddle;
+
+    impl AccessHook for Twiddle {
+        type Guard = ();
+
+        fn enter(&self, _access: &Access) -> Result<(), MemAccessTryError> {
+            Ok(())
+        }
+        fn modifies(&self, _guard: &()) -> bool {
+            true
+        }
+        fn write_value<V: AccessValue>(&self, _guard: &(), _index: usize, value: V) -> V {
+            V::from_u64(!value.to_u64())
+        }
+        fn read_value<V: AccessValue>(&self, _guard: &(), _index: usize, value: V) -> V {
+            V::from_u64(value.to_u64().wrapping_add(1))
+        }
+    }
+
+    #[test]
+    fn modifying_hook_and_typed_subclone() {
+        let mut buf = [0u8; 64];
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 64);
+        let acc = HookedAccessor::with_hook(mmio.clone(), Twiddle);
+        assert_eq!(acc.reg_size(), Some(4));
+
+        let src: [u8; 40] = core::array::from_fn(|i| i as u8);
+        let sub = acc.subclone8(0x10, 0);
+        assert_eq!(sub.reg_size(), Some(1));
+        assert_eq!(sub.base(), 0x10);
+        unsafe {
+            sub.copy_from_u8(src.as_ptr(), 0, src.len());
+            assert_eq!(mmio.read_mem_u8(0x10 + 39), !39);
+            let mut dst = [0u8; 40];
+            sub.copy_to_u8(0, dst.as_mut_ptr(), dst.len());
+            assert!(dst
+                .iter()
+                .enumerate()
+                .all(|(i, &v)| v == (!(i as u8)).wrapping_add(1)));
+            acc.write_reg_u32(1, 0);
+            assert_eq!(mmio.read_mem_u32(4), u32::MAX);
+        }
+
+        assert_eq!(
+            acc.try_subclone(0x40, 0).unwrap_err(),
+            MemAccessTryError::OutOfBounds
+        );
+        assert_eq!(sub.try_subclone32(0x8, 0x4).unwrap().base(), 0x18);
+        assert_eq!(
+            sub.try_subclone16(0x30, 0x4).unwrap_err(),
+            MemAccessTryError::OutOfBounds
+        );
+    }
+}
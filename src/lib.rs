@@ -15,11 +15,81 @@ pub use mmio_accessor::*;
 pub mod bus_accessor;
 pub use bus_accessor::*;
 
+pub mod mem_access_bus;
+pub use mem_access_bus::*;
+
+pub mod bus_lock;
+pub use bus_lock::*;
+
+pub mod locked_bus_accessor;
+pub use locked_bus_accessor::*;
+
+pub mod desc_ring;
+pub use desc_ring::*;
+
+pub mod axi_dma;
+pub use axi_dma::*;
+
+pub mod axi_vdma;
+pub use axi_vdma::*;
+
+pub mod hooked_accessor;
+pub use hooked_accessor::*;
+
+pub mod protected_accessor;
+pub use protected_accessor::*;
+
+pub mod sub_accessor;
+pub use sub_accessor::*;
+
+pub mod slice_accessor;
+pub use slice_accessor::*;
+
 #[cfg(feature = "std")]
 pub mod shared_bus_accessor;
 #[cfg(feature = "std")]
 pub use shared_bus_accessor::*;
 
+#[cfg(feature = "std")]
+pub mod bus_router;
+#[cfg(feature = "std")]
+pub use bus_router::*;
+
+#[cfg(feature = "std")]
+pub mod ram_bus;
+#[cfg(feature = "std")]
+pub use ram_bus::*;
+
+#[cfg(feature = "std")]
+pub mod register_model;
+#[cfg(feature = "std")]
+pub use register_model::*;
+
+#[cfg(feature = "std")]
+pub mod tracing_accessor;
+#[cfg(feature = "std")]
+pub use tracing_accessor::*;
+
+#[cfg(feature = "std")]
+pub mod access_session;
+#[cfg(feature = "std")]
+pub use access_session::*;
+
+#[cfg(feature = "std")]
+pub mod fault_injection;
+#[cfg(feature = "std")]
+pub use fault_injection::*;
+
+#[cfg(feature = "std")]
+pub mod profiling_accessor;
+#[cfg(feature = "std")]
+pub use profiling_accessor::*;
+
+#[cfg(feature = "std")]
+pub mod heap_accessor;
+#[cfg(feature = "std")]
+pub use heap_accessor::*;
+
 #[cfg(all(feature = "std", unix))]
 pub mod mmap_accessor;
 #[cfg(all(feature = "std", unix))]
@@ -35,6 +105,11 @@ pub mod udmabuf_accessor;
 #[cfg(all(feature = "std", unix))]
 pub use udmabuf_accessor::*;
 
+#[cfg(feature = "std")]
+pub mod dma_pool;
+#[cfg(feature = "std")]
+pub use dma_pool::*;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -59,6 +134,82 @@ mod tests {
         }
     }
 
+    #[test]
+    fn mmio_try_subclone() {
+        let mut buf: [u32; 4] = [0; 4];
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 16);
+
+        let sub = mmio.try_subclone8(4, 8).unwrap();
+        assert_eq!(sub.addr(), mmio.addr() + 4);
+        assert_eq!(sub.size(), 8);
+        assert_eq!(mmio.try_subclone32(8, 0).unwrap().size(), 8);
+
+        assert_eq!(
+            mmio.try_subclone32(8, 16).unwrap_err(),
+            MemAccessTryError::OutOfBounds
+        );
+        assert_eq!(
+            mmio.try_subclone32(32, 0).unwrap_err(),
+            MemAccessTryError::OutOfBounds
+        );
+        // An offset exactly at the end is rejected, as on the bus side
+        // (only a whole clone of an empty parent is allowed)
+        assert_eq!(
+            mmio.try_subclone32(16, 0).unwrap_err(),
+            MemAccessTryError::OutOfBounds
+        );
+        let empty = MmioAccessor::<u32>::new(mmio.addr(), 0);
+        assert_eq!(empty.try_subclone(0, 0).unwrap().size(), 0);
+        assert_eq!(
+            mmio.try_subclone32(4, usize::MAX).unwrap_err(),
+            MemAccessTryError::AddressOverflow
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "subclone out of range")]
+    fn mmio_subclone_out_of_range() {
+        let mmio = MmioAccessor::<u32>::new(0x1000, 16);
+        let _ = mmio.subclone(8, 16);
+    }
+
+    #[test]
+    fn phys_try_subclone() {
+        let phys = PhysAccessor::<u32, 0x1000, 0x100>::new();
+        assert!(phys.try_subclone(0, 0).is_ok());
+        assert!(phys.try_subclone(0, 0x100).is_ok());
+        assert_eq!(
+            phys.try_subclone(0x10, 0x10).unwrap_err(),
+            MemAccessTryError::AddressOutOfRange
+        );
+        assert_eq!(
+            phys.try_subclone(0x80, 0x100).unwrap_err(),
+            MemAccessTryError::OutOfBounds
+        );
+    }
+
+    #[test]
+    fn phys_const_sub() {
+        let phys = PhysAccessor::<u32, 0x1000, 0x100>::new();
+
+        let uart = phys.sub::<0x40, 0x20, 0x1040>();
+        assert_eq!(uart.addr(), 0x1040);
+        assert_eq!(uart.size(), 0x20);
+
+        let tail = phys.sub_::<u8, 0xf0, 0x10, 0x10f0>();
+        assert_eq!(tail.addr(), 0x10f0);
+        assert_eq!(tail.size(), 0x10);
+
+        let reg = tail.sub::<0xc, 4, 0x10fc>();
+        assert_eq!(reg.addr(), 0x10fc);
+
+        // absolute-address form
+        let at: PhysAccessor<u32, 0x1040, 0x20> = phys.sub_at();
+        assert_eq!(at.addr(), uart.addr());
+        let at = phys.sub_at_::<u16, 0x10f8, 8>();
+        assert_eq!(at.size(), 8);
+    }
+
     /*
     #[test]
     fn uio_access() {
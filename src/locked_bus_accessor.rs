@@ -0,0 +1,777 @@
+#![allow(dead_code)]
+
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use super::bus_accessor::{
+    read_slice_burst, read_value_lanes, subregion_range, write_slice_burst, write_value_lanes, Bus,
+    BusAccessorConfig, BusAccessorError, BusAddress, BusValue, BusWord, Endianness,
+};
+use super::bus_lock::BusLock;
+use super::mem_accessor::expect_subclone;
+use super::{MemAccess, MemAccessTryError};
+
+/// `SharedBusAccessor` の no_std 版。ロック方式 `BusLock` と共有方法 `P` を差し替えられる。
+///
+/// - `P`: ロックへの共有ハンドル（`&'static L`、`Rc<L>`、`Arc<L>` など `Deref + Clone`）
+/// - `P::Target`: `BusLock<B>` を実装したロック（`RefCell`、`SpinLock`、`Mutex` など）
+/// - `base`/`size`/`subclone`/`MemAccess` の振る舞いは `SharedBusAccessor` と同じ
+/// - ロックを取れない場合（`RefCell` の再入など）は `BusAccessorError::Busy`
+#[derive(Debug)]
+pub struct LockedBusAccessor<P, B, A, D, S, E>
+where
+    P: Deref,
+    P::Target: BusLock<B>,
+{
+    bus: P,
+    base: usize,
+    size: usize,
+    config: BusAccessorConfig,
+    _phantom: PhantomData<(B, A, D, S, E)>,
+}
+
+// ハンドル P を clone するだけなので B: Clone は不要
+impl<P, B, A, D, S, E> Clone for LockedBusAccessor<P, B, A, D, S, E>
+where
+    P: Deref + Clone,
+    P::Target: BusLock<B>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            bus: self.bus.clone(),
+            base: self.base,
+            size: self.size,
+            config: self.config,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<P, B, A, D, S, E> LockedBusAccessor<P, B, A, D, S, E>
+where
+    P: Deref,
+    P::Target: BusLock<B>,
+    B: Bus<A, D, S>,
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+    E: Endianness,
+{
+    /// ロック済みバスへのハンドル `bus` から作る。base=0、size=無制限。
+    pub fn new(bus: P) -> Self {
+        Self::new_with_range(bus, 0, 0)
+    }
+
+    /// ロック済みバスへのハンドル `bus` から指定の `base`/`size` で作る。
+    pub fn new_with_range(bus: P, base: usize, size: usize) -> Self {
+        Self {
+            bus,
+            base,
+            size,
+            config: BusAccessorConfig::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// このアクセサの先頭アドレス（バス絶対アドレス）を返す。
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    /// このアクセサのリージョンサイズを返す（0 = 無制限）。
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// バースト設定を返す。
+    pub fn config(&self) -> &BusAccessorConfig {
+        &self.config
+    }
+
+    /// バースト設定を変更する（このハンドルと、以降の `subclone` にのみ反映）。
+    pub fn set_config(&mut self, config: BusAccessorConfig) {
+        self.config = config;
+    }
+
+    /// 共有ハンドルを返す。
+    pub fn handle(&self) -> &P {
+        &self.bus
+    }
+
+    /// アクセサを分解して共有ハンドルを返す。
+    pub fn into_handle(self) -> P {
+        self.bus
+    }
+
+    /// エンディアン型パラメータを変えてサブクローンを作る汎用版。
+    ///
+    /// - `offset`: このアクセサ先頭からのバイトオフセット
+    /// - `size`: サブリージョンのサイズ（0 = 残り全部）
+    /// - 範囲外・オーバーフローは panic する（`try_subclone_` はエラーを返す）
+    pub fn subclone_<NewE: Endianness>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> LockedBusAccessor<P, B, A, D, S, NewE>
+    where
+        P: Clone,
+    {
+        expect_subclone(
+            self.try_subclone_::<NewE>(offset, size)
+                .map_err(map_bus_err),
+            offset,
+            size,
+        )
+    }
+
+    /// 範囲を検査してサブクローンを作る（エラーは `SharedBusAccessor::try_subclone_` と同じ）。
+    #[allow(clippy::type_complexity)]
+    pub fn try_subclone_<NewE: Endianness>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<LockedBusAccessor<P, B, A, D, S, NewE>, BusAccessorError<B::Error>>
+    where
+        P: Clone,
+    {
+        let (base, size) = subregion_range(self.base, self.size, offset, size)?;
+        Ok(LockedBusAccessor {
+            bus: self.bus.clone(),
+            base,
+            size,
+            config: self.config,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// 同じエンディアンでサブクローンを作る。
+    pub fn subclone(&self, offset: usize, size: usize) -> Self
+    where
+        P: Clone,
+    {
+        self.subclone_::<E>(offset, size)
+    }
+
+    /// 同じエンディアンで範囲を検査してサブクローンを作る。
+    pub fn try_subclone(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<Self, BusAccessorError<B::Error>>
+    where
+        P: Clone,
+    {
+        self.try_subclone_::<E>(offset, size)
+    }
+
+    // ロックを取って f を呼ぶ。取れなければ Busy
+    fn with_bus<R>(
+        &self,
+        f: impl FnOnce(&mut B) -> Result<R, BusAccessorError<B::Error>>,
+    ) -> Result<R, BusAccessorError<B::Error>> {
+        self.bus.with_lock(f).unwrap_or(Err(BusAccessorError::Busy))
+    }
+
+    // -----------------------------------------------------------------------
+    //  コアアクセス
+    // -----------------------------------------------------------------------
+
+    /// 任意の `BusValue` 型を `offset` バイト位置に書き込む。
+    /// データ幅より小さい場合は strb で部分書き込み、大きい場合は複数アクセスに分割する。
+    pub fn write_value<V: BusValue>(
+        &self,
+        offset: usize,
+        value: V,
+    ) -> Result<(), BusAccessorError<B::Error>> {
+        let abs_addr = self.check_slice::<V>(offset, 1)?;
+        self.with_bus(|bus| {
+            write_value_lanes::<B, A, D, S, E, V>(bus, &self.config, abs_addr, value)
+        })
+    }
+
+    /// 任意の `BusValue` 型を `offset` バイト位置から読み込む。
+    /// データ幅より小さい/大きい場合の処理は `write_value` と対称。
+    pub fn read_value<V: BusValue>(&self, offset: usize) -> Result<V, BusAccessorError<B::Error>> {
+        let abs_addr = self.check_slice::<V>(offset, 1)?;
+        self.with_bus(|bus| read_value_lanes::<B, A, D, S, E, V>(bus, &self.config, abs_addr))
+    }
+
+    // -----------------------------------------------------------------------
+    //  型付きアクセス便利メソッド
+    // -----------------------------------------------------------------------
+
+    pub fn write_u8(&self, offset: usize, value: u8) -> Result<(), BusAccessorError<B::Error>> {
+        self.write_value(offset, value)
+    }
+
+    pub fn write_u16(&self, offset: usize, value: u16) -> Result<(), BusAccessorError<B::Error>> {
+        self.write_value(offset, value)
+    }
+
+    pub fn write_u32(&self, offset: usize, value: u32) -> Result<(), BusAccessorError<B::Error>> {
+        self.write_value(offset, value)
+    }
+
+    pub fn write_u64(&self, offset: usize, value: u64) -> Result<(), BusAccessorError<B::Error>> {
+        self.write_value(offset, value)
+    }
+
+    pub fn write_usize(
+        &self,
+        offset: usize,
+        value: usize,
+    ) -> Result<(), BusAccessorError<B::Error>> {
+        self.write_value(offset, value)
+    }
+
+    pub fn read_u8(&self, offset: usize) -> Result<u8, BusAccessorError<B::Error>> {
+        self.read_value(offset)
+    }
+
+    pub fn read_u16(&self, offset: usize) -> Result<u16, BusAccessorError<B::Error>> {
+        self.read_value(offset)
+    }
+
+    pub fn read_u32(&self, offset: usize) -> Result<u32, BusAccessorError<B::Error>> {
+        self.read_value(offset)
+    }
+
+    pub fn read_u64(&self, offset: usize) -> Result<u64, BusAccessorError<B::Error>> {
+        self.read_value(offset)
+    }
+
+    pub fn read_usize(&self, offset: usize) -> Result<usize, BusAccessorError<B::Error>> {
+        self.read_value(offset)
+    }
+
+    // -----------------------------------------------------------------------
+    //  一括転送
+    // -----------------------------------------------------------------------
+
+    /// `src` を `offset` から連続して書き込む。ロックは全体で 1 回、転送はバーストにまとめる。
+    pub fn write_slice<V: BusValue>(
+        &self,
+        offset: usize,
+        src: &[V],
+    ) -> Result<(), BusAccessorError<B::Error>> {
+        let abs_base = self.check_slice::<V>(offset, src.len())?;
+        self.with_bus(|bus| write_slice_burst::<B, A, D, S, E, V>(bus, &self.config, abs_base, src))
+    }
+
+    /// `offset` から `dst.len()` 個の値を連続して読み込む。
+    pub fn read_slice<V: BusValue>(
+        &self,
+        offset: usize,
+        dst: &mut [V],
+    ) -> Result<(), BusAccessorError<B::Error>> {
+        let abs_base = self.check_slice::<V>(offset, dst.len())?;
+        self.with_bus(|bus| read_slice_burst::<B, A, D, S, E, V>(bus, &self.config, abs_base, dst))
+    }
+
+    fn check_slice<V: BusValue>(
+        &self,
+        offset: usize,
+        count: usize,
+    ) -> Result<usize, BusAccessorError<B::Error>> {
+        let bytes = count
+            .checked_mul(V::BYTES)
+            .ok_or(BusAccessorError::AddressOverflow)?;
+        if self.size != 0 {
+            let end = offset
+                .checked_add(bytes)
+                .ok_or(BusAccessorError::AddressOverflow)?;
+            if end > self.size {
+                return Err(BusAccessorError::OutOfBounds);
+            }
+        }
+        self.base
+            .checked_add(offset)
+            .ok_or(BusAccessorError::AddressOverflow)
+    }
+
+    unsafe fn write_raw<V: BusValue>(&self, dst_adr: usize, src_ptr: *const V, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let src = unsafe { core::slice::from_raw_parts(src_ptr, count) };
+        self.write_slice(dst_adr, src).map_err(map_bus_err).unwrap();
+    }
+
+    unsafe fn read_raw<V: BusValue>(&self, src_adr: usize, dst_ptr: *mut V, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let dst = unsafe { core::slice::from_raw_parts_mut(dst_ptr, count) };
+        self.read_slice(src_adr, dst).map_err(map_bus_err).unwrap();
+    }
+}
+
+fn map_bus_err<E>(err: BusAccessorError<E>) -> MemAccessTryError {
+    match err {
+        BusAccessorError::AddressOverflow => MemAccessTryError::AddressOverflow,
+        BusAccessorError::AddressOutOfRange => MemAccessTryError::AddressOutOfRange,
+        BusAccessorError::OutOfBounds => MemAccessTryError::OutOfBounds,
+        BusAccessorError::StrbTooNarrow => MemAccessTryError::StrbTooNarrow,
+        BusAccessorError::ReadSideEffects => MemAccessTryError::ReadSideEffects,
+        BusAccessorError::Busy => MemAccessTryError::Busy,
+        BusAccessorError::Bus(_) => MemAccessTryError::AccessFault,
+    }
+}
+
+impl<P, B, A, D, S, E> MemAccess for LockedBusAccessor<P, B, A, D, S, E>
+where
+    P: Deref,
+    P::Target: BusLock<B>,
+    B: Bus<A, D, S>,
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+    E: Endianness,
+{
+    fn addr(&self) -> usize {
+        self.base
+    }
+    fn size(&self) -> usize {
+        self.size
+    }
+    fn phys_addr(&self) -> usize {
+        self.base
+    }
+
+    unsafe fn copy_to_usize(&self, src_adr: usize, dst_ptr: *mut usize, count: usize) {
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
+    }
+    unsafe fn copy_to_u8(&self, src_adr: usize, dst_ptr: *mut u8, count: usize) {
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
+    }
+    unsafe fn copy_to_u16(&self, src_adr: usize, dst_ptr: *mut u16, count: usize) {
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
+    }
+    unsafe fn copy_to_u32(&self, src_adr: usize, dst_ptr: *mut u32, count: usize) {
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
+    }
+    unsafe fn copy_to_u64(&self, src_adr: usize, dst_ptr: *mut u64, count: usize) {
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
+    }
+    unsafe fn copy_to_isize(&self, src_adr: usize, dst_ptr: *mut isize, count: usize) {
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
+    }
+    unsafe fn copy_to_i8(&self, src_adr: usize, dst_ptr: *mut i8, count: usize) {
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
+    }
+    unsafe fn copy_to_i16(&self, src_adr: usize, dst_ptr: *mut i16, count: usize) {
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
+    }
+    unsafe fn copy_to_i32(&self, src_adr: usize, dst_ptr: *mut i32, count: usize) {
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
+    }
+    unsafe fn copy_to_i64(&self, src_adr: usize, dst_ptr: *mut i64, count: usize) {
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
+    }
+    unsafe fn copy_to_f32(&self, src_adr: usize, dst_ptr: *mut f32, count: usize) {
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
+    }
+    unsafe fn copy_to_f64(&self, src_adr: usize, dst_ptr: *mut f64, count: usize) {
+        unsafe { self.read_raw(src_adr, dst_ptr, count) }
+    }
+
+    unsafe fn copy_from_usize(&self, src_ptr: *const usize, dst_adr: usize, count: usize) {
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
+    }
+    unsafe fn copy_from_u8(&self, src_ptr: *const u8, dst_adr: usize, count: usize) {
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
+    }
+    unsafe fn copy_from_u16(&self, src_ptr: *const u16, dst_adr: usize, count: usize) {
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
+    }
+    unsafe fn copy_from_u32(&self, src_ptr: *const u32, dst_adr: usize, count: usize) {
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
+    }
+    unsafe fn copy_from_u64(&self, src_ptr: *const u64, dst_adr: usize, count: usize) {
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
+    }
+    unsafe fn copy_from_isize(&self, src_ptr: *const isize, dst_adr: usize, count: usize) {
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
+    }
+    unsafe fn copy_from_i8(&self, src_ptr: *const i8, dst_adr: usize, count: usize) {
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
+    }
+    unsafe fn copy_from_i16(&self, src_ptr: *const i16, dst_adr: usize, count: usize) {
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
+    }
+    unsafe fn copy_from_i32(&self, src_ptr: *const i32, dst_adr: usize, count: usize) {
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
+    }
+    unsafe fn copy_from_i64(&self, src_ptr: *const i64, dst_adr: usize, count: usize) {
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
+    }
+    unsafe fn copy_from_f32(&self, src_ptr: *const f32, dst_adr: usize, count: usize) {
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
+    }
+    unsafe fn copy_from_f64(&self, src_ptr: *const f64, dst_adr: usize, count: usize) {
+        unsafe { self.write_raw(dst_adr, src_ptr, count) }
+    }
+
+    unsafe fn write_mem(&self, offset: usize, data: usize) {
+        unsafe { self.try_write_mem(offset, data) }.unwrap();
+    }
+    unsafe fn write_mem_usize(&self, offset: usize, data: usize) {
+        unsafe { self.try_write_mem_usize(offset, data) }.unwrap();
+    }
+    unsafe fn write_mem_u8(&self, offset: usize, data: u8) {
+        unsafe { self.try_write_mem_u8(offset, data) }.unwrap();
+    }
+    unsafe fn write_mem_u16(&self, offset: usize, data: u16) {
+        unsafe { self.try_write_mem_u16(offset, data) }.unwrap();
+    }
+    unsafe fn write_mem_u32(&self, offset: usize, data: u32) {
+        unsafe { self.try_write_mem_u32(offset, data) }.unwrap();
+    }
+    unsafe fn write_mem_u64(&self, offset: usize, data: u64) {
+        unsafe { self.try_write_mem_u64(offset, data) }.unwrap();
+    }
+    unsafe fn write_mem_isize(&self, offset: usize, data: isize) {
+        unsafe { self.try_write_mem_isize(offset, data) }.unwrap();
+    }
+    unsafe fn write_mem_i8(&self, offset: usize, data: i8) {
+        unsafe { self.try_write_mem_i8(offset, data) }.unwrap();
+    }
+    unsafe fn write_mem_i16(&self, offset: usize, data: i16) {
+        unsafe { self.try_write_mem_i16(offset, data) }.unwrap();
+    }
+    unsafe fn write_mem_i32(&self, offset: usize, data: i32) {
+        unsafe { self.try_write_mem_i32(offset, data) }.unwrap();
+    }
+    unsafe fn write_mem_i64(&self, offset: usize, data: i64) {
+        unsafe { self.try_write_mem_i64(offset, data) }.unwrap();
+    }
+    unsafe fn write_mem_f32(&self, offset: usize, data: f32) {
+        unsafe { self.try_write_mem_f32(offset, data) }.unwrap();
+    }
+    unsafe fn write_mem_f64(&self, offset: usize, data: f64) {
+        unsafe { self.try_write_mem_f64(offset, data) }.unwrap();
+    }
+
+    unsafe fn read_mem(&self, offset: usize) -> usize {
+        unsafe { self.try_read_mem(offset) }.unwrap()
+    }
+    unsafe fn read_mem_usize(&self, offset: usize) -> usize {
+        unsafe { self.try_read_mem_usize(offset) }.unwrap()
+    }
+    unsafe fn read_mem_u8(&self, offset: usize) -> u8 {
+        unsafe { self.try_read_mem_u8(offset) }.unwrap()
+    }
+    unsafe fn read_mem_u16(&self, offset: usize) -> u16 {
+        unsafe { self.try_read_mem_u16(offset) }.unwrap()
+    }
+    unsafe fn read_mem_u32(&self, offset: usize) -> u32 {
+        unsafe { self.try_read_mem_u32(offset) }.unwrap()
+    }
+    unsafe fn read_mem_u64(&self, offset: usize) -> u64 {
+        unsafe { self.try_read_mem_u64(offset) }.unwrap()
+    }
+    unsafe fn read_mem_isize(&self, offset: usize) -> isize {
+        unsafe { self.try_read_mem_isize(offset) }.unwrap()
+    }
+    unsafe fn read_mem_i8(&self, offset: usize) -> i8 {
+        unsafe { self.try_read_mem_i8(offset) }.unwrap()
+    }
+    unsafe fn read_mem_i16(&self, offset: usize) -> i16 {
+        unsafe { self.try_read_mem_i16(offset) }.unwrap()
+    }
+    unsafe fn read_mem_i32(&self, offset: usize) -> i32 {
+        unsafe { self.try_read_mem_i32(offset) }.unwrap()
+    }
+    unsafe fn read_mem_i64(&self, offset: usize) -> i64 {
+        unsafe { self.try_read_mem_i64(offset) }.unwrap()
+    }
+    unsafe fn read_mem_f32(&self, offset: usize) -> f32 {
+        unsafe { self.try_read_mem_f32(offset) }.unwrap()
+    }
+    unsafe fn read_mem_f64(&self, offset: usize) -> f64 {
+        unsafe { self.try_read_mem_f64(offset) }.unwrap()
+    }
+
+    unsafe fn write_reg(&self, reg: usize, data: usize) {
+        unsafe { self.write_mem(reg * D::BYTES, data) }
+    }
+    unsafe fn write_reg_usize(&self, reg: usize, data: usize) {
+        unsafe { self.write_mem_usize(reg * D::BYTES, data) }
+    }
+    unsafe fn write_reg_u8(&self, reg: usize, data: u8) {
+        unsafe { self.write_mem_u8(reg * D::BYTES, data) }
+    }
+    unsafe fn write_reg_u16(&self, reg: usize, data: u16) {
+        unsafe { self.write_mem_u16(reg * D::BYTES, data) }
+    }
+    unsafe fn write_reg_u32(&self, reg: usize, data: u32) {
+        unsafe { self.write_mem_u32(reg * D::BYTES, data) }
+    }
+    unsafe fn write_reg_u64(&self, reg: usize, data: u64) {
+        unsafe { self.write_mem_u64(reg * D::BYTES, data) }
+    }
+    unsafe fn write_reg_isize(&self, reg: usize, data: isize) {
+        unsafe { self.write_mem_isize(reg * D::BYTES, data) }
+    }
+    unsafe fn write_reg_i8(&self, reg: usize, data: i8) {
+        unsafe { self.write_mem_i8(reg * D::BYTES, data) }
+    }
+    unsafe fn write_reg_i16(&self, reg: usize, data: i16) {
+        unsafe { self.write_mem_i16(reg * D::BYTES, data) }
+    }
+    unsafe fn write_reg_i32(&self, reg: usize, data: i32) {
+        unsafe { self.write_mem_i32(reg * D::BYTES, data) }
+    }
+    unsafe fn write_reg_i64(&self, reg: usize, data: i64) {
+        unsafe { self.write_mem_i64(reg * D::BYTES, data) }
+    }
+    unsafe fn write_reg_f32(&self, reg: usize, data: f32) {
+        unsafe { self.write_mem_f32(reg * D::BYTES, data) }
+    }
+    unsafe fn write_reg_f64(&self, reg: usize, data: f64) {
+        unsafe { self.write_mem_f64(reg * D::BYTES, data) }
+    }
+
+    unsafe fn read_reg(&self, reg: usize) -> usize {
+        unsafe { self.read_mem(reg * D::BYTES) }
+    }
+    unsafe fn read_reg_usize(&self, reg: usize) -> usize {
+        unsafe { self.read_mem_usize(reg * D::BYTES) }
+    }
+    unsafe fn read_reg_u8(&self, reg: usize) -> u8 {
+        unsafe { self.read_mem_u8(reg * D::BYTES) }
+    }
+    unsafe fn read_reg_u16(&self, reg: usize) -> u16 {
+        unsafe { self.read_mem_u16(reg * D::BYTES) }
+    }
+    unsafe fn read_reg_u32(&self, reg: usize) -> u32 {
+        unsafe { self.read_mem_u32(reg * D::BYTES) }
+    }
+    unsafe fn read_reg_u64(&self, reg: usize) -> u64 {
+        unsafe { self.read_mem_u64(reg * D::BYTES) }
+    }
+    unsafe fn read_reg_isize(&self, reg: usize) -> isize {
+        unsafe { self.read_mem_isize(reg * D::BYTES) }
+    }
+    unsafe fn read_reg_i8(&self, reg: usize) -> i8 {
+        unsafe { self.read_mem_i8(reg * D::BYTES) }
+    }
+    unsafe fn read_reg_i16(&self, reg: usize) -> i16 {
+        unsafe { self.read_mem_i16(reg * D::BYTES) }
+    }
+    unsafe fn read_reg_i32(&self, reg: usize) -> i32 {
+        unsafe { self.read_mem_i32(reg * D::BYTES) }
+    }
+    unsafe fn read_reg_i64(&self, reg: usize) -> i64 {
+        unsafe { self.read_mem_i64(reg * D::BYTES) }
+    }
+    unsafe fn read_reg_f32(&self, reg: usize) -> f32 {
+        unsafe { self.read_mem_f32(reg * D::BYTES) }
+    }
+    unsafe fn read_reg_f64(&self, reg: usize) -> f64 {
+        unsafe { self.read_mem_f64(reg * D::BYTES) }
+    }
+
+    unsafe fn try_write_mem(&self, offset: usize, data: usize) -> Result<(), MemAccessTryError> {
+        self.write_usize(offset, data).map_err(map_bus_err)
+    }
+    unsafe fn try_write_mem_usize(
+        &self,
+        offset: usize,
+        data: usize,
+    ) -> Result<(), MemAccessTryError> {
+        self.write_usize(offset, data).map_err(map_bus_err)
+    }
+    unsafe fn try_write_mem_u8(&self, offset: usize, data: u8) -> Result<(), MemAccessTryError> {
+        self.write_u8(offset, data).map_err(map_bus_err)
+    }
+    unsafe fn try_write_mem_u16(&self, offset: usize, data: u16) -> Result<(), MemAccessTryError> {
+        self.write_u16(offset, data).map_err(map_bus_err)
+    }
+    unsafe fn try_write_mem_u32(&self, offset: usize, data: u32) -> Result<(), MemAccessTryError> {
+        self.write_u32(offset, data).map_err(map_bus_err)
+    }
+    unsafe fn try_write_mem_u64(&self, offset: usize, data: u64) -> Result<(), MemAccessTryError> {
+        self.write_u64(offset, data).map_err(map_bus_err)
+    }
+    unsafe fn try_write_mem_isize(
+        &self,
+        offset: usize,
+        data: isize,
+    ) -> Result<(), MemAccessTryError> {
+        self.write_value(offset, data).map_err(map_bus_err)
+    }
+    unsafe fn try_write_mem_i8(&self, offset: usize, data: i8) -> Result<(), MemAccessTryError> {
+        self.write_value(offset, data).map_err(map_bus_err)
+    }
+    unsafe fn try_write_mem_i16(&self, offset: usize, data: i16) -> Result<(), MemAccessTryError> {
+        self.write_value(offset, data).map_err(map_bus_err)
+    }
+    unsafe fn try_write_mem_i32(&self, offset: usize, data: i32) -> Result<(), MemAccessTryError> {
+        self.write_value(offset, data).map_err(map_bus_err)
+    }
+    unsafe fn try_write_mem_i64(&self, offset: usize, data: i64) -> Result<(), MemAccessTryError> {
+        self.write_value(offset, data).map_err(map_bus_err)
+    }
+    unsafe fn try_write_mem_f32(&self, offset: usize, data: f32) -> Result<(), MemAccessTryError> {
+        self.write_value(offset, data).map_err(map_bus_err)
+    }
+    unsafe fn try_write_mem_f64(&self, offset: usize, data: f64) -> Result<(), MemAccessTryError> {
+        self.write_value(offset, data).map_err(map_bus_err)
+    }
+
+    unsafe fn try_read_mem(&self, offset: usize) -> Result<usize, MemAccessTryError> {
+        self.read_usize(offset).map_err(map_bus_err)
+    }
+    unsafe fn try_read_mem_usize(&self, offset: usize) -> Result<usize, MemAccessTryError> {
+        self.read_usize(offset).map_err(map_bus_err)
+    }
+    unsafe fn try_read_mem_u8(&self, offset: usize) -> Result<u8, MemAccessTryError> {
+        self.read_u8(offset).map_err(map_bus_err)
+    }
+    unsafe fn try_read_mem_u16(&self, offset: usize) -> Result<u16, MemAccessTryError> {
+        self.read_u16(offset).map_err(map_bus_err)
+    }
+    unsafe fn try_read_mem_u32(&self, offset: usize) -> Result<u32, MemAccessTryError> {
+        self.read_u32(offset).map_err(map_bus_err)
+    }
+    unsafe fn try_read_mem_u64(&self, offset: usize) -> Result<u64, MemAccessTryError> {
+        self.read_u64(offset).map_err(map_bus_err)
+    }
+    unsafe fn try_read_mem_isize(&self, offset: usize) -> Result<isize, MemAccessTryError> {
+        self.read_value(offset).map_err(map_bus_err)
+    }
+    unsafe fn try_read_mem_i8(&self, offset: usize) -> Result<i8, MemAccessTryError> {
+        self.read_value(offset).map_err(map_bus_err)
+    }
+    unsafe fn try_read_mem_i16(&self, offset: usize) -> Result<i16, MemAccessTryError> {
+        self.read_value(offset).map_err(map_bus_err)
+    }
+    unsafe fn try_read_mem_i32(&self, offset: usize) -> Result<i32, MemAccessTryError> {
+        self.read_value(offset).map_err(map_bus_err)
+    }
+    unsafe fn try_read_mem_i64(&self, offset: usize) -> Result<i64, MemAccessTryError> {
+        self.read_value(offset).map_err(map_bus_err)
+    }
+    unsafe fn try_read_mem_f32(&self, offset: usize) -> Result<f32, MemAccessTryError> {
+        self.read_value(offset).map_err(map_bus_err)
+    }
+    unsafe fn try_read_mem_f64(&self, offset: usize) -> Result<f64, MemAccessTryError> {
+        self.read_value(offset).map_err(map_bus_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus_accessor::{BigEndian, LittleEndian};
+    use core::cell::RefCell;
+
+    #[derive(Debug)]
+    struct MockBus {
+        mem: [u8; 64],
+    }
+
+    impl Default for MockBus {
+        fn default() -> Self {
+            Self { mem: [0; 64] }
+        }
+    }
+
+    impl Bus<usize, u32, u8> for MockBus {
+        type Error = ();
+
+        fn write(&mut self, addr: usize, data: u32, strb: u8) -> Result<(), ()> {
+            for lane in 0..4 {
+                if ((strb >> lane) & 1) == 1 {
+                    self.mem[addr + lane] = ((data >> (lane * 8)) & 0xFF) as u8;
+                }
+            }
+            Ok(())
+        }
+
+        fn read(&mut self, addr: usize) -> Result<u32, ()> {
+            let mut data = 0u32;
+            for lane in 0..4 {
+                data |= (self.mem[addr + lane] as u32) << (lane * 8);
+            }
+            Ok(data)
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "subclone out of range")]
+    fn refcell_subclone_out_of_range_panics() {
+        let lock = RefCell::new(MockBus::default());
+        let root: LockedBusAccessor<_, MockBus, usize, u32, u8, LittleEndian> =
+            LockedBusAccessor::new_with_range(&lock, 0, 16);
+        let _ = root.subclone(32, 0);
+    }
+
+    #[test]
+    fn refcell_subclone_shares_bus() {
+        let lock = RefCell::new(MockBus::default());
+        let root: LockedBusAccessor<_, MockBus, usize, u32, u8, LittleEndian> =
+            LockedBusAccessor::new_with_range(&lock, 0, 64);
+
+        let sub = root.subclone(16, 16);
+        assert_eq!(sub.base(), 16);
+        assert_eq!(sub.size(), 16);
+        sub.write_u32(0, 0x1234_5678).unwrap();
+        assert_eq!(root.read_u32(16).unwrap(), 0x1234_5678);
+        assert_eq!(
+            sub.write_u32(13, 0).unwrap_err(),
+            BusAccessorError::OutOfBounds
+        );
+
+        let be = root.subclone_::<BigEndian>(32, 0);
+        be.write_u64(0, 0x0011_2233_4455_6677).unwrap();
+        assert_eq!(be.read_u64(0).unwrap(), 0x0011_2233_4455_6677);
+
+        assert_eq!(
+            sub.try_subclone(8, 16).unwrap_err(),
+            BusAccessorError::OutOfBounds
+        );
+        assert_eq!(
+            sub.try_subclone(16, 0).unwrap_err(),
+            BusAccessorError::OutOfBounds
+        );
+    }
+
+    #[test]
+    fn refcell_reentry_is_busy() {
+        let lock = RefCell::new(MockBus::default());
+        let accessor: LockedBusAccessor<_, MockBus, usize, u32, u8, LittleEndian> =
+            LockedBusAccessor::new(&lock);
+
+        let guard = lock.borrow_mut();
+        assert_eq!(accessor.read_u32(0), Err(BusAccessorError::Busy));
+        assert_eq!(
+            unsafe { accessor.try_write_mem_u32(0, 1) },
+            Err(MemAccessTryError::Busy)
+        );
+        drop(guard);
+        assert_eq!(accessor.read_u32(0), Ok(0));
+    }
+
+    #[cfg(target_has_atomic = "8")]
+    #[test]
+    fn spin_lock_memaccess_copy() {
+        use crate::bus_lock::SpinLock;
+
+        let lock = SpinLock::new(MockBus::default());
+        let root: LockedBusAccessor<_, MockBus, usize, u32, u8, LittleEndian> =
+            LockedBusAccessor::new(&lock);
+        let sub = root.subclone(6, 32);
+
+        let src: [u16; 9] = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let mut dst = [0u16; 9];
+        unsafe {
+            sub.copy_from_u16(src.as_ptr(), 2, src.len());
+            sub.copy_to_u16(2, dst.as_mut_ptr(), dst.len());
+            assert_eq!(root.read_mem_u16(8), 1);
+        }
+        assert_eq!(src, dst);
+        assert_eq!(lock.into_inner().mem[8], 1);
+    }
+}
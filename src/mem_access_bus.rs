@@ -0,0 +1,309 @@
+#![allow(dead_code)]
+
+use crate::{Bus, BusAddress, BusWord, MemAccess, MemAccessTryError};
+
+// Bus adapter for any MemAccess (MmioAccessor, UioAccessor, ...).
+// Bus addresses are byte offsets in the accessor, byte lane i of a bus word
+// is the byte that lands at offset + i when the word is stored in host order
+// (offset + i on little-endian hosts).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrbMode {
+    // write only the enabled bytes (byte writes must be supported by the target)
+    ByteLanes,
+    // read the whole word, merge the enabled bytes and write the whole word back
+    ReadModifyWrite,
+}
+
+#[derive(Debug)]
+pub struct MemAccessBus<T> {
+    accessor: T,
+    strb_mode: StrbMode,
+}
+
+impl<T: MemAccess> MemAccessBus<T> {
+    pub fn new(accessor: T) -> Self {
+        Self::with_strb_mode(accessor, StrbMode::ByteLanes)
+    }
+
+    pub fn with_strb_mode(accessor: T, strb_mode: StrbMode) -> Self {
+        MemAccessBus {
+            accessor,
+            strb_mode,
+        }
+    }
+
+    pub fn accessor(&self) -> &T {
+        &self.accessor
+    }
+
+    pub fn strb_mode(&self) -> StrbMode {
+        self.strb_mode
+    }
+
+    pub fn set_strb_mode(&mut self, strb_mode: StrbMode) {
+        self.strb_mode = strb_mode;
+    }
+
+    pub fn into_inner(self) -> T {
+        self.accessor
+    }
+
+    fn check(&self, offset: usize, bytes: usize) -> Result<(), MemAccessTryError> {
+        // 1/2/4/8 bytes, or a multiple of 8 moved as u64 pieces
+        if !(bytes.is_power_of_two() && bytes <= 8 || bytes % 8 == 0) {
+            return Err(MemAccessTryError::AccessFault);
+        }
+        if offset % bytes != 0 {
+            return Err(MemAccessTryError::Misaligned);
+        }
+        let end = offset
+            .checked_add(bytes)
+            .ok_or(MemAccessTryError::AddressOverflow)?;
+        // size 0 means unlimited (e.g. BusAccessor)
+        let size = self.accessor.size();
+        if size != 0 && end > size {
+            return Err(MemAccessTryError::OutOfBounds);
+        }
+        Ok(())
+    }
+
+    unsafe fn read_chunk(&self, offset: usize, bytes: usize) -> Result<[u8; 8], MemAccessTryError> {
+        let mut buf = [0u8; 8];
+        match bytes {
+            1 => buf[0] = self.accessor.try_read_mem_u8(offset)?,
+            2 => buf[..2].copy_from_slice(&self.accessor.try_read_mem_u16(offset)?.to_ne_bytes()),
+            4 => buf[..4].copy_from_slice(&self.accessor.try_read_mem_u32(offset)?.to_ne_bytes()),
+            8 => buf.copy_from_slice(&self.accessor.try_read_mem_u64(offset)?.to_ne_bytes()),
+            _ => return Err(MemAccessTryError::AccessFault),
+        }
+        Ok(buf)
+    }
+
+    unsafe fn write_chunk(&self, offset: usize, bytes: &[u8]) -> Result<(), MemAccessTryError> {
+        match bytes.len() {
+            1 => self.accessor.try_write_mem_u8(offset, bytes[0]),
+            2 => self
+                .accessor
+                .try_write_mem_u16(offset, u16::from_ne_bytes([bytes[0], bytes[1]])),
+            4 => self
+                .accessor
+                .try_write_mem_u32(offset, u32::from_ne_bytes(bytes.try_into().unwrap())),
+            8 => self
+                .accessor
+                .try_write_mem_u64(offset, u64::from_ne_bytes(bytes.try_into().unwrap())),
+            _ => Err(MemAccessTryError::AccessFault),
+        }
+    }
+
+    // memory bytes [pos, pos + width) of `word` stored at `offset`
+    unsafe fn write_run<D: BusWord>(
+        &self,
+        offset: usize,
+        word: &D,
+        pos: usize,
+        width: usize,
+    ) -> Result<(), MemAccessTryError> {
+        let mut buf = [0u8; 8];
+        for (i, byte) in buf[..width].iter_mut().enumerate() {
+            *byte = word.lane(host_lane_offset(D::BYTES, pos + i));
+        }
+        self.write_chunk(offset + pos, &buf[..width])
+    }
+
+    unsafe fn write_word<D: BusWord>(
+        &self,
+        offset: usize,
+        word: &D,
+    ) -> Result<(), MemAccessTryError> {
+        let width = D::BYTES.min(8);
+        for pos in (0..D::BYTES).step_by(width) {
+            self.write_run(offset, word, pos, width)?;
+        }
+        Ok(())
+    }
+
+    unsafe fn read_word<D: BusWord>(&self, offset: usize) -> Result<D, MemAccessTryError> {
+        let width = D::BYTES.min(8);
+        let mut word = D::zero();
+        for pos in (0..D::BYTES).step_by(width) {
+            let buf = self.read_chunk(offset + pos, width)?;
+            for (i, byte) in buf[..width].iter().enumerate() {
+                word.set_lane(host_lane_offset(D::BYTES, pos + i), *byte);
+            }
+        }
+        Ok(word)
+    }
+}
+
+// byte lane <-> byte position in memory, for a bus word stored in host order
+// (the mapping is its own inverse)
+fn host_lane_offset(word_bytes: usize, lane: usize) -> usize {
+    if cfg!(target_endian = "little") {
+        lane
+    } else {
+        word_bytes - 1 - lane
+    }
+}
+
+impl<T, A, D, S> Bus<A, D, S> for MemAccessBus<T>
+where
+    T: MemAccess,
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+{
+    type Error = MemAccessTryError;
+
+    fn write(&mut self, addr: A, data: D, strb: S) -> Result<(), Self::Error> {
+        let bytes = D::BYTES;
+        let offset = addr.to_usize();
+        self.check(offset, bytes)?;
+
+        // a bus without strobe (S = ()) always writes every lane
+        let enabled = |lane: usize| S::BITS == 0 || (lane < S::BITS && strb.bit(lane));
+        let count = (0..bytes).filter(|&lane| enabled(lane)).count();
+        if count == 0 {
+            return Ok(());
+        }
+        if count == bytes {
+            return unsafe { self.write_word(offset, &data) };
+        }
+        if S::BITS < bytes {
+            return Err(MemAccessTryError::StrbTooNarrow);
+        }
+
+        match self.strb_mode {
+            StrbMode::ReadModifyWrite => {
+                let mut merged: D = unsafe { self.read_word(offset) }?;
+                for lane in (0..bytes).filter(|&lane| enabled(lane)) {
+                    merged.set_lane(lane, data.lane(lane));
+                }
+                unsafe { self.write_word(offset, &merged) }
+            }
+            StrbMode::ByteLanes => {
+                // enabled bytes in memory order
+                let enabled_at = |pos: usize| enabled(host_lane_offset(bytes, pos));
+
+                // write the largest naturally aligned runs of enabled bytes
+                let mut pos = 0;
+                while pos < bytes {
+                    if !enabled_at(pos) {
+                        pos += 1;
+                        continue;
+                    }
+                    let mut width = 8;
+                    while width > 1
+                        && (pos % width != 0
+                            || pos + width > bytes
+                            || !(pos..pos + width).all(enabled_at))
+                    {
+                        width /= 2;
+                    }
+                    unsafe { self.write_run(offset, &data, pos, width) }?;
+                    pos += width;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn read(&mut self, addr: A) -> Result<D, Self::Error> {
+        let offset = addr.to_usize();
+        self.check(offset, D::BYTES)?;
+        unsafe { self.read_word(offset) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BusAccessor, LittleEndian, MmioAccessor};
+
+    #[test]
+    fn full_and_strobed_write() {
+        let mut buf = [0u32; 4];
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 16);
+        let mut bus = MemAccessBus::new(mmio.clone());
+
+        Bus::<usize, u32, u8>::write(&mut bus, 4, 0x1234_5678, 0xf).unwrap();
+        Bus::<usize, u32, u8>::write(&mut bus, 8, 0xaabb_ccdd, 0b0110).unwrap();
+        assert_eq!(
+            Bus::<usize, u32, u8>::read(&mut bus, 4).unwrap(),
+            0x1234_5678
+        );
+        assert_eq!(
+            Bus::<usize, u32, u8>::read(&mut bus, 8).unwrap(),
+            0x00bb_cc00
+        );
+
+        unsafe {
+            assert_eq!(mmio.read_mem_u8(9), 0xcc);
+            assert_eq!(mmio.read_mem_u8(10), 0xbb);
+        }
+    }
+
+    #[test]
+    fn read_modify_write() {
+        let mut buf = [0x1111_1111u32; 4];
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 16);
+        let mut bus = MemAccessBus::with_strb_mode(mmio, StrbMode::ReadModifyWrite);
+
+        Bus::<usize, u32, u8>::write(&mut bus, 0, 0xaabb_ccdd, 0b1001).unwrap();
+        assert_eq!(
+            Bus::<usize, u32, u8>::read(&mut bus, 0).unwrap(),
+            0xaa11_11dd
+        );
+    }
+
+    #[test]
+    fn bounds_and_alignment() {
+        let mut buf = [0u32; 4];
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 16);
+        let mut bus = MemAccessBus::new(mmio);
+
+        assert_eq!(
+            Bus::<usize, u32, u8>::read(&mut bus, 16),
+            Err(MemAccessTryError::OutOfBounds)
+        );
+        assert_eq!(
+            Bus::<usize, u32, u8>::read(&mut bus, 2),
+            Err(MemAccessTryError::Misaligned)
+        );
+    }
+
+    #[test]
+    fn wide_word_over_mmio() {
+        let mut buf = [0u64; 8];
+        let mmio = MmioAccessor::<u64>::new(buf.as_mut_ptr() as usize, 64);
+        let mut bus = MemAccessBus::new(mmio.clone());
+
+        let data: [u8; 32] = core::array::from_fn(|i| i as u8);
+        Bus::<usize, [u8; 32], u32>::write(&mut bus, 32, data, u32::MAX).unwrap();
+        Bus::<usize, [u8; 32], u32>::write(&mut bus, 0, [0xEE; 32], 0x0000_0FF0).unwrap();
+        let word: [u8; 32] = Bus::<usize, [u8; 32], u32>::read(&mut bus, 32).unwrap();
+        assert_eq!(word, data);
+        unsafe {
+            assert_eq!(mmio.read_mem_u8(32 + host_lane_offset(32, 9)), 9);
+            assert_eq!(mmio.read_mem_u8(host_lane_offset(32, 4)), 0xEE);
+            assert_eq!(mmio.read_mem_u8(host_lane_offset(32, 12)), 0);
+        }
+    }
+
+    #[test]
+    fn bus_accessor_on_mmio() {
+        let mut buf = [0u64; 4];
+        let mmio = MmioAccessor::<u64>::new(buf.as_mut_ptr() as usize, 32);
+        let bus = MemAccessBus::new(mmio.clone());
+        let accessor = BusAccessor::<_, usize, u64, u8, LittleEndian>::new(bus);
+
+        accessor.write_u16(3, 0xabcd).unwrap();
+        accessor.write_u32(6, 0x1234_5678).unwrap();
+        assert_eq!(accessor.read_u16(3).unwrap(), 0xabcd);
+        assert_eq!(accessor.read_u32(6).unwrap(), 0x1234_5678);
+        unsafe {
+            assert_eq!(mmio.read_mem_u16(6), 0x5678);
+            assert_eq!(mmio.read_mem_u16(8), 0x1234);
+        }
+    }
+}
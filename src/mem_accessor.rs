@@ -8,12 +8,27 @@ pub trait MemRegion {
     fn addr(&self) -> usize;
     fn size(&self) -> usize;
     fn phys_addr(&self) -> usize;
+
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError>
+    where
+        Self: Sized,
+    {
+        subregion_size(self.size(), offset, size)?;
+        Ok(self.subclone(offset, size))
+    }
 }
 
 pub trait MemAccessBase {
     fn reg_size() -> usize;
 
     fn subclone(&self, offset: usize, size: usize) -> Self;
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError>
+    where
+        Self: Sized + MemAccess,
+    {
+        subregion_size(self.size(), offset, size)?;
+        Ok(self.subclone(offset, size))
+    }
 
     unsafe fn copy_to_<V>(&self, src_adr: usize, dst_ptr: *mut V, count: usize);
     unsafe fn copy_from_<V>(&self, src_ptr: *const V, dst_adr: usize, count: usize);
@@ -24,7 +39,21 @@ pub trait MemAccessBase {
     unsafe fn read_reg_<V>(&self, reg: usize) -> V;
 }
 
+// Accessors whose element type can change on subclone (`subclone_::<NewU>`), so
+// wrappers can provide `subclone8` etc. on top of them.
+pub trait SubcloneAs {
+    type As<NewU>: MemAccess + MemAccessBase;
+
+    fn subclone_as<NewU>(&self, offset: usize, size: usize) -> Self::As<NewU>;
+    fn try_subclone_as<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<Self::As<NewU>, MemAccessTryError>;
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum MemAccessTryError {
     AccessFault,
     AddressOverflow,
@@ -32,7 +61,104 @@ pub enum MemAccessTryError {
     OutOfBounds,
     StrbTooNarrow,
     LockPoisoned,
+    Misaligned,
+    Busy,
+    ReadSideEffects,
+    PermissionDenied,
+}
+
+// Size of the subregion at `offset` (size == 0 means "the rest"), or an error if it
+// does not fit in `parent_size`. Shared by every checked subclone, memory and bus alike:
+// an offset at the end would make an empty region that size == 0 cannot express, so
+// only the whole-region clone (0, 0) of an empty parent may start there.
+pub(crate) fn subregion_size(
+    parent_size: usize,
+    offset: usize,
+    size: usize,
+) -> Result<usize, MemAccessTryError> {
+    let end = offset
+        .checked_add(size)
+        .ok_or(MemAccessTryError::AddressOverflow)?;
+    if end > parent_size || (offset == parent_size && offset != 0) {
+        return Err(MemAccessTryError::OutOfBounds);
+    }
+    Ok(if size == 0 {
+        parent_size - offset
+    } else {
+        size
+    })
+}
+
+pub(crate) fn expect_subclone<T>(
+    result: Result<T, MemAccessTryError>,
+    offset: usize,
+    size: usize,
+) -> T {
+    match result {
+        Ok(region) => region,
+        Err(err) => panic!(
+            "subclone out of range (offset: 0x{:x}, size: 0x{:x}): {:?}",
+            offset, size, err
+        ),
+    }
+}
+
+// Generates every typed MemAccess method of a wrapper accessor: `$copy!` gets the
+// copy_to/copy_from quartet of a type, `$rw!` a write/read quartet with its AccessKind names.
+macro_rules! for_each_access_type {
+    ($copy:ident, $rw:ident) => {
+        $rw!(usize, Write, write_mem, try_write_mem, Read, read_mem, try_read_mem);
+        $rw!(usize, WriteReg, write_reg, try_write_reg, ReadReg, read_reg, try_read_reg);
+        for_each_access_type!(@types $copy, $rw;
+            usize: copy_to_usize, try_copy_to_usize, copy_from_usize, try_copy_from_usize,
+                write_mem_usize, try_write_mem_usize, read_mem_usize, try_read_mem_usize,
+                write_reg_usize, try_write_reg_usize, read_reg_usize, try_read_reg_usize;
+            u8: copy_to_u8, try_copy_to_u8, copy_from_u8, try_copy_from_u8,
+                write_mem_u8, try_write_mem_u8, read_mem_u8, try_read_mem_u8,
+                write_reg_u8, try_write_reg_u8, read_reg_u8, try_read_reg_u8;
+            u16: copy_to_u16, try_copy_to_u16, copy_from_u16, try_copy_from_u16,
+                write_mem_u16, try_write_mem_u16, read_mem_u16, try_read_mem_u16,
+                write_reg_u16, try_write_reg_u16, read_reg_u16, try_read_reg_u16;
+            u32: copy_to_u32, try_copy_to_u32, copy_from_u32, try_copy_from_u32,
+                write_mem_u32, try_write_mem_u32, read_mem_u32, try_read_mem_u32,
+                write_reg_u32, try_write_reg_u32, read_reg_u32, try_read_reg_u32;
+            u64: copy_to_u64, try_copy_to_u64, copy_from_u64, try_copy_from_u64,
+                write_mem_u64, try_write_mem_u64, read_mem_u64, try_read_mem_u64,
+                write_reg_u64, try_write_reg_u64, read_reg_u64, try_read_reg_u64;
+            isize: copy_to_isize, try_copy_to_isize, copy_from_isize, try_copy_from_isize,
+                write_mem_isize, try_write_mem_isize, read_mem_isize, try_read_mem_isize,
+                write_reg_isize, try_write_reg_isize, read_reg_isize, try_read_reg_isize;
+            i8: copy_to_i8, try_copy_to_i8, copy_from_i8, try_copy_from_i8,
+                write_mem_i8, try_write_mem_i8, read_mem_i8, try_read_mem_i8,
+                write_reg_i8, try_write_reg_i8, read_reg_i8, try_read_reg_i8;
+            i16: copy_to_i16, try_copy_to_i16, copy_from_i16, try_copy_from_i16,
+                write_mem_i16, try_write_mem_i16, read_mem_i16, try_read_mem_i16,
+                write_reg_i16, try_write_reg_i16, read_reg_i16, try_read_reg_i16;
+            i32: copy_to_i32, try_copy_to_i32, copy_from_i32, try_copy_from_i32,
+                write_mem_i32, try_write_mem_i32, read_mem_i32, try_read_mem_i32,
+                write_reg_i32, try_write_reg_i32, read_reg_i32, try_read_reg_i32;
+            i64: copy_to_i64, try_copy_to_i64, copy_from_i64, try_copy_from_i64,
+                write_mem_i64, try_write_mem_i64, read_mem_i64, try_read_mem_i64,
+                write_reg_i64, try_write_reg_i64, read_reg_i64, try_read_reg_i64;
+            f32: copy_to_f32, try_copy_to_f32, copy_from_f32, try_copy_from_f32,
+                write_mem_f32, try_write_mem_f32, read_mem_f32, try_read_mem_f32,
+                write_reg_f32, try_write_reg_f32, read_reg_f32, try_read_reg_f32;
+            f64: copy_to_f64, try_copy_to_f64, copy_from_f64, try_copy_from_f64,
+                write_mem_f64, try_write_mem_f64, read_mem_f64, try_read_mem_f64,
+                write_reg_f64, try_write_reg_f64, read_reg_f64, try_read_reg_f64;
+        );
+    };
+    (@types $copy:ident, $rw:ident; $($t:ty: $copy_to:ident, $try_copy_to:ident, $copy_from:ident, $try_copy_from:ident,
+        $write_mem:ident, $try_write_mem:ident, $read_mem:ident, $try_read_mem:ident,
+        $write_reg:ident, $try_write_reg:ident, $read_reg:ident, $try_read_reg:ident;)*) => {
+        $(
+            $copy!($t, $copy_to, $try_copy_to, $copy_from, $try_copy_from);
+            $rw!($t, Write, $write_mem, $try_write_mem, Read, $read_mem, $try_read_mem);
+            $rw!($t, WriteReg, $write_reg, $try_write_reg, ReadReg, $read_reg, $try_read_reg);
+        )*
+    };
 }
+pub(crate) use for_each_access_type;
 
 pub trait MemAccess {
     fn addr(&self) -> usize;
@@ -550,11 +676,7 @@ pub trait MemAccess {
         }
         Ok(())
     }
-    unsafe fn try_write_reg_isize(
-        &self,
-        reg: usize,
-        data: isize,
-    ) -> Result<(), MemAccessTryError> {
+    unsafe fn try_write_reg_isize(&self, reg: usize, data: isize) -> Result<(), MemAccessTryError> {
         unsafe {
             self.write_reg_isize(reg, data);
         }
@@ -691,6 +813,16 @@ impl<T: MemRegion, U> MemAccessor<T, U> {
         MemAccessor::<T, NewU>::new(self.region.subclone(offset, size))
     }
 
+    pub fn try_subclone_<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MemAccessor<T, NewU>, MemAccessTryError> {
+        Ok(MemAccessor::<T, NewU>::new(
+            self.region.try_subclone(offset, size)?,
+        ))
+    }
+
     pub fn subclone8(&self, offset: usize, size: usize) -> MemAccessor<T, u8> {
         self.subclone_::<u8>(offset, size)
     }
@@ -706,6 +838,38 @@ impl<T: MemRegion, U> MemAccessor<T, U> {
     pub fn subclone64(&self, offset: usize, size: usize) -> MemAccessor<T, u64> {
         self.subclone_::<u64>(offset, size)
     }
+
+    pub fn try_subclone8(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MemAccessor<T, u8>, MemAccessTryError> {
+        self.try_subclone_::<u8>(offset, size)
+    }
+
+    pub fn try_subclone16(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MemAccessor<T, u16>, MemAccessTryError> {
+        self.try_subclone_::<u16>(offset, size)
+    }
+
+    pub fn try_subclone32(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MemAccessor<T, u32>, MemAccessTryError> {
+        self.try_subclone_::<u32>(offset, size)
+    }
+
+    pub fn try_subclone64(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MemAccessor<T, u64>, MemAccessTryError> {
+        self.try_subclone_::<u64>(offset, size)
+    }
 }
 
 impl<T: MemRegion, U> Clone for MemAccessor<T, U> {
@@ -714,6 +878,22 @@ impl<T: MemRegion, U> Clone for MemAccessor<T, U> {
     }
 }
 
+impl<T: MemRegion, U> SubcloneAs for MemAccessor<T, U> {
+    type As<NewU> = MemAccessor<T, NewU>;
+
+    fn subclone_as<NewU>(&self, offset: usize, size: usize) -> Self::As<NewU> {
+        self.subclone_::<NewU>(offset, size)
+    }
+
+    fn try_subclone_as<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<Self::As<NewU>, MemAccessTryError> {
+        self.try_subclone_::<NewU>(offset, size)
+    }
+}
+
 impl<T: MemRegion, U> MemAccessBase for MemAccessor<T, U> {
     fn reg_size() -> usize {
         core::mem::size_of::<U>()
@@ -723,6 +903,10 @@ impl<T: MemRegion, U> MemAccessBase for MemAccessor<T, U> {
         self.subclone_::<U>(offset, size)
     }
 
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        self.try_subclone_::<U>(offset, size)
+    }
+
     unsafe fn copy_to_<V>(&self, src_adr: usize, dst_ptr: *mut V, count: usize) {
         debug_assert!(src_adr + count * core::mem::size_of::<V>() <= self.size());
         let src_ptr: *const V = (self.addr() + src_adr) as *const V;
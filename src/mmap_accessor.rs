@@ -167,16 +167,16 @@ impl MmapRegion {
 
 impl MemRegion for MmapRegion {
     fn subclone(&self, offset: usize, size: usize) -> Self {
-        debug_assert!(offset < self.size);
-        let new_addr = self.addr + offset;
-        let new_size = self.size - offset;
-        debug_assert!(size <= new_size);
-        let new_size = if size == 0 { new_size } else { size };
-        MmapRegion {
+        expect_subclone(self.try_subclone(offset, size), offset, size)
+    }
+
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        let new_size = subregion_size(self.size, offset, size)?;
+        Ok(MmapRegion {
             mfile: self.mfile.clone(),
-            addr: new_addr,
+            addr: self.addr + offset,
             size: new_size,
-        }
+        })
     }
 
     fn addr(&self) -> usize {
@@ -224,6 +224,18 @@ impl<U> MmapAccessor<U> {
         }
     }
 
+    pub fn try_subclone_<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MmapAccessor<NewU>, MemAccessTryError> {
+        Ok(MmapAccessor::<NewU> {
+            accessor: MemAccessor::<MmapRegion, NewU>::new(
+                self.accessor.region().try_subclone(offset, size)?,
+            ),
+        })
+    }
+
     pub fn subclone8(&self, offset: usize, size: usize) -> MmapAccessor<u8> {
         self.subclone_::<u8>(offset, size)
     }
@@ -239,6 +251,38 @@ impl<U> MmapAccessor<U> {
     pub fn subclone64(&self, offset: usize, size: usize) -> MmapAccessor<u64> {
         self.subclone_::<u64>(offset, size)
     }
+
+    pub fn try_subclone8(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MmapAccessor<u8>, MemAccessTryError> {
+        self.try_subclone_::<u8>(offset, size)
+    }
+
+    pub fn try_subclone16(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MmapAccessor<u16>, MemAccessTryError> {
+        self.try_subclone_::<u16>(offset, size)
+    }
+
+    pub fn try_subclone32(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MmapAccessor<u32>, MemAccessTryError> {
+        self.try_subclone_::<u32>(offset, size)
+    }
+
+    pub fn try_subclone64(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MmapAccessor<u64>, MemAccessTryError> {
+        self.try_subclone_::<u64>(offset, size)
+    }
 }
 
 impl<U> Clone for MmapAccessor<U> {
@@ -247,6 +291,22 @@ impl<U> Clone for MmapAccessor<U> {
     }
 }
 
+impl<U> SubcloneAs for MmapAccessor<U> {
+    type As<NewU> = MmapAccessor<NewU>;
+
+    fn subclone_as<NewU>(&self, offset: usize, size: usize) -> Self::As<NewU> {
+        self.subclone_::<NewU>(offset, size)
+    }
+
+    fn try_subclone_as<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<Self::As<NewU>, MemAccessTryError> {
+        self.try_subclone_::<NewU>(offset, size)
+    }
+}
+
 impl<U> MemAccessBase for MmapAccessor<U> {
     fn reg_size() -> usize {
         core::mem::size_of::<U>()
@@ -256,6 +316,10 @@ impl<U> MemAccessBase for MmapAccessor<U> {
         self.subclone_::<U>(offset, size)
     }
 
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        self.try_subclone_::<U>(offset, size)
+    }
+
     delegate! {
         to self.accessor {
             unsafe fn copy_to_<V>(&self, src_adr: usize, dst_ptr: *mut V, count: usize);
@@ -21,15 +21,15 @@ impl MmioRegion {
 
 impl MemRegion for MmioRegion {
     fn subclone(&self, offset: usize, size: usize) -> Self {
-        debug_assert!(offset < self.size);
-        let new_addr = self.addr + offset;
-        let new_size = self.size - offset;
-        debug_assert!(size <= new_size);
-        let new_size = if size == 0 { new_size } else { size };
-        MmioRegion {
-            addr: new_addr,
+        expect_subclone(self.try_subclone(offset, size), offset, size)
+    }
+
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        let new_size = subregion_size(self.size, offset, size)?;
+        Ok(MmioRegion {
+            addr: self.addr + offset,
             size: new_size,
-        }
+        })
     }
 
     fn addr(&self) -> usize {
@@ -77,6 +77,18 @@ impl<U> MmioAccessor<U> {
         }
     }
 
+    pub fn try_subclone_<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MmioAccessor<NewU>, MemAccessTryError> {
+        Ok(MmioAccessor::<NewU> {
+            mem_accessor: MemAccessor::<MmioRegion, NewU>::new(
+                self.mem_accessor.region().try_subclone(offset, size)?,
+            ),
+        })
+    }
+
     pub fn subclone8(&self, offset: usize, size: usize) -> MmioAccessor<u8> {
         self.subclone_::<u8>(offset, size)
     }
@@ -93,6 +105,38 @@ impl<U> MmioAccessor<U> {
         self.subclone_::<u64>(offset, size)
     }
 
+    pub fn try_subclone8(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MmioAccessor<u8>, MemAccessTryError> {
+        self.try_subclone_::<u8>(offset, size)
+    }
+
+    pub fn try_subclone16(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MmioAccessor<u16>, MemAccessTryError> {
+        self.try_subclone_::<u16>(offset, size)
+    }
+
+    pub fn try_subclone32(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MmioAccessor<u32>, MemAccessTryError> {
+        self.try_subclone_::<u32>(offset, size)
+    }
+
+    pub fn try_subclone64(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<MmioAccessor<u64>, MemAccessTryError> {
+        self.try_subclone_::<u64>(offset, size)
+    }
+
     delegate! {
         to self.mem_accessor.region() {
             pub fn addr(&self) -> usize;
@@ -107,6 +151,22 @@ impl<U> Clone for MmioAccessor<U> {
     }
 }
 
+impl<U> SubcloneAs for MmioAccessor<U> {
+    type As<NewU> = MmioAccessor<NewU>;
+
+    fn subclone_as<NewU>(&self, offset: usize, size: usize) -> Self::As<NewU> {
+        self.subclone_::<NewU>(offset, size)
+    }
+
+    fn try_subclone_as<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<Self::As<NewU>, MemAccessTryError> {
+        self.try_subclone_::<NewU>(offset, size)
+    }
+}
+
 impl<U> MemAccessBase for MmioAccessor<U> {
     fn reg_size() -> usize {
         core::mem::size_of::<U>()
@@ -116,6 +176,10 @@ impl<U> MemAccessBase for MmioAccessor<U> {
         self.subclone_::<U>(offset, size)
     }
 
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        self.try_subclone_::<U>(offset, size)
+    }
+
     delegate! {
         to self.mem_accessor {
             unsafe fn copy_to_<V>(&self, src_adr: usize, dst_ptr: *mut V, count: usize);
@@ -15,9 +15,16 @@ impl<const ADDR: usize, const SIZE: usize> PhysRegion<ADDR, SIZE> {
 
 impl<const ADDR: usize, const SIZE: usize> MemRegion for PhysRegion<ADDR, SIZE> {
     fn subclone(&self, offset: usize, size: usize) -> Self {
-        debug_assert!(offset == 0);
-        debug_assert!(size == 0 || size == SIZE);
-        PhysRegion::<ADDR, SIZE> {}
+        expect_subclone(self.try_subclone(offset, size), offset, size)
+    }
+
+    // ADDR/SIZE are type parameters, so only the whole region can be represented
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        subregion_size(SIZE, offset, size)?;
+        if offset != 0 || (size != 0 && size != SIZE) {
+            return Err(MemAccessTryError::AddressOutOfRange);
+        }
+        Ok(PhysRegion::<ADDR, SIZE> {})
     }
 
     fn addr(&self) -> usize {
@@ -69,6 +76,65 @@ impl<U, const ADDR: usize, const SIZE: usize> PhysAccessor<U, ADDR, SIZE> {
         }
     }
 
+    pub fn try_subclone_<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<PhysAccessor<NewU, ADDR, SIZE>, MemAccessTryError> {
+        Ok(PhysAccessor::<NewU, ADDR, SIZE> {
+            mem_accessor: MemAccessor::<PhysRegion<ADDR, SIZE>, NewU>::new(
+                self.mem_accessor.region().try_subclone(offset, size)?,
+            ),
+        })
+    }
+
+    /// Sub-block at `OFFSET` bytes from the start of this region. `NEW_ADDR` must be
+    /// `ADDR + OFFSET` (stable Rust cannot compute it in the return type), so the offset
+    /// and the resulting address are both spelled out and checked at compile time.
+    pub fn sub<const OFFSET: usize, const LEN: usize, const NEW_ADDR: usize>(
+        &self,
+    ) -> PhysAccessor<U, NEW_ADDR, LEN> {
+        self.sub_::<U, OFFSET, LEN, NEW_ADDR>()
+    }
+
+    pub fn sub_<NewU, const OFFSET: usize, const LEN: usize, const NEW_ADDR: usize>(
+        &self,
+    ) -> PhysAccessor<NewU, NEW_ADDR, LEN> {
+        const {
+            assert!(
+                match ADDR.checked_add(OFFSET) {
+                    Some(addr) => addr == NEW_ADDR,
+                    None => false,
+                },
+                "NEW_ADDR must be ADDR + OFFSET"
+            );
+        }
+        self.sub_at_::<NewU, NEW_ADDR, LEN>()
+    }
+
+    /// Sub-block at the absolute address `SUB_ADDR`. The bounds are checked at
+    /// compile time.
+    pub fn sub_at<const SUB_ADDR: usize, const SUB_SIZE: usize>(
+        &self,
+    ) -> PhysAccessor<U, SUB_ADDR, SUB_SIZE> {
+        self.sub_at_::<U, SUB_ADDR, SUB_SIZE>()
+    }
+
+    pub fn sub_at_<NewU, const SUB_ADDR: usize, const SUB_SIZE: usize>(
+        &self,
+    ) -> PhysAccessor<NewU, SUB_ADDR, SUB_SIZE> {
+        const {
+            assert!(SUB_SIZE > 0, "empty subregion");
+            assert!(SUB_ADDR >= ADDR, "subregion starts before the region");
+            assert!(SUB_SIZE <= SIZE, "subregion is larger than the region");
+            assert!(
+                SUB_ADDR - ADDR <= SIZE - SUB_SIZE,
+                "subregion exceeds the region"
+            );
+        }
+        PhysAccessor::<NewU, SUB_ADDR, SUB_SIZE>::new()
+    }
+
     pub fn subclone8(&self, offset: usize, size: usize) -> PhysAccessor<u8, ADDR, SIZE> {
         self.subclone_::<u8>(offset, size)
     }
@@ -85,6 +151,38 @@ impl<U, const ADDR: usize, const SIZE: usize> PhysAccessor<U, ADDR, SIZE> {
         self.subclone_::<u64>(offset, size)
     }
 
+    pub fn try_subclone8(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<PhysAccessor<u8, ADDR, SIZE>, MemAccessTryError> {
+        self.try_subclone_::<u8>(offset, size)
+    }
+
+    pub fn try_subclone16(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<PhysAccessor<u16, ADDR, SIZE>, MemAccessTryError> {
+        self.try_subclone_::<u16>(offset, size)
+    }
+
+    pub fn try_subclone32(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<PhysAccessor<u32, ADDR, SIZE>, MemAccessTryError> {
+        self.try_subclone_::<u32>(offset, size)
+    }
+
+    pub fn try_subclone64(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<PhysAccessor<u64, ADDR, SIZE>, MemAccessTryError> {
+        self.try_subclone_::<u64>(offset, size)
+    }
+
     delegate! {
         to self.mem_accessor.region() {
             pub fn addr(&self) -> usize;
@@ -99,6 +197,22 @@ impl<U, const ADDR: usize, const SIZE: usize> Clone for PhysAccessor<U, ADDR, SI
     }
 }
 
+impl<U, const ADDR: usize, const SIZE: usize> SubcloneAs for PhysAccessor<U, ADDR, SIZE> {
+    type As<NewU> = PhysAccessor<NewU, ADDR, SIZE>;
+
+    fn subclone_as<NewU>(&self, offset: usize, size: usize) -> Self::As<NewU> {
+        self.subclone_::<NewU>(offset, size)
+    }
+
+    fn try_subclone_as<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<Self::As<NewU>, MemAccessTryError> {
+        self.try_subclone_::<NewU>(offset, size)
+    }
+}
+
 impl<U, const ADDR: usize, const SIZE: usize> MemAccessBase for PhysAccessor<U, ADDR, SIZE> {
     fn reg_size() -> usize {
         core::mem::size_of::<U>()
@@ -108,6 +222,10 @@ impl<U, const ADDR: usize, const SIZE: usize> MemAccessBase for PhysAccessor<U,
         self.subclone_::<U>(offset, size)
     }
 
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        self.try_subclone_::<U>(offset, size)
+    }
+
     delegate! {
         to self.mem_accessor {
             unsafe fn copy_to_<V>(&self, src_adr: usize, dst_ptr: *mut V, count: usize);
@@ -0,0 +1,351 @@
+#![allow(dead_code)]
+
+use core::fmt;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+use super::hooked_accessor::{Access, AccessHook, AccessKind, HookedAccessor, TransparentHook};
+use super::mem_accessor::{MemAccess, MemAccessBase, MemAccessTryError};
+
+const HISTOGRAM_BUCKETS: usize = 40;
+
+/// 2 のべき乗刻みのレイテンシヒストグラム。
+///
+/// バケット `i` は `[2^(i-1), 2^i)` ns（バケット 0 は 1ns 未満、最後のバケットはそれ以上すべて）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    buckets: [u64; HISTOGRAM_BUCKETS],
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: [0; HISTOGRAM_BUCKETS],
+        }
+    }
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency: Duration) {
+        let ns = latency.as_nanos().min(u64::MAX as u128) as u64;
+        let index = (u64::BITS - ns.leading_zeros()) as usize;
+        self.buckets[index.min(HISTOGRAM_BUCKETS - 1)] += 1;
+    }
+
+    pub fn buckets(&self) -> &[u64] {
+        &self.buckets
+    }
+
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// 割合 `p`（0.0〜1.0）のアクセスが収まるバケットの上限。
+    pub fn percentile(&self, p: f64) -> Duration {
+        let target = (self.count() as f64 * p).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (i, &n) in self.buckets.iter().enumerate() {
+            seen += n;
+            if seen >= target {
+                return Duration::from_nanos(1u64 << i);
+            }
+        }
+        Duration::from_nanos(1u64 << (HISTOGRAM_BUCKETS - 1))
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (a, b) in self.buckets.iter_mut().zip(other.buckets.iter()) {
+            *a += b;
+        }
+    }
+}
+
+/// アドレスごとの集計。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccessStats {
+    /// 単発の読み出し（`read_mem_*`/`read_reg_*`）
+    pub reads: u64,
+    /// 単発の書き込み（`write_mem_*`/`write_reg_*`）
+    pub writes: u64,
+    /// `copy_to_*`/`copy_from_*` の回数（先頭アドレスで集計）
+    pub copies: u64,
+    pub bytes: u64,
+    /// 幅ごとの回数（1/2/4/8 バイト）
+    pub by_width: [u64; 4],
+    pub errors: u64,
+    pub total_time: Duration,
+    pub max_time: Duration,
+    pub histogram: LatencyHistogram,
+}
+
+impl AccessStats {
+    pub fn accesses(&self) -> u64 {
+        self.reads + self.writes + self.copies
+    }
+
+    pub fn mean_time(&self) -> Duration {
+        match self.accesses() {
+            0 => Duration::ZERO,
+            n => self.total_time / n as u32,
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.reads += other.reads;
+        self.writes += other.writes;
+        self.copies += other.copies;
+        self.bytes += other.bytes;
+        for (a, b) in self.by_width.iter_mut().zip(other.by_width.iter()) {
+            *a += b;
+        }
+        self.errors += other.errors;
+        self.total_time += other.total_time;
+        self.max_time = self.max_time.max(other.max_time);
+        self.histogram.merge(&other.histogram);
+    }
+}
+
+/// 集計結果。`Display` はアクセス数の多い順の表を出す。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileReport {
+    /// (バイトオフセット, 集計) のアドレス順
+    pub entries: Vec<(usize, AccessStats)>,
+}
+
+impl ProfileReport {
+    pub fn total(&self) -> AccessStats {
+        let mut total = AccessStats::default();
+        for (_, stats) in &self.entries {
+            total.merge(stats);
+        }
+        total
+    }
+
+    /// アクセス数の多い順に `n` 件（ポーリングループの発見用）。
+    pub fn hottest(&self, n: usize) -> Vec<(usize, AccessStats)> {
+        let mut entries = self.entries.clone();
+        entries.sort_by(|a, b| b.1.accesses().cmp(&a.1.accesses()).then(a.0.cmp(&b.0)));
+        entries.truncate(n);
+        entries
+    }
+
+    /// 最大レイテンシの大きい順に `n` 件。
+    pub fn slowest(&self, n: usize) -> Vec<(usize, AccessStats)> {
+        let mut entries = self.entries.clone();
+        entries.sort_by(|a, b| b.1.max_time.cmp(&a.1.max_time).then(a.0.cmp(&b.0)));
+        entries.truncate(n);
+        entries
+    }
+}
+
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>12} {:>10} {:>10} {:>8} {:>8} {:>10} {:>10} {:>10}",
+            "offset", "reads", "writes", "copies", "errors", "mean", "p99", "max"
+        )?;
+        for (offset, stats) in self.hottest(self.entries.len()) {
+            writeln!(
+                f,
+                "{:>#12x} {:>10} {:>10} {:>8} {:>8} {:>10?} {:>10?} {:>10?}",
+                offset,
+                stats.reads,
+                stats.writes,
+                stats.copies,
+                stats.errors,
+                stats.mean_time(),
+                stats.histogram.percentile(0.99),
+                stats.max_time
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 集計の置き場。clone したハンドル（と、それを使う `ProfilingAccessor`）は同じ集計を共有する。
+#[derive(Debug, Clone, Default)]
+pub struct Profiler {
+    stats: Arc<Mutex<BTreeMap<usize, AccessStats>>>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stats(&self, offset: usize) -> Option<AccessStats> {
+        self.lock().get(&offset).copied()
+    }
+
+    pub fn report(&self) -> ProfileReport {
+        ProfileReport {
+            entries: self.lock().iter().map(|(&k, &v)| (k, v)).collect(),
+        }
+    }
+
+    pub fn reset(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<usize, AccessStats>> {
+        self.stats
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn record(
+        &self,
+        kind: AccessKind,
+        offset: usize,
+        width: usize,
+        count: usize,
+        latency: Duration,
+        failed: bool,
+    ) {
+        let mut stats = self.lock();
+        let stats = stats.entry(offset).or_default();
+        match kind {
+            AccessKind::Read | AccessKind::ReadReg => stats.reads += 1,
+            AccessKind::Write | AccessKind::WriteReg => stats.writes += 1,
+            AccessKind::CopyTo | AccessKind::CopyFrom => stats.copies += 1,
+        }
+        if let Some(i) = [1, 2, 4, 8].iter().position(|&w| w == width) {
+            stats.by_width[i] += 1;
+        }
+        stats.bytes += (width * count) as u64;
+        stats.errors += failed as u64;
+        stats.total_time += latency;
+        stats.max_time = stats.max_time.max(latency);
+        stats.histogram.record(latency);
+    }
+}
+
+impl AccessHook for Profiler {
+    // アクセス開始時刻
+    type Guard = Instant;
+
+    fn enter(&self, _access: &Access) -> Result<Instant, MemAccessTryError> {
+        Ok(Instant::now())
+    }
+
+    fn exit(&self, access: &Access, start: Instant, _value: u64, _data: &[u8], ok: bool) {
+        let latency = start.elapsed();
+        // 換算がオーバーフローしたアクセスは usize::MAX にまとめる
+        let offset = access.bytes().map_or(usize::MAX, |bytes| bytes.start);
+        self.record(
+            access.kind,
+            offset,
+            access.width,
+            access.count,
+            latency,
+            !ok,
+        );
+    }
+}
+
+impl TransparentHook for Profiler {}
+
+/// 任意の `MemAccess` を包み、アドレスごと・幅ごとのアクセス数とレイテンシを `Profiler` に集計する。
+///
+/// - アドレスはラッパーを作った時点の先頭からのバイトオフセット（`subclone` 後も同じ基準）
+/// - レジスタアクセスは `reg * reg_size` バイト目として集計する（`reg_size` は内側の
+///   `MemAccessBase::reg_size()`、`new_with_reg_size` では明示した値）
+pub type ProfilingAccessor<A> = HookedAccessor<A, Profiler>;
+
+impl<A: MemAccess> HookedAccessor<A, Profiler> {
+    pub fn new(inner: A, profiler: Profiler) -> Self
+    where
+        A: MemAccessBase,
+    {
+        Self::with_hook(inner, profiler)
+    }
+
+    /// `MemAccessBase` を持たないアクセサ（`SharedBusAccessor` 等）は 1 レジスタのバイト数を明示する。
+    pub fn new_with_reg_size(inner: A, profiler: Profiler, reg_size: usize) -> Self {
+        Self::with_hook_and_reg_size(inner, profiler, reg_size)
+    }
+
+    pub fn profiler(&self) -> &Profiler {
+        self.hook()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus_accessor::LittleEndian;
+    use crate::mmio_accessor::MmioAccessor;
+    use crate::ram_bus::RamBus;
+    use crate::shared_bus_accessor::SharedBusAccessor;
+    use std::format;
+
+    #[test]
+    fn counts_per_address_and_width() {
+        let mut mem = [0u64; 8];
+        let acc = MmioAccessor::<u64>::new(mem.as_mut_ptr() as usize, 64);
+        let profiler = Profiler::new();
+        let acc = ProfilingAccessor::new(acc, profiler.clone());
+        let sub = acc.subclone(0x20, 0x20);
+
+        unsafe {
+            for _ in 0..10 {
+                acc.read_reg_u32(1);
+            }
+            acc.write_mem_u16(0x08, 1);
+            sub.write_mem_u8(0, 2);
+            let buf = [0u64; 4];
+            sub.copy_from_u64(buf.as_ptr(), 0, 4);
+        }
+
+        let stats = profiler.stats(0x08).unwrap();
+        assert_eq!((stats.reads, stats.writes), (10, 1));
+        assert_eq!(stats.by_width, [0, 1, 10, 0]);
+        assert_eq!(stats.histogram.count(), 11);
+
+        let stats = profiler.stats(0x20).unwrap();
+        assert_eq!((stats.writes, stats.copies, stats.bytes), (1, 1, 33));
+
+        let report = profiler.report();
+        assert_eq!(report.hottest(1)[0].0, 0x08);
+        assert_eq!(report.total().accesses(), 13);
+        let text = format!("{}", report);
+        assert_eq!(text.lines().count(), 3);
+        assert!(text.lines().nth(1).unwrap().trim_start().starts_with("0x8"));
+
+        profiler.reset();
+        assert!(profiler.report().entries.is_empty());
+    }
+
+    #[test]
+    fn shared_bus_latency_and_errors() {
+        let mut ram = RamBus::new(16);
+        ram.set_delay(Some(Duration::from_micros(200)));
+        let acc = SharedBusAccessor::<_, usize, u32, u8, LittleEndian>::new(ram);
+        let profiler = Profiler::new();
+        let acc = ProfilingAccessor::new_with_reg_size(acc, profiler.clone(), 4);
+
+        unsafe {
+            acc.write_mem_u32(0, 1);
+            assert!(acc.try_read_mem_u32(0x10).is_err());
+        }
+        let report = profiler.report();
+        let slow = report.slowest(1)[0].1;
+        assert!(slow.max_time >= Duration::from_micros(200));
+        assert!(slow.histogram.percentile(1.0) >= Duration::from_micros(200));
+        assert_eq!(profiler.stats(0x10).unwrap().errors, 1);
+    }
+
+    #[test]
+    fn histogram_buckets() {
+        let mut hist = LatencyHistogram::default();
+        hist.record(Duration::ZERO);
+        hist.record(Duration::from_nanos(3));
+        hist.record(Duration::from_nanos(1000));
+        assert_eq!(hist.buckets()[..3], [1, 0, 1]);
+        assert_eq!(hist.percentile(0.5), Duration::from_nanos(4));
+        assert_eq!(hist.percentile(1.0), Duration::from_nanos(1024));
+    }
+}
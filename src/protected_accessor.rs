@@ -0,0 +1,202 @@
+use core::ops::Range;
+
+use super::hooked_accessor::{Access, AccessHook, HookedAccessor};
+use super::mem_accessor::{MemAccess, MemAccessBase, MemAccessTryError};
+
+/// 領域全体を書き込み禁止にするテーブル（`as_read_only()` が使う）。
+#[allow(clippy::single_range_in_vec_init)]
+pub const READ_ONLY: &[Range<usize>] = &[0..usize::MAX];
+
+/// 書き込みを保護する範囲のテーブル（`ProtectedAccessor` のフック）。
+///
+/// `table` はラップした時点の先頭からのバイトオフセット範囲の並び
+/// （`&'static [Range<usize>]`、`Vec`、`Arc<[Range<usize>]>` など `AsRef` できるもの）。
+#[derive(Debug, Clone)]
+pub struct WriteProtection<T> {
+    table: T,
+}
+
+impl<T: AsRef<[Range<usize>]>> WriteProtection<T> {
+    pub fn new(table: T) -> Self {
+        Self { table }
+    }
+
+    pub fn table(&self) -> &T {
+        &self.table
+    }
+
+    /// `bytes` が保護範囲に重なるか（換算がオーバーフローした `None` は保護扱い）。
+    pub fn covers(&self, bytes: Option<Range<usize>>) -> bool {
+        let Some(bytes) = bytes else {
+            return true;
+        };
+        self.table
+            .as_ref()
+            .iter()
+            .any(|range| range.start < bytes.end && bytes.start < range.end)
+    }
+}
+
+impl<T: AsRef<[Range<usize>]>> AccessHook for WriteProtection<T> {
+    type Guard = ();
+
+    fn enter(&self, access: &Access) -> Result<(), MemAccessTryError> {
+        if access.is_write() && self.covers(access.bytes()) {
+            Err(MemAccessTryError::PermissionDenied)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn refused(&self, access: &Access, _error: MemAccessTryError) -> ! {
+        panic!(
+            "write to protected region (offset: 0x{:x}, len: {})",
+            access.base.wrapping_add(access.offset),
+            access.width.wrapping_mul(access.count)
+        );
+    }
+}
+
+/// 書き込み保護付きのアクセサ。
+///
+/// - 読み出しと `copy_to` はそのまま内側に委譲する
+/// - 保護範囲に重なる書き込み・`copy_from` は `try_*` なら `PermissionDenied` を返し、
+///   それ以外は panic する（内側へは一切アクセスしない）
+/// - レジスタ番号は内側の `MemAccessBase::reg_size()`（`new_with_reg_size` では明示した値）で
+///   バイトオフセットに換算し、換算がオーバーフローする書き込みは保護範囲として扱う
+/// - `subclone`/`subclone8` 等した結果も同じテーブルで保護される
+/// - 保護を抜けられないよう、内側のアクセサは外に出さない
+pub type ProtectedAccessor<A, T = &'static [Range<usize>]> = HookedAccessor<A, WriteProtection<T>>;
+
+impl<A: MemAccess, T: AsRef<[Range<usize>]>> HookedAccessor<A, WriteProtection<T>> {
+    pub fn new(inner: A, table: T) -> Self
+    where
+        A: MemAccessBase,
+    {
+        Self::with_hook(inner, WriteProtection::new(table))
+    }
+
+    /// `MemAccessBase` を持たないアクセサ（`BusAccessor` 等）は 1 レジスタのバイト数を明示する。
+    pub fn new_with_reg_size(inner: A, table: T, reg_size: usize) -> Self {
+        Self::with_hook_and_reg_size(inner, WriteProtection::new(table), reg_size)
+    }
+
+    pub fn table(&self) -> &T {
+        self.hook().table()
+    }
+
+    /// このアクセサの `offset` から `len` バイトが保護範囲に重なるか。
+    pub fn is_protected(&self, offset: usize, len: usize) -> bool {
+        let bytes = self
+            .base()
+            .checked_add(offset)
+            .and_then(|start| Some(start..start.checked_add(len.max(1))?));
+        self.hook().covers(bytes)
+    }
+}
+
+/// 書き込み保護付きのビューを作る拡張トレイト。
+///
+/// 監視用スレッドには `as_read_only()` を渡し、制御用スレッドだけが元のアクセサを持つ。
+pub trait WriteProtect: MemAccess + MemAccessBase + Sized {
+    /// すべての書き込みを拒否するビュー。
+    fn as_read_only(&self) -> ProtectedAccessor<Self> {
+        self.write_protected(READ_ONLY)
+    }
+
+    /// `table` の範囲（このアクセサ先頭からのバイトオフセット）への書き込みを拒否するビュー。
+    fn write_protected<T: AsRef<[Range<usize>]>>(&self, table: T) -> ProtectedAccessor<Self, T> {
+        ProtectedAccessor::new(self.subclone(0, 0), table)
+    }
+}
+
+impl<A: MemAccess + MemAccessBase> WriteProtect for A {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MmioAccessor;
+
+    #[test]
+    fn read_only_view() {
+        let mut buf = [0u32; 4];
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 16);
+        let monitor = mmio.as_read_only();
+        unsafe {
+            mmio.write_mem_u32(0x4, 0x1234_5678);
+            assert_eq!(monitor.read_mem_u32(0x4), 0x1234_5678);
+            assert_eq!(monitor.try_read_reg_u32(1), Ok(0x1234_5678));
+            assert_eq!(
+                monitor.try_write_mem_u8(0x0, 0xff),
+                Err(MemAccessTryError::PermissionDenied)
+            );
+            assert_eq!(
+                monitor.try_write_reg(3, 1),
+                Err(MemAccessTryError::PermissionDenied)
+            );
+            let src = [1u16; 2];
+            assert_eq!(
+                monitor.try_copy_from_u16(src.as_ptr(), 0x8, 2),
+                Err(MemAccessTryError::PermissionDenied)
+            );
+            assert_eq!(mmio.read_mem_u64(0x8), 0);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn protected_ranges() {
+        // 0x8..0xc を PLL 設定レジスタとして保護する
+        let mut buf = [0u32; 8];
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 32);
+        let guarded = mmio.write_protected(&[0x8..0xc][..]);
+        unsafe {
+            assert_eq!(guarded.try_write_reg_u32(1, 0x11), Ok(()));
+            assert_eq!(
+                guarded.try_write_reg_u32(2, 0x22),
+                Err(MemAccessTryError::PermissionDenied)
+            );
+            assert_eq!(
+                guarded.try_write_mem_u64(0x4, 0x33),
+                Err(MemAccessTryError::PermissionDenied)
+            );
+            assert_eq!(guarded.try_write_mem_u32(0xc, 0x44), Ok(()));
+
+            // subclone してもオフセットは元の先頭基準で保護される
+            let sub = guarded.subclone(0x8, 0x10);
+            assert_eq!(
+                sub.try_write_mem_u8(0x3, 0x55),
+                Err(MemAccessTryError::PermissionDenied)
+            );
+            assert_eq!(sub.try_write_mem_u32(0x4, 0x66), Ok(()));
+            assert!(guarded.is_protected(0x8, 4));
+            assert!(!sub.is_protected(0x4, 4));
+
+            // 型を変えた subclone も元の先頭基準で、レジスタ番号は新しい幅で換算する
+            let bytes = guarded.subclone8(0x4, 0);
+            assert_eq!(
+                bytes.try_write_reg_u8(4, 0x77),
+                Err(MemAccessTryError::PermissionDenied)
+            );
+            assert_eq!(bytes.try_write_reg_u8(3, 0x77), Ok(()));
+
+            // 換算がオーバーフローするレジスタ番号は保護範囲として扱う
+            assert_eq!(
+                guarded.try_write_reg_u32(usize::MAX / 2, 0),
+                Err(MemAccessTryError::PermissionDenied)
+            );
+
+            assert_eq!(mmio.read_reg_u32(1), 0x7700_0011);
+            assert_eq!(mmio.read_reg_u32(2), 0);
+            assert_eq!(mmio.read_reg_u32(3), 0x66);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "protected")]
+    fn write_panics() {
+        let mut buf = [0u32; 4];
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 16);
+        unsafe { mmio.as_read_only().write_reg_u32(0, 1) };
+    }
+}
@@ -0,0 +1,397 @@
+#![allow(dead_code)]
+
+use core::fmt;
+use std::boxed::Box;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use std::vec;
+use std::vec::Vec;
+
+use super::bus_accessor::{burst_addr, Bus, BusAddress, BusWord};
+
+const SPARSE_PAGE_BYTES: usize = 4096;
+
+/// `RamBus` のエラー（`addr` はアクセスしたバイトアドレス）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RamBusError {
+    OutOfRange(usize),
+    Misaligned(usize),
+    ReadOnly(usize),
+    WriteOnly(usize),
+    Fault(usize),
+}
+
+impl fmt::Display for RamBusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfRange(addr) => write!(f, "address 0x{addr:x} is out of range"),
+            Self::Misaligned(addr) => write!(f, "address 0x{addr:x} is not word aligned"),
+            Self::ReadOnly(addr) => write!(f, "write to read-only address 0x{addr:x}"),
+            Self::WriteOnly(addr) => write!(f, "read from write-only address 0x{addr:x}"),
+            Self::Fault(addr) => write!(f, "access fault at address 0x{addr:x}"),
+        }
+    }
+}
+
+impl std::error::Error for RamBusError {}
+
+/// `RamBus::add_region` で設定する範囲の属性。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RamRegionKind {
+    /// 書き込みは `RamBusError::ReadOnly`（読み出しは可能）
+    ReadOnly,
+    /// 読み出しは `RamBusError::WriteOnly`（書き込みは可能）
+    WriteOnly,
+    /// 読み書きとも `RamBusError::Fault`（SLVERR/DECERR の模擬）
+    Fault,
+}
+
+/// `RamBus` のアクセスカウンタ。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RamBusStats {
+    /// 単発 `write`（バースト内のビートは含まない）
+    pub writes: usize,
+    /// 単発 `read`
+    pub reads: usize,
+    pub write_bursts: usize,
+    pub read_bursts: usize,
+    /// 書き込まれたバイト数（strb 有効レーンのみ）
+    pub bytes_written: usize,
+    pub bytes_read: usize,
+    /// エラーになったアクセス数
+    pub errors: usize,
+    /// ウェイトを含む模擬サイクル数（1 ビート = 1 + ウェイト）
+    pub cycles: u64,
+}
+
+#[derive(Debug)]
+enum Storage {
+    Dense(Vec<u8>),
+    Sparse(BTreeMap<usize, Box<[u8; SPARSE_PAGE_BYTES]>>),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Region {
+    base: usize,
+    size: usize,
+    kind: RamRegionKind,
+}
+
+/// テスト用のメモリモデル。任意の `A`/`D`/`S` で `Bus` を実装する。
+///
+/// - アドレスはバイトアドレス、バイトレーン i はアドレス `addr + i`
+/// - `strb` の有効レーンのみ書き込む（`S = ()` は全レーン）
+/// - 密（`new`）または疎（`sparse`、4 KiB ページ単位で確保）なバックストア
+/// - `add_region` で範囲ごとに読み出し専用・書き込み専用・エラーを設定できる
+/// - ウェイトサイクルと実時間の遅延、アクセスカウンタを持つ
+#[derive(Debug)]
+pub struct RamBus {
+    storage: Storage,
+    size: usize,
+    regions: Vec<Region>,
+    read_wait: u64,
+    write_wait: u64,
+    delay: Option<Duration>,
+    stats: RamBusStats,
+}
+
+impl RamBus {
+    /// `size` バイトのゼロ初期化済みメモリを確保する。
+    pub fn new(size: usize) -> Self {
+        Self::with_storage(Storage::Dense(vec![0; size]), size)
+    }
+
+    /// `size` バイトの疎なメモリを作る。未書き込みの領域は 0 として読める。
+    pub fn sparse(size: usize) -> Self {
+        Self::with_storage(Storage::Sparse(BTreeMap::new()), size)
+    }
+
+    fn with_storage(storage: Storage, size: usize) -> Self {
+        Self {
+            storage,
+            size,
+            regions: Vec::new(),
+            read_wait: 0,
+            write_wait: 0,
+            delay: None,
+            stats: RamBusStats::default(),
+        }
+    }
+
+    /// アドレス空間のサイズを返す。
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// `[base, base + size)` に属性を設定する。重なる場合は後から追加したものが優先。
+    pub fn add_region(&mut self, base: usize, size: usize, kind: RamRegionKind) {
+        self.regions.push(Region { base, size, kind });
+    }
+
+    /// `add_region` のビルダー版。
+    pub fn with_region(mut self, base: usize, size: usize, kind: RamRegionKind) -> Self {
+        self.add_region(base, size, kind);
+        self
+    }
+
+    /// 設定した範囲属性をすべて解除する。
+    pub fn clear_regions(&mut self) {
+        self.regions.clear();
+    }
+
+    /// 1 ビートあたりのウェイトサイクルを設定する（`stats().cycles` に加算）。
+    pub fn set_wait_states(&mut self, read_wait: u64, write_wait: u64) {
+        self.read_wait = read_wait;
+        self.write_wait = write_wait;
+    }
+
+    /// アクセス（単発・バーストとも 1 回）ごとに実時間で待つ。`None` で無効。
+    pub fn set_delay(&mut self, delay: Option<Duration>) {
+        self.delay = delay;
+    }
+
+    /// カウンタを返す。
+    pub fn stats(&self) -> &RamBusStats {
+        &self.stats
+    }
+
+    /// カウンタをクリアする。
+    pub fn reset_stats(&mut self) {
+        self.stats = RamBusStats::default();
+    }
+
+    /// 範囲属性・カウンタを無視してメモリに直接書き込む（初期データのロードなど）。
+    pub fn load(&mut self, addr: usize, data: &[u8]) -> Result<(), RamBusError> {
+        self.check_range(addr, data.len())?;
+        for (i, &byte) in data.iter().enumerate() {
+            self.store_byte(addr + i, byte);
+        }
+        Ok(())
+    }
+
+    /// 範囲属性・カウンタを無視してメモリを直接読み出す。
+    pub fn dump(&self, addr: usize, data: &mut [u8]) -> Result<(), RamBusError> {
+        self.check_range(addr, data.len())?;
+        for (i, byte) in data.iter_mut().enumerate() {
+            *byte = self.load_byte(addr + i);
+        }
+        Ok(())
+    }
+
+    fn check_range(&self, addr: usize, bytes: usize) -> Result<(), RamBusError> {
+        match addr.checked_add(bytes) {
+            Some(end) if end <= self.size => Ok(()),
+            _ => Err(RamBusError::OutOfRange(addr)),
+        }
+    }
+
+    fn region_kind(&self, addr: usize, bytes: usize) -> Option<RamRegionKind> {
+        self.regions
+            .iter()
+            .rev()
+            .find(|r| addr < r.base.saturating_add(r.size) && r.base < addr + bytes)
+            .map(|r| r.kind)
+    }
+
+    fn check_access(&self, addr: usize, bytes: usize, write: bool) -> Result<(), RamBusError> {
+        if bytes != 0 && addr % bytes != 0 {
+            return Err(RamBusError::Misaligned(addr));
+        }
+        self.check_range(addr, bytes)?;
+        match self.region_kind(addr, bytes) {
+            Some(RamRegionKind::Fault) => Err(RamBusError::Fault(addr)),
+            Some(RamRegionKind::ReadOnly) if write => Err(RamBusError::ReadOnly(addr)),
+            Some(RamRegionKind::WriteOnly) if !write => Err(RamBusError::WriteOnly(addr)),
+            _ => Ok(()),
+        }
+    }
+
+    fn load_byte(&self, addr: usize) -> u8 {
+        match &self.storage {
+            Storage::Dense(mem) => mem[addr],
+            Storage::Sparse(pages) => pages
+                .get(&(addr / SPARSE_PAGE_BYTES))
+                .map_or(0, |page| page[addr % SPARSE_PAGE_BYTES]),
+        }
+    }
+
+    fn store_byte(&mut self, addr: usize, value: u8) {
+        match &mut self.storage {
+            Storage::Dense(mem) => mem[addr] = value,
+            Storage::Sparse(pages) => {
+                let page = pages
+                    .entry(addr / SPARSE_PAGE_BYTES)
+                    .or_insert_with(|| Box::new([0; SPARSE_PAGE_BYTES]));
+                page[addr % SPARSE_PAGE_BYTES] = value;
+            }
+        }
+    }
+
+    fn wait(&mut self, beats: usize, wait_states: u64) {
+        self.stats.cycles += beats as u64 * (1 + wait_states);
+        if let Some(delay) = self.delay {
+            std::thread::sleep(delay);
+        }
+    }
+
+    fn write_word<D: BusWord, S: BusWord>(
+        &mut self,
+        addr: usize,
+        data: &D,
+        strb: &S,
+    ) -> Result<(), RamBusError> {
+        if let Err(err) = self.check_access(addr, D::BYTES, true) {
+            self.stats.errors += 1;
+            return Err(err);
+        }
+        for lane in 0..D::BYTES {
+            if S::BITS == 0 || (lane < S::BITS && strb.bit(lane)) {
+                self.store_byte(addr + lane, data.lane(lane));
+                self.stats.bytes_written += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_word<D: BusWord>(&mut self, addr: usize) -> Result<D, RamBusError> {
+        if let Err(err) = self.check_access(addr, D::BYTES, false) {
+            self.stats.errors += 1;
+            return Err(err);
+        }
+        let mut word = D::zero();
+        for lane in 0..D::BYTES {
+            word.set_lane(lane, self.load_byte(addr + lane));
+        }
+        self.stats.bytes_read += D::BYTES;
+        Ok(word)
+    }
+}
+
+impl<A, D, S> Bus<A, D, S> for RamBus
+where
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+{
+    type Error = RamBusError;
+
+    fn write(&mut self, addr: A, data: D, strb: S) -> Result<(), Self::Error> {
+        self.stats.writes += 1;
+        self.wait(1, self.write_wait);
+        self.write_word(addr.to_usize(), &data, &strb)
+    }
+
+    fn read(&mut self, addr: A) -> Result<D, Self::Error> {
+        self.stats.reads += 1;
+        self.wait(1, self.read_wait);
+        self.read_word(addr.to_usize())
+    }
+
+    fn write_burst(&mut self, addr: A, data: &[D], strb: &[S]) -> Result<(), Self::Error>
+    where
+        A: BusAddress,
+        D: BusWord,
+        S: Copy,
+    {
+        self.stats.write_bursts += 1;
+        self.wait(data.len(), self.write_wait);
+        let base = addr.to_usize();
+        for (i, (data, strb)) in data.iter().zip(strb).enumerate() {
+            let addr = burst_addr(base, i, D::BYTES).ok_or(RamBusError::OutOfRange(base))?;
+            self.write_word(addr, data, strb)?;
+        }
+        Ok(())
+    }
+
+    fn read_burst(&mut self, addr: A, data: &mut [D]) -> Result<(), Self::Error>
+    where
+        A: BusAddress,
+        D: BusWord,
+    {
+        self.stats.read_bursts += 1;
+        self.wait(data.len(), self.read_wait);
+        let base = addr.to_usize();
+        for (i, data) in data.iter_mut().enumerate() {
+            let addr = burst_addr(base, i, D::BYTES).ok_or(RamBusError::OutOfRange(base))?;
+            *data = self.read_word(addr)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus_accessor::{BusAccessor, LittleEndian};
+    use crate::MemAccess;
+
+    #[test]
+    fn strobed_writes_and_stats() {
+        let mut ram = RamBus::new(64);
+        ram.set_wait_states(2, 1);
+
+        Bus::<usize, u32, u8>::write(&mut ram, 4, 0x1122_3344, 0b1010).unwrap();
+        let word: u32 = Bus::<usize, u32, u8>::read(&mut ram, 4).unwrap();
+        assert_eq!(word, 0x1100_3300);
+        assert_eq!(
+            Bus::<usize, u32, u8>::read(&mut ram, 2),
+            Err(RamBusError::Misaligned(2))
+        );
+        assert_eq!(
+            Bus::<usize, u32, u8>::read(&mut ram, 64),
+            Err(RamBusError::OutOfRange(64))
+        );
+
+        let stats = *ram.stats();
+        assert_eq!((stats.writes, stats.reads, stats.errors), (1, 3, 2));
+        assert_eq!((stats.bytes_written, stats.bytes_read), (2, 4));
+        assert_eq!(stats.cycles, 2 + 3 * 3);
+    }
+
+    #[test]
+    fn regions() {
+        let mut ram = RamBus::new(64)
+            .with_region(0x10, 0x10, RamRegionKind::ReadOnly)
+            .with_region(0x20, 0x10, RamRegionKind::WriteOnly)
+            .with_region(0x30, 0x04, RamRegionKind::Fault);
+        ram.load(0x10, &[0xAA; 4]).unwrap();
+
+        assert_eq!(
+            Bus::<usize, u32, u8>::write(&mut ram, 0x10, 0, 0xF),
+            Err(RamBusError::ReadOnly(0x10))
+        );
+        assert_eq!(Bus::<usize, u32, u8>::read(&mut ram, 0x10), Ok(0xAAAA_AAAA));
+        Bus::<usize, u32, u8>::write(&mut ram, 0x20, 0x55, 0xF).unwrap();
+        assert_eq!(
+            Bus::<usize, u32, u8>::read(&mut ram, 0x20),
+            Err(RamBusError::WriteOnly(0x20))
+        );
+        assert_eq!(
+            Bus::<usize, u32, u8>::read(&mut ram, 0x30),
+            Err(RamBusError::Fault(0x30))
+        );
+
+        let mut buf = [0u8; 4];
+        ram.dump(0x20, &mut buf).unwrap();
+        assert_eq!(buf, [0x55, 0, 0, 0]);
+    }
+
+    #[test]
+    fn sparse_storage_with_accessor_bursts() {
+        let ram = RamBus::sparse(1 << 30);
+        let accessor = BusAccessor::<_, usize, u64, u8, LittleEndian>::new(ram);
+
+        let src: [u32; 64] = core::array::from_fn(|i| i as u32 * 3);
+        let mut dst = [0u32; 64];
+        unsafe {
+            accessor.copy_from_u32(src.as_ptr(), 0x2000_0000, src.len());
+            accessor.copy_to_u32(0x2000_0000, dst.as_mut_ptr(), dst.len());
+        }
+        assert_eq!(src, dst);
+        assert_eq!(accessor.read_u64(0x3FFF_FFF8).unwrap(), 0);
+
+        let stats = *accessor.bus().stats();
+        assert_eq!((stats.write_bursts, stats.read_bursts), (1, 1));
+        assert_eq!(stats.bytes_written, 256);
+    }
+}
@@ -0,0 +1,432 @@
+#![allow(dead_code)]
+
+use core::fmt;
+use std::boxed::Box;
+use std::collections::{BTreeMap, VecDeque};
+
+use super::bus_accessor::{Bus, BusAddress, BusWord};
+
+/// `RegisterModel` のエラー（`addr` はアクセスしたバイトアドレス）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterModelError {
+    /// レジスタが定義されていないアドレス（DECERR の模擬）
+    Unmapped(usize),
+    /// バスワードが 64bit を超える
+    TooWide(usize),
+}
+
+impl fmt::Display for RegisterModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unmapped(addr) => write!(f, "no register at address 0x{addr:x}"),
+            Self::TooWide(addr) => {
+                write!(f, "bus word wider than 64 bits at address 0x{addr:x}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegisterModelError {}
+
+/// レジスタ 1 本の振る舞い。ビットごとの属性はマスクで指定する。
+///
+/// - `read_only` : 書き込みを無視するビット（ステータスなど）
+/// - `write_1_to_clear` : 1 を書いたビットだけクリア（割り込みステータスなど）
+/// - `read_to_clear` : 読み出した後にクリア
+/// - `self_clearing` : 1 を書くと立ち、次の読み出しでは 1、その後 0 に戻る（ソフトリセット等）
+/// - `fifo` : データポート。読み出しは RX キューから取り出し（空なら 0）、書き込みは TX キューに積む
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegisterSpec {
+    pub reset: u64,
+    pub read_only: u64,
+    pub write_1_to_clear: u64,
+    pub read_to_clear: u64,
+    pub self_clearing: u64,
+    pub fifo: bool,
+}
+
+impl RegisterSpec {
+    /// 全ビット読み書き可能なレジスタ。
+    pub const fn new(reset: u64) -> Self {
+        Self {
+            reset,
+            read_only: 0,
+            write_1_to_clear: 0,
+            read_to_clear: 0,
+            self_clearing: 0,
+            fifo: false,
+        }
+    }
+
+    /// 全ビット読み出し専用のレジスタ。
+    pub const fn read_only(value: u64) -> Self {
+        Self::new(value).with_read_only(!0)
+    }
+
+    /// FIFO データポート。
+    pub const fn fifo() -> Self {
+        let mut spec = Self::new(0);
+        spec.fifo = true;
+        spec
+    }
+
+    pub const fn with_read_only(mut self, mask: u64) -> Self {
+        self.read_only = mask;
+        self
+    }
+
+    pub const fn with_write_1_to_clear(mut self, mask: u64) -> Self {
+        self.write_1_to_clear = mask;
+        self
+    }
+
+    pub const fn with_read_to_clear(mut self, mask: u64) -> Self {
+        self.read_to_clear = mask;
+        self
+    }
+
+    pub const fn with_self_clearing(mut self, mask: u64) -> Self {
+        self.self_clearing = mask;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RegisterState {
+    spec: RegisterSpec,
+    value: u64,
+    // 読み出し後にクリアする self-clearing ビット
+    pending_clear: u64,
+    rx: VecDeque<u64>,
+    tx: VecDeque<u64>,
+}
+
+/// レジスタの値と FIFO。コールバックからは副作用なしでここを操作する。
+#[derive(Debug, Clone, Default)]
+pub struct RegisterBank {
+    regs: BTreeMap<usize, RegisterState>,
+}
+
+impl RegisterBank {
+    pub fn contains(&self, addr: usize) -> bool {
+        self.regs.contains_key(&addr)
+    }
+
+    /// 現在値（副作用なし）。
+    pub fn get(&self, addr: usize) -> Option<u64> {
+        self.regs.get(&addr).map(|reg| reg.value)
+    }
+
+    /// 値を直接設定する（属性・コールバックは無視）。未定義アドレスなら何もしない。
+    pub fn set(&mut self, addr: usize, value: u64) {
+        if let Some(reg) = self.regs.get_mut(&addr) {
+            reg.value = value;
+        }
+    }
+
+    pub fn set_bits(&mut self, addr: usize, mask: u64) {
+        if let Some(reg) = self.regs.get_mut(&addr) {
+            reg.value |= mask;
+        }
+    }
+
+    pub fn clear_bits(&mut self, addr: usize, mask: u64) {
+        if let Some(reg) = self.regs.get_mut(&addr) {
+            reg.value &= !mask;
+        }
+    }
+
+    /// FIFO ポートの RX キューに積む（ドライバの読み出しで取り出される）。
+    pub fn push_rx(&mut self, addr: usize, value: u64) {
+        if let Some(reg) = self.regs.get_mut(&addr) {
+            reg.rx.push_back(value);
+        }
+    }
+
+    /// FIFO ポートにドライバが書いた値を古い順に取り出す。
+    pub fn pop_tx(&mut self, addr: usize) -> Option<u64> {
+        self.regs.get_mut(&addr)?.tx.pop_front()
+    }
+
+    pub fn rx_len(&self, addr: usize) -> usize {
+        self.regs.get(&addr).map_or(0, |reg| reg.rx.len())
+    }
+
+    pub fn tx_len(&self, addr: usize) -> usize {
+        self.regs.get(&addr).map_or(0, |reg| reg.tx.len())
+    }
+}
+
+type ReadHook = Box<dyn FnMut(&mut RegisterBank) + Send>;
+type WriteHook = Box<dyn FnMut(&mut RegisterBank, u64) + Send>;
+
+#[derive(Default)]
+struct RegisterHooks {
+    on_read: Option<ReadHook>,
+    on_write: Option<WriteHook>,
+}
+
+/// 読み書きに副作用を持つレジスタブロックのモデル。
+///
+/// 任意のワード幅（64bit まで）の `Bus` として振る舞うので、`SharedBusAccessor` 等で包めば
+/// `MemAccess` として UIO ドライバのテストに使える。
+///
+/// - レジスタはバイトアドレスで定義し、1 回のアクセスは 1 レジスタに対応する
+/// - 未定義アドレスは `RegisterModelError::Unmapped`
+/// - strb が無効なレーンは書き込まれない（W1C も同様）
+/// - `on_read` は値を返す前に、`on_write` は書き込み反映後に呼ばれる
+///   （ステータスを立てる、別レジスタを更新する、テストへ通知する等に使う）
+#[derive(Default)]
+pub struct RegisterModel {
+    bank: RegisterBank,
+    hooks: BTreeMap<usize, RegisterHooks>,
+}
+
+impl fmt::Debug for RegisterModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegisterModel")
+            .field("bank", &self.bank)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RegisterModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `addr` にレジスタを定義する（既存なら置き換えてリセット値に戻す）。
+    pub fn add(&mut self, addr: usize, spec: RegisterSpec) {
+        self.bank.regs.insert(
+            addr,
+            RegisterState {
+                spec,
+                value: spec.reset,
+                pending_clear: 0,
+                rx: VecDeque::new(),
+                tx: VecDeque::new(),
+            },
+        );
+    }
+
+    pub fn with_register(mut self, addr: usize, spec: RegisterSpec) -> Self {
+        self.add(addr, spec);
+        self
+    }
+
+    pub fn on_read(&mut self, addr: usize, f: impl FnMut(&mut RegisterBank) + Send + 'static) {
+        self.hooks.entry(addr).or_default().on_read = Some(Box::new(f));
+    }
+
+    /// `f` には strb 適用前のバス上の書き込み値が渡される。
+    pub fn on_write(
+        &mut self,
+        addr: usize,
+        f: impl FnMut(&mut RegisterBank, u64) + Send + 'static,
+    ) {
+        self.hooks.entry(addr).or_default().on_write = Some(Box::new(f));
+    }
+
+    /// 全レジスタをリセット値に戻し、FIFO を空にする。
+    pub fn reset(&mut self) {
+        for reg in self.bank.regs.values_mut() {
+            reg.value = reg.spec.reset;
+            reg.pending_clear = 0;
+            reg.rx.clear();
+            reg.tx.clear();
+        }
+    }
+
+    pub fn bank(&self) -> &RegisterBank {
+        &self.bank
+    }
+
+    pub fn bank_mut(&mut self) -> &mut RegisterBank {
+        &mut self.bank
+    }
+
+    fn write_register(
+        &mut self,
+        addr: usize,
+        data: u64,
+        lanes: u64,
+    ) -> Result<(), RegisterModelError> {
+        let reg = self
+            .bank
+            .regs
+            .get_mut(&addr)
+            .ok_or(RegisterModelError::Unmapped(addr))?;
+        let spec = reg.spec;
+        if spec.fifo {
+            reg.tx.push_back(data & lanes);
+        } else {
+            let writable = lanes & !spec.read_only & !spec.write_1_to_clear;
+            let cleared = data & lanes & spec.write_1_to_clear;
+            reg.value = (reg.value & !writable & !cleared) | (data & writable);
+            reg.pending_clear |= data & writable & spec.self_clearing;
+        }
+
+        if let Some(hook) = self.hooks.get_mut(&addr).and_then(|h| h.on_write.as_mut()) {
+            hook(&mut self.bank, data);
+        }
+        Ok(())
+    }
+
+    fn read_register(&mut self, addr: usize) -> Result<u64, RegisterModelError> {
+        if !self.bank.contains(addr) {
+            return Err(RegisterModelError::Unmapped(addr));
+        }
+        if let Some(hook) = self.hooks.get_mut(&addr).and_then(|h| h.on_read.as_mut()) {
+            hook(&mut self.bank);
+        }
+
+        let reg = self.bank.regs.get_mut(&addr).unwrap();
+        if reg.spec.fifo {
+            return Ok(reg.rx.pop_front().unwrap_or(0));
+        }
+        let value = reg.value;
+        reg.value &= !reg.spec.read_to_clear & !reg.pending_clear;
+        reg.pending_clear = 0;
+        Ok(value)
+    }
+}
+
+impl<A, D, S> Bus<A, D, S> for RegisterModel
+where
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+{
+    type Error = RegisterModelError;
+
+    fn write(&mut self, addr: A, data: D, strb: S) -> Result<(), Self::Error> {
+        let addr = addr.to_usize();
+        if D::BYTES > 8 {
+            return Err(RegisterModelError::TooWide(addr));
+        }
+        let mut value = 0u64;
+        let mut lanes = 0u64;
+        for lane in 0..D::BYTES {
+            value |= (data.lane(lane) as u64) << (lane * 8);
+            if S::BITS == 0 || (lane < S::BITS && strb.bit(lane)) {
+                lanes |= 0xff << (lane * 8);
+            }
+        }
+        self.write_register(addr, value, lanes)
+    }
+
+    fn read(&mut self, addr: A) -> Result<D, Self::Error> {
+        let addr = addr.to_usize();
+        if D::BYTES > 8 {
+            return Err(RegisterModelError::TooWide(addr));
+        }
+        let value = self.read_register(addr)?;
+        let mut word = D::zero();
+        for lane in 0..D::BYTES {
+            word.set_lane(lane, (value >> (lane * 8)) as u8);
+        }
+        Ok(word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axi_dma::*;
+    use crate::bus_accessor::LittleEndian;
+    use crate::shared_bus_accessor::SharedBusAccessor;
+    use crate::MemAccess;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    type Regs = SharedBusAccessor<RegisterModel, usize, u32, u8, LittleEndian>;
+
+    #[test]
+    fn register_side_effects() {
+        let model = RegisterModel::new()
+            .with_register(0x00, RegisterSpec::new(0).with_read_only(0xff00))
+            .with_register(0x04, RegisterSpec::new(0x7).with_write_1_to_clear(0x7))
+            .with_register(0x08, RegisterSpec::new(0x3).with_read_to_clear(0x1))
+            .with_register(0x0c, RegisterSpec::read_only(0x1234_5678));
+        let regs = Regs::new(model);
+
+        unsafe {
+            regs.write_reg_u32(0, 0xffff);
+            assert_eq!(regs.read_reg_u32(0), 0x00ff);
+
+            regs.write_reg_u32(1, 0x5);
+            assert_eq!(regs.read_reg_u32(1), 0x2);
+
+            assert_eq!(regs.read_reg_u32(2), 0x3);
+            assert_eq!(regs.read_reg_u32(2), 0x2);
+
+            regs.write_reg_u32(3, 0);
+            assert_eq!(regs.read_reg_u32(3), 0x1234_5678);
+
+            // 部分書き込みは strb のレーンだけ反映
+            regs.write_mem_u8(0x01, 0xaa);
+            assert_eq!(regs.read_reg_u32(0), 0x00ff);
+            regs.write_mem_u8(0x00, 0x11);
+            assert_eq!(regs.read_reg_u32(0), 0x0011);
+        }
+        assert_eq!(
+            Bus::<usize, u32, u8>::read(&mut RegisterModel::new(), 0x10),
+            Err(RegisterModelError::Unmapped(0x10))
+        );
+    }
+
+    #[test]
+    fn fifo_port_and_callbacks() {
+        let mut model = RegisterModel::new()
+            .with_register(0x00, RegisterSpec::fifo())
+            .with_register(0x04, RegisterSpec::read_only(0));
+        model.bank_mut().push_rx(0x00, 0xab);
+        model.bank_mut().push_rx(0x00, 0xcd);
+
+        // FIFO へ書くとステータスの「TX あり」ビットを立てる
+        let writes = Arc::new(AtomicUsize::new(0));
+        let counter = writes.clone();
+        model.on_write(0x00, move |bank, _| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            bank.set_bits(0x04, 0x1);
+        });
+        // RX 残数をステータス上位に見せる
+        model.on_read(0x04, |bank| {
+            let status = bank.get(0x04).unwrap() & 0xff;
+            bank.set(0x04, status | (bank.rx_len(0x00) as u64) << 8);
+        });
+
+        let regs = Regs::new(model);
+        unsafe {
+            assert_eq!(regs.read_reg_u32(1), 0x200);
+            assert_eq!(regs.read_reg_u32(0), 0xab);
+            assert_eq!(regs.read_reg_u32(0), 0xcd);
+            assert_eq!(regs.read_reg_u32(0), 0);
+            regs.write_reg_u32(0, 0x55);
+            regs.write_reg_u32(0, 0x66);
+            assert_eq!(regs.read_reg_u32(1), 0x001);
+        }
+        assert_eq!(writes.load(Ordering::Relaxed), 2);
+
+        let mut model = regs.into_inner().unwrap();
+        assert_eq!(model.bank_mut().pop_tx(0x00), Some(0x55));
+        assert_eq!(model.bank_mut().pop_tx(0x00), Some(0x66));
+        assert_eq!(model.bank_mut().pop_tx(0x00), None);
+    }
+
+    #[test]
+    fn axi_dma_soft_reset() {
+        let model = RegisterModel::new()
+            .with_register(
+                AXI_DMA_MM2S_DMACR,
+                RegisterSpec::new(0).with_self_clearing(AXI_DMA_DMACR_RESET as u64),
+            )
+            .with_register(AXI_DMA_MM2S_DMASR, RegisterSpec::read_only(1));
+        let dma = AxiDma::new(Regs::new(model));
+
+        unsafe {
+            dma.reset(1).unwrap_err();
+            dma.reset(2).unwrap();
+        }
+    }
+}
@@ -0,0 +1,416 @@
+use super::*;
+use delegate::delegate;
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::mem::{align_of, size_of};
+
+// Ordinary memory borrowed from a byte slice (for tests and host simulation).
+//
+// `new` takes the slice by `&mut` so nothing else touches it for `'a`, but clones and
+// subclones alias it freely and write through `&self`, like `&'a [Cell<u8>]`. The
+// marker says so, which also keeps the region (and its accessors) on one thread.
+#[derive(Debug)]
+pub struct SliceRegion<'a> {
+    addr: usize,
+    size: usize,
+    _buf: PhantomData<&'a [Cell<u8>]>,
+}
+
+impl<'a> SliceRegion<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        SliceRegion {
+            addr: buf.as_mut_ptr() as usize,
+            size: buf.len(),
+            _buf: PhantomData,
+        }
+    }
+}
+
+impl MemRegion for SliceRegion<'_> {
+    fn subclone(&self, offset: usize, size: usize) -> Self {
+        expect_subclone(self.try_subclone(offset, size), offset, size)
+    }
+
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        let new_size = subregion_size(self.size, offset, size)?;
+        Ok(SliceRegion {
+            addr: self.addr + offset,
+            size: new_size,
+            _buf: PhantomData,
+        })
+    }
+
+    fn addr(&self) -> usize {
+        self.addr
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn phys_addr(&self) -> usize {
+        self.addr()
+    }
+}
+
+impl Clone for SliceRegion<'_> {
+    fn clone(&self) -> Self {
+        self.subclone(0, 0)
+    }
+}
+
+#[derive(Debug)]
+pub struct SliceAccessor<'a, U> {
+    mem_accessor: MemAccessor<SliceRegion<'a>, U>,
+}
+
+impl<'a, U> From<SliceAccessor<'a, U>> for MemAccessor<SliceRegion<'a>, U> {
+    fn from(from: SliceAccessor<'a, U>) -> MemAccessor<SliceRegion<'a>, U> {
+        from.mem_accessor
+    }
+}
+
+impl<'a, U> SliceAccessor<'a, U> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self {
+            mem_accessor: MemAccessor::<SliceRegion<'a>, U>::new(SliceRegion::new(buf)),
+        }
+    }
+
+    pub fn subclone_<NewU>(&self, offset: usize, size: usize) -> SliceAccessor<'a, NewU> {
+        SliceAccessor::<'a, NewU> {
+            mem_accessor: MemAccessor::<SliceRegion<'a>, NewU>::new(
+                self.mem_accessor.region().subclone(offset, size),
+            ),
+        }
+    }
+
+    pub fn try_subclone_<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<SliceAccessor<'a, NewU>, MemAccessTryError> {
+        Ok(SliceAccessor::<'a, NewU> {
+            mem_accessor: MemAccessor::<SliceRegion<'a>, NewU>::new(
+                self.mem_accessor.region().try_subclone(offset, size)?,
+            ),
+        })
+    }
+
+    pub fn subclone8(&self, offset: usize, size: usize) -> SliceAccessor<'a, u8> {
+        self.subclone_::<u8>(offset, size)
+    }
+
+    pub fn subclone16(&self, offset: usize, size: usize) -> SliceAccessor<'a, u16> {
+        self.subclone_::<u16>(offset, size)
+    }
+
+    pub fn subclone32(&self, offset: usize, size: usize) -> SliceAccessor<'a, u32> {
+        self.subclone_::<u32>(offset, size)
+    }
+
+    pub fn subclone64(&self, offset: usize, size: usize) -> SliceAccessor<'a, u64> {
+        self.subclone_::<u64>(offset, size)
+    }
+
+    pub fn try_subclone8(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<SliceAccessor<'a, u8>, MemAccessTryError> {
+        self.try_subclone_::<u8>(offset, size)
+    }
+
+    pub fn try_subclone16(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<SliceAccessor<'a, u16>, MemAccessTryError> {
+        self.try_subclone_::<u16>(offset, size)
+    }
+
+    pub fn try_subclone32(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<SliceAccessor<'a, u32>, MemAccessTryError> {
+        self.try_subclone_::<u32>(offset, size)
+    }
+
+    pub fn try_subclone64(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<SliceAccessor<'a, u64>, MemAccessTryError> {
+        self.try_subclone_::<u64>(offset, size)
+    }
+
+    delegate! {
+        to self.mem_accessor.region() {
+            pub fn addr(&self) -> usize;
+            pub fn size(&self) -> usize;
+        }
+    }
+}
+
+impl<'a, U> Clone for SliceAccessor<'a, U> {
+    fn clone(&self) -> Self {
+        self.subclone_::<U>(0, 0)
+    }
+}
+
+impl<'a, U> SubcloneAs for SliceAccessor<'a, U> {
+    type As<NewU> = SliceAccessor<'a, NewU>;
+
+    fn subclone_as<NewU>(&self, offset: usize, size: usize) -> Self::As<NewU> {
+        self.subclone_::<NewU>(offset, size)
+    }
+
+    fn try_subclone_as<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<Self::As<NewU>, MemAccessTryError> {
+        self.try_subclone_::<NewU>(offset, size)
+    }
+}
+
+// Bounds and alignment of `count` values of `V` at `offset`. Unlike MMIO, ordinary
+// memory backings check every access so that they stay sound.
+pub(crate) fn check_access<V>(
+    addr: usize,
+    size: usize,
+    offset: usize,
+    count: usize,
+) -> Result<(), MemAccessTryError> {
+    let len = count
+        .checked_mul(size_of::<V>())
+        .ok_or(MemAccessTryError::AddressOverflow)?;
+    let end = offset
+        .checked_add(len)
+        .ok_or(MemAccessTryError::AddressOverflow)?;
+    if end > size {
+        return Err(MemAccessTryError::OutOfBounds);
+    }
+    if (addr + offset) % align_of::<V>() != 0 {
+        return Err(MemAccessTryError::Misaligned);
+    }
+    Ok(())
+}
+
+pub(crate) fn expect_access(result: Result<(), MemAccessTryError>, offset: usize) {
+    if let Err(err) = result {
+        panic!("invalid access (offset: 0x{:x}): {:?}", offset, err);
+    }
+}
+
+macro_rules! checked_offset {
+    ($self:ident, Write, $offset:expr) => {
+        $offset
+    };
+    ($self:ident, Read, $offset:expr) => {
+        $offset
+    };
+    ($self:ident, WriteReg, $offset:expr) => {
+        $offset.checked_mul(Self::reg_size()).unwrap_or(usize::MAX)
+    };
+    ($self:ident, ReadReg, $offset:expr) => {
+        $offset.checked_mul(Self::reg_size()).unwrap_or(usize::MAX)
+    };
+}
+#[cfg(feature = "std")]
+pub(crate) use checked_offset;
+
+// `MemAccess` over `self.mem_accessor`, checking every access with `check_access`
+macro_rules! checked_copy_fns {
+    ($t:ty, $copy_to:ident, $try_copy_to:ident, $copy_from:ident, $try_copy_from:ident) => {
+        unsafe fn $copy_to(&self, src_adr: usize, dst_ptr: *mut $t, count: usize) {
+            expect_access(self.check::<$t>(src_adr, count), src_adr);
+            unsafe { self.mem_accessor.$copy_to(src_adr, dst_ptr, count) }
+        }
+        unsafe fn $try_copy_to(
+            &self,
+            src_adr: usize,
+            dst_ptr: *mut $t,
+            count: usize,
+        ) -> Result<(), MemAccessTryError> {
+            self.check::<$t>(src_adr, count)?;
+            unsafe { self.mem_accessor.$copy_to(src_adr, dst_ptr, count) };
+            Ok(())
+        }
+        unsafe fn $copy_from(&self, src_ptr: *const $t, dst_adr: usize, count: usize) {
+            expect_access(self.check::<$t>(dst_adr, count), dst_adr);
+            unsafe { self.mem_accessor.$copy_from(src_ptr, dst_adr, count) }
+        }
+        unsafe fn $try_copy_from(
+            &self,
+            src_ptr: *const $t,
+            dst_adr: usize,
+            count: usize,
+        ) -> Result<(), MemAccessTryError> {
+            self.check::<$t>(dst_adr, count)?;
+            unsafe { self.mem_accessor.$copy_from(src_ptr, dst_adr, count) };
+            Ok(())
+        }
+    };
+}
+#[cfg(feature = "std")]
+pub(crate) use checked_copy_fns;
+
+macro_rules! checked_rw_fns {
+    ($t:ty, $write_kind:ident, $write:ident, $try_write:ident, $read_kind:ident, $read:ident, $try_read:ident) => {
+        unsafe fn $write(&self, offset: usize, data: $t) {
+            let adr = checked_offset!(self, $write_kind, offset);
+            expect_access(self.check::<$t>(adr, 1), adr);
+            unsafe { self.mem_accessor.$write(offset, data) }
+        }
+        unsafe fn $try_write(&self, offset: usize, data: $t) -> Result<(), MemAccessTryError> {
+            self.check::<$t>(checked_offset!(self, $write_kind, offset), 1)?;
+            unsafe { self.mem_accessor.$write(offset, data) };
+            Ok(())
+        }
+        unsafe fn $read(&self, offset: usize) -> $t {
+            let adr = checked_offset!(self, $read_kind, offset);
+            expect_access(self.check::<$t>(adr, 1), adr);
+            unsafe { self.mem_accessor.$read(offset) }
+        }
+        unsafe fn $try_read(&self, offset: usize) -> Result<$t, MemAccessTryError> {
+            self.check::<$t>(checked_offset!(self, $read_kind, offset), 1)?;
+            Ok(unsafe { self.mem_accessor.$read(offset) })
+        }
+    };
+}
+#[cfg(feature = "std")]
+pub(crate) use checked_rw_fns;
+
+// The generic `MemAccessBase` accessors over `self.mem_accessor`, checked the same way
+macro_rules! checked_base_fns {
+    () => {
+        unsafe fn copy_to_<V>(&self, src_adr: usize, dst_ptr: *mut V, count: usize) {
+            expect_access(self.check::<V>(src_adr, count), src_adr);
+            unsafe { self.mem_accessor.copy_to_(src_adr, dst_ptr, count) }
+        }
+        unsafe fn copy_from_<V>(&self, src_ptr: *const V, dst_adr: usize, count: usize) {
+            expect_access(self.check::<V>(dst_adr, count), dst_adr);
+            unsafe { self.mem_accessor.copy_from_(src_ptr, dst_adr, count) }
+        }
+
+        unsafe fn write_mem_<V>(&self, offset: usize, data: V) {
+            expect_access(self.check::<V>(offset, 1), offset);
+            unsafe { self.mem_accessor.write_mem_(offset, data) }
+        }
+        unsafe fn read_mem_<V>(&self, offset: usize) -> V {
+            expect_access(self.check::<V>(offset, 1), offset);
+            unsafe { self.mem_accessor.read_mem_(offset) }
+        }
+        unsafe fn write_reg_<V>(&self, reg: usize, data: V) {
+            let adr = checked_offset!(self, WriteReg, reg);
+            expect_access(self.check::<V>(adr, 1), adr);
+            unsafe { self.mem_accessor.write_reg_(reg, data) }
+        }
+        unsafe fn read_reg_<V>(&self, reg: usize) -> V {
+            let adr = checked_offset!(self, ReadReg, reg);
+            expect_access(self.check::<V>(adr, 1), adr);
+            unsafe { self.mem_accessor.read_reg_(reg) }
+        }
+    };
+}
+#[cfg(feature = "std")]
+pub(crate) use checked_base_fns;
+
+impl<'a, U> MemAccessBase for SliceAccessor<'a, U> {
+    fn reg_size() -> usize {
+        core::mem::size_of::<U>()
+    }
+
+    fn subclone(&self, offset: usize, size: usize) -> Self {
+        self.subclone_::<U>(offset, size)
+    }
+
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        self.try_subclone_::<U>(offset, size)
+    }
+
+    checked_base_fns!();
+}
+
+impl<U> SliceAccessor<'_, U> {
+    fn check<V>(&self, offset: usize, count: usize) -> Result<(), MemAccessTryError> {
+        check_access::<V>(self.addr(), self.size(), offset, count)
+    }
+}
+
+impl<U> MemAccess for SliceAccessor<'_, U> {
+    fn addr(&self) -> usize {
+        self.mem_accessor.addr()
+    }
+    fn size(&self) -> usize {
+        self.mem_accessor.size()
+    }
+    fn phys_addr(&self) -> usize {
+        self.mem_accessor.phys_addr()
+    }
+
+    for_each_access_type!(checked_copy_fns, checked_rw_fns);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slice_access() {
+        let mut buf = [0u64; 4];
+        let bytes = unsafe { core::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut u8, 32) };
+        let acc = SliceAccessor::<u32>::new(bytes);
+        unsafe {
+            acc.write_reg_u32(1, 0x1234_5678);
+            acc.write_mem_u16(0x10, 0xabcd);
+            assert_eq!(acc.read_mem_u32(4), 0x1234_5678);
+            assert_eq!(acc.read_reg_u16(4), 0xabcd);
+
+            // subclone keeps the offset and shares the same memory
+            let sub = acc.subclone8(0x10, 0x10);
+            assert_eq!(sub.read_mem_u16(0), 0xabcd);
+            sub.write_reg_u8(2, 0x5a);
+            assert_eq!(acc.read_mem_u8(0x12), 0x5a);
+
+            assert_eq!(
+                acc.try_read_mem_u32(0x1e),
+                Err(MemAccessTryError::OutOfBounds)
+            );
+            assert_eq!(
+                acc.try_write_mem_u32(0x2, 0),
+                Err(MemAccessTryError::Misaligned)
+            );
+            let src = [1u32; 4];
+            assert_eq!(
+                sub.try_copy_from_u32(src.as_ptr(), 4, 4),
+                Err(MemAccessTryError::OutOfBounds)
+            );
+            assert_eq!(sub.try_copy_from_u32(src.as_ptr(), 4, 3), Ok(()));
+        }
+        assert_eq!(buf[3], 0x0000_0001_0000_0001);
+    }
+
+    #[test]
+    #[should_panic(expected = "OutOfBounds")]
+    fn slice_out_of_bounds_panics() {
+        let mut buf = [0u8; 8];
+        let acc = SliceAccessor::<u8>::new(&mut buf);
+        unsafe { acc.write_mem_u8(8, 1) };
+    }
+
+    #[test]
+    #[should_panic(expected = "OutOfBounds")]
+    fn generic_access_is_checked() {
+        let mut buf = [0u8; 8];
+        let acc = SliceAccessor::<u32>::new(&mut buf);
+        unsafe {
+            MemAccessBase::write_mem_::<u32>(&acc, 4, 1);
+            assert_eq!(MemAccessBase::read_reg_::<u32>(&acc, 1), 1);
+            MemAccessBase::write_reg_::<u32>(&acc, 2, 1);
+        }
+    }
+}
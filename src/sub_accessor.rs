@@ -0,0 +1,180 @@
+use core::marker::PhantomData;
+
+use super::hooked_accessor::{Access, AccessHook, HookedAccessor};
+use super::mem_accessor::{MemAccess, MemAccessBase, MemAccessTryError};
+
+/// 親アクセサの借用（`SubAccessor` のフック）。アクセス自体には何もしない。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Borrowed<'a>(PhantomData<&'a ()>);
+
+impl AccessHook for Borrowed<'_> {
+    type Guard = ();
+
+    fn enter(&self, _access: &Access) -> Result<(), MemAccessTryError> {
+        Ok(())
+    }
+}
+
+/// 親アクセサを借用した部分領域。
+///
+/// - `subregion` は `&self` を借用するので、親より長く生きられない
+/// - `split_at` は `&mut self` を借用するので、分割した 2 つは互いに重ならず、
+///   生きている間は親からも触れない
+/// - 内側のアクセサは外に出さない（`subclone`/`try_subclone` の結果も同じ借用のまま）
+pub type SubAccessor<'a, A> = HookedAccessor<A, Borrowed<'a>>;
+
+fn borrowed<'a, A: MemAccess + MemAccessBase>(inner: A) -> SubAccessor<'a, A> {
+    HookedAccessor::with_hook(inner, Borrowed(PhantomData))
+}
+
+impl<A: MemAccess + MemAccessBase> HookedAccessor<A, Borrowed<'_>> {
+    /// この部分領域の `offset` から `size` バイトをさらに借用する。
+    pub fn subregion(&self, offset: usize, size: usize) -> SubAccessor<'_, A> {
+        borrowed(sub_checked(self.inner_ref(), offset, size))
+    }
+
+    /// `subregion` の検査版（範囲外・空の部分領域は `OutOfBounds`）。
+    pub fn try_subregion(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<SubAccessor<'_, A>, MemAccessTryError> {
+        try_sub(self.inner_ref(), offset, size).map(borrowed)
+    }
+
+    /// `mid` バイト目で 2 つの重ならない部分領域に分ける。
+    pub fn split_at(&mut self, mid: usize) -> (SubAccessor<'_, A>, SubAccessor<'_, A>) {
+        let (head, tail) = split_checked(self.inner_ref(), mid);
+        (borrowed(head), borrowed(tail))
+    }
+}
+
+/// 借用付きの部分領域を作る拡張トレイト。
+///
+/// 互いに重ならないレジスタブロックを別々のドライバに渡すときは `split_at` を使う。
+pub trait Subregion: MemAccess + MemAccessBase + Sized {
+    /// `offset` から `size` バイトを借用する。
+    fn subregion(&self, offset: usize, size: usize) -> SubAccessor<'_, Self> {
+        borrowed(sub_checked(self, offset, size))
+    }
+
+    /// `subregion` の検査版（範囲外・空の部分領域は `OutOfBounds`）。
+    fn try_subregion(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<SubAccessor<'_, Self>, MemAccessTryError> {
+        try_sub(self, offset, size).map(borrowed)
+    }
+
+    /// `mid` バイト目で 2 つの重ならない部分領域に分ける。
+    fn split_at(&mut self, mid: usize) -> (SubAccessor<'_, Self>, SubAccessor<'_, Self>) {
+        let (head, tail) = split_checked(self, mid);
+        (borrowed(head), borrowed(tail))
+    }
+}
+
+impl<A: MemAccess + MemAccessBase> Subregion for A {}
+
+// `subclone` は size == 0 を「残り全部」と扱うので、空の部分領域は作らせない
+fn try_sub<A: MemAccess + MemAccessBase>(
+    parent: &A,
+    offset: usize,
+    size: usize,
+) -> Result<A, MemAccessTryError> {
+    if size == 0 {
+        return Err(MemAccessTryError::OutOfBounds);
+    }
+    parent.try_subclone(offset, size)
+}
+
+fn sub_checked<A: MemAccess + MemAccessBase>(parent: &A, offset: usize, size: usize) -> A {
+    match try_sub(parent, offset, size) {
+        Ok(sub) => sub,
+        Err(err) => panic!(
+            "subregion out of range (offset: 0x{:x}, size: 0x{:x}, parent size: 0x{:x}): {:?}",
+            offset,
+            size,
+            parent.size(),
+            err
+        ),
+    }
+}
+
+fn split_checked<A: MemAccess + MemAccessBase>(parent: &A, mid: usize) -> (A, A) {
+    assert!(
+        mid > 0 && mid < parent.size(),
+        "split point out of range (mid: 0x{:x}, size: 0x{:x})",
+        mid,
+        parent.size()
+    );
+    (
+        parent.subclone(0, mid),
+        parent.subclone(mid, parent.size() - mid),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MmioAccessor;
+
+    #[test]
+    fn split_disjoint_blocks() {
+        let mut buf = [0u32; 8];
+        let mut mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 32);
+        {
+            let (mut ctrl, status) = mmio.split_at(0x10);
+            assert_eq!(ctrl.size(), 0x10);
+            assert_eq!(status.size(), 0x10);
+            assert_eq!(status.addr(), ctrl.addr() + 0x10);
+
+            let (lo, hi) = ctrl.split_at(0x8);
+            unsafe {
+                lo.write_reg_u32(1, 0x11);
+                hi.write_reg_u32(0, 0x22);
+                status.write_reg_u32(3, 0x33);
+            }
+
+            let reg = status.subregion(0xc, 4);
+            assert_eq!(unsafe { reg.read_mem_u32(0) }, 0x33);
+        }
+        unsafe {
+            assert_eq!(mmio.read_reg_u32(1), 0x11);
+            assert_eq!(mmio.read_reg_u32(2), 0x22);
+            assert_eq!(mmio.read_reg_u32(7), 0x33);
+        }
+    }
+
+    #[test]
+    fn try_subregion_checks_range() {
+        let mut buf = [0u32; 4];
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 16);
+        let sub = mmio.try_subregion(0x4, 0x8).unwrap();
+        assert_eq!(sub.size(), 0x8);
+        assert_eq!(
+            sub.try_subregion(0x4, 0x4).unwrap().addr(),
+            mmio.addr() + 0x8
+        );
+        assert_eq!(
+            sub.try_subregion(0x4, 0x8).unwrap_err(),
+            MemAccessTryError::OutOfBounds
+        );
+        assert_eq!(
+            sub.try_subregion(0x0, 0).unwrap_err(),
+            MemAccessTryError::OutOfBounds
+        );
+        assert_eq!(
+            sub.try_subclone(0x8, 0).unwrap_err(),
+            MemAccessTryError::OutOfBounds
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn subregion_out_of_range() {
+        let mut buf = [0u32; 4];
+        let mmio = MmioAccessor::<u32>::new(buf.as_mut_ptr() as usize, 16);
+        let _ = mmio.subregion(0x8, 0x10);
+    }
+}
@@ -0,0 +1,465 @@
+#![allow(dead_code)]
+
+use core::fmt;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+use super::bus_accessor::{Bus, BusAddress, BusWord};
+use super::hooked_accessor::{Access, AccessHook, AccessKind, HookedAccessor, TransparentHook};
+use super::mem_accessor::{MemAccess, MemAccessTryError};
+
+/// 1 回のアクセスの記録。
+///
+/// - `phys_addr` はアクセサの `phys_addr()`（`TracingBus` では 0）
+/// - `offset` はアクセサ先頭からのバイトオフセット（レジスタアクセスはレジスタ番号、バスはバスアドレス）
+/// - `value` は読み書きした値のビット列（コピーは 0、64bit を超える部分は切り捨て）
+/// - `timestamp` は `Tracer` 作成からの経過時間
+/// - `failed` は `try_*` やバスがエラーを返した
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessRecord {
+    pub kind: AccessKind,
+    pub phys_addr: usize,
+    pub offset: usize,
+    pub width: usize,
+    pub count: usize,
+    pub value: u64,
+    pub timestamp: Duration,
+    pub thread: ThreadId,
+    pub failed: bool,
+}
+
+impl fmt::Display for AccessRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{:>12.6}] {:?} {} 0x{:x}+0x{:x} w{}",
+            self.timestamp.as_secs_f64(),
+            self.thread,
+            self.kind,
+            self.phys_addr,
+            self.offset,
+            self.width
+        )?;
+        match self.kind {
+            AccessKind::CopyTo | AccessKind::CopyFrom => write!(f, " x{}", self.count)?,
+            _ => write!(f, " = 0x{:x}", self.value)?,
+        }
+        if self.failed {
+            f.write_str(" (failed)")?;
+        }
+        Ok(())
+    }
+}
+
+/// 記録の出力先。
+///
+/// クロージャ `Fn(&AccessRecord)` もそのまま使える（`tracing` クレート等へ流す場合）。
+pub trait TraceSink: Send + Sync {
+    fn record(&self, record: &AccessRecord);
+
+    /// コピーの記録。`data` は転送したバイト列（失敗時は空）。
+    fn record_data(&self, record: &AccessRecord, data: &[u8]) {
+        let _ = data;
+        self.record(record)
+    }
+}
+
+impl<F: Fn(&AccessRecord) + Send + Sync> TraceSink for F {
+    fn record(&self, record: &AccessRecord) {
+        self(record)
+    }
+}
+
+/// 直近 `capacity` 件を保持するリングバッファ。clone したハンドルは同じバッファを指す。
+#[derive(Debug, Clone)]
+pub struct TraceRing {
+    records: Arc<Mutex<VecDeque<AccessRecord>>>,
+    capacity: usize,
+}
+
+impl TraceRing {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 保持している記録（古い順）のコピー。
+    pub fn records(&self) -> Vec<AccessRecord> {
+        self.lock().iter().copied().collect()
+    }
+
+    /// 保持している記録を取り出して空にする。
+    pub fn take(&self) -> Vec<AccessRecord> {
+        self.lock().drain(..).collect()
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<AccessRecord>> {
+        self.records
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl TraceSink for TraceRing {
+    fn record(&self, record: &AccessRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut records = self.lock();
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(*record);
+    }
+}
+
+/// 1 件 1 行のテキストで書き出す（ファイル、stderr 等）。書き込みエラーは無視する。
+#[derive(Debug)]
+pub struct TraceWriter<W: Write + Send> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> TraceWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<W: Write + Send> TraceSink for TraceWriter<W> {
+    fn record(&self, record: &AccessRecord) {
+        let mut writer = self
+            .writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _ = writeln!(writer, "{}", record);
+    }
+}
+
+/// `log` クレートへ出力する（target は `jelly_mem_access::trace`）。
+#[cfg(feature = "log")]
+#[derive(Debug, Clone, Copy)]
+pub struct TraceLog {
+    pub level: log::Level,
+}
+
+#[cfg(feature = "log")]
+impl TraceLog {
+    pub const fn new(level: log::Level) -> Self {
+        Self { level }
+    }
+}
+
+#[cfg(feature = "log")]
+impl TraceSink for TraceLog {
+    fn record(&self, record: &AccessRecord) {
+        log::log!(target: "jelly_mem_access::trace", self.level, "{}", record);
+    }
+}
+
+/// 記録の出力先と時刻の基準。clone（subclone 含む）したアクセサ間で共有する。
+#[derive(Clone)]
+pub struct Tracer {
+    sink: Arc<dyn TraceSink>,
+    start: Instant,
+}
+
+impl fmt::Debug for Tracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracer")
+            .field("start", &self.start)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Tracer {
+    pub fn new(sink: impl TraceSink + 'static) -> Self {
+        Self::from_arc(Arc::new(sink))
+    }
+
+    pub fn from_arc(sink: Arc<dyn TraceSink>) -> Self {
+        Self {
+            sink,
+            start: Instant::now(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn record(
+        &self,
+        kind: AccessKind,
+        phys_addr: usize,
+        offset: usize,
+        width: usize,
+        count: usize,
+        value: u64,
+        failed: bool,
+        data: &[u8],
+    ) {
+        let record = AccessRecord {
+            kind,
+            phys_addr,
+            offset,
+            width,
+            count,
+            value,
+            timestamp: self.start.elapsed(),
+            thread: thread::current().id(),
+            failed,
+        };
+        if data.is_empty() {
+            self.sink.record(&record);
+        } else {
+            self.sink.record_data(&record, data);
+        }
+    }
+}
+
+impl AccessHook for Tracer {
+    type Guard = ();
+
+    fn enter(&self, _access: &Access) -> Result<(), MemAccessTryError> {
+        Ok(())
+    }
+
+    fn exit(&self, access: &Access, _guard: (), value: u64, data: &[u8], ok: bool) {
+        self.record(
+            access.kind,
+            access.phys_addr,
+            access.offset,
+            access.width,
+            access.count,
+            value,
+            !ok,
+            data,
+        );
+    }
+}
+
+impl TransparentHook for Tracer {}
+
+/// 任意の `MemAccess` を包み、すべての読み書き・コピーを `Tracer` に記録する。
+///
+/// - アクセス自体は内側のアクセサにそのまま委譲する（`try_*` の結果も変えない）
+/// - `addr`/`size`/`phys_addr` は内側の値を返す
+/// - `subclone`/`subclone8` 等した結果も同じ `Tracer` に記録する
+pub type TracingAccessor<A> = HookedAccessor<A, Tracer>;
+
+impl<A: MemAccess> HookedAccessor<A, Tracer> {
+    pub fn new(inner: A, sink: impl TraceSink + 'static) -> Self {
+        Self::with_tracer(inner, Tracer::new(sink))
+    }
+
+    /// 既存の `Tracer` を共有する（複数のアクセサを 1 本の記録にまとめる）。
+    pub fn with_tracer(inner: A, tracer: Tracer) -> Self {
+        Self::from_parts(inner, tracer, None)
+    }
+
+    pub fn tracer(&self) -> &Tracer {
+        self.hook()
+    }
+}
+
+/// 任意の `Bus` を包み、すべてのビートを `Tracer` に記録する。
+///
+/// バーストは内側の `write_burst`/`read_burst` にそのまま渡し、ビートごとに 1 件記録する。
+#[derive(Debug)]
+pub struct TracingBus<B> {
+    inner: B,
+    tracer: Tracer,
+}
+
+impl<B> TracingBus<B> {
+    pub fn new(inner: B, sink: impl TraceSink + 'static) -> Self {
+        Self::with_tracer(inner, Tracer::new(sink))
+    }
+
+    pub fn with_tracer(inner: B, tracer: Tracer) -> Self {
+        Self { inner, tracer }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    pub fn tracer(&self) -> &Tracer {
+        &self.tracer
+    }
+
+    fn trace<D: BusWord>(&self, kind: AccessKind, addr: usize, data: &D, failed: bool) {
+        let mut value = 0u64;
+        for lane in 0..D::BYTES.min(8) {
+            value |= (data.lane(lane) as u64) << (lane * 8);
+        }
+        self.tracer
+            .record(kind, 0, addr, D::BYTES, 1, value, failed, &[]);
+    }
+}
+
+impl<B, A, D, S> Bus<A, D, S> for TracingBus<B>
+where
+    B: Bus<A, D, S>,
+    A: BusAddress,
+    D: BusWord,
+    S: BusWord,
+{
+    type Error = B::Error;
+
+    fn write(&mut self, addr: A, data: D, strb: S) -> Result<(), Self::Error> {
+        let result = self.inner.write(addr, data, strb);
+        self.trace(AccessKind::Write, addr.to_usize(), &data, result.is_err());
+        result
+    }
+
+    fn read(&mut self, addr: A) -> Result<D, Self::Error> {
+        let result = self.inner.read(addr);
+        let data = result.as_ref().map_or(D::zero(), |data| *data);
+        self.trace(AccessKind::Read, addr.to_usize(), &data, result.is_err());
+        result
+    }
+
+    fn write_burst(&mut self, addr: A, data: &[D], strb: &[S]) -> Result<(), Self::Error>
+    where
+        A: BusAddress,
+        D: BusWord,
+        S: Copy,
+    {
+        let result = self.inner.write_burst(addr, data, strb);
+        let base = addr.to_usize();
+        for (i, data) in data.iter().enumerate() {
+            self.trace(
+                AccessKind::Write,
+                base + i * D::BYTES,
+                data,
+                result.is_err(),
+            );
+        }
+        result
+    }
+
+    fn read_burst(&mut self, addr: A, data: &mut [D]) -> Result<(), Self::Error>
+    where
+        A: BusAddress,
+        D: BusWord,
+    {
+        let result = self.inner.read_burst(addr, data);
+        let base = addr.to_usize();
+        for (i, data) in data.iter().enumerate() {
+            self.trace(AccessKind::Read, base + i * D::BYTES, data, result.is_err());
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus_accessor::{BusAccessor, LittleEndian};
+    use crate::mem_accessor::MemAccessor;
+    use crate::mmio_accessor::MmioRegion;
+    use crate::ram_bus::RamBus;
+    use std::string::String;
+
+    #[test]
+    fn records_accesses_and_subclones() {
+        let mut mem = [0u32; 16];
+        let region = MmioRegion::new(mem.as_mut_ptr() as usize, 64);
+        let ring = TraceRing::new(4);
+        let acc = TracingAccessor::new(MemAccessor::<_, u32>::new(region), ring.clone());
+
+        let sub = acc.subclone(0x10, 0x10);
+        unsafe {
+            acc.write_mem_u32(0x04, 0x1234);
+            sub.write_reg_u16(1, 0xbeef);
+            assert_eq!(acc.read_mem_i8(0x04), 0x34);
+            let src = [1u32, 2, 3];
+            sub.copy_from_u32(src.as_ptr(), 0, src.len());
+            acc.write_mem_u8(0, 0);
+        }
+        assert_eq!(sub.phys_addr(), acc.phys_addr() + 0x10);
+
+        let records = ring.records();
+        assert_eq!(records.len(), 4);
+        let (first, last) = (&records[0], &records[3]);
+        assert_eq!(
+            (first.kind, first.offset, first.width, first.value),
+            (AccessKind::WriteReg, 1, 2, 0xbeef)
+        );
+        assert_eq!(first.phys_addr, acc.phys_addr() + 0x10);
+        assert_eq!(first.thread, thread::current().id());
+        assert_eq!(records[2].kind, AccessKind::CopyFrom);
+        assert_eq!(records[2].count, 3);
+        assert_eq!(
+            (last.kind, last.width, last.value),
+            (AccessKind::Write, 1, 0)
+        );
+        assert!(records.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+        assert_eq!(ring.take().len(), 4);
+        assert!(ring.records().is_empty());
+    }
+
+    #[test]
+    fn try_failures_are_recorded() {
+        let ring = TraceRing::new(16);
+        let bus = TracingBus::new(RamBus::new(16), ring.clone());
+        let acc = BusAccessor::<_, usize, u32, u8, LittleEndian>::new(bus);
+        let acc = TracingAccessor::new(acc, ring.clone());
+
+        unsafe {
+            acc.write_mem_u32(0x8, 0xdead_beef);
+            assert!(acc.try_read_mem_u32(0x10).is_err());
+        }
+        let records = ring.records();
+        // バス側のビート、アクセサ側の記録の順
+        assert_eq!(records[0].kind, AccessKind::Write);
+        assert_eq!((records[0].offset, records[0].value), (0x8, 0xdead_beef));
+        assert_eq!(records[1].offset, 0x8);
+        let last = records.last().unwrap();
+        assert_eq!((last.kind, last.offset), (AccessKind::Read, 0x10));
+        assert!(last.failed);
+    }
+
+    #[test]
+    fn writer_sink_formats_lines() {
+        let writer = Arc::new(TraceWriter::new(Vec::new()));
+        let sink = writer.clone();
+        let mut bus = TracingBus::new(RamBus::new(16), move |record: &AccessRecord| {
+            sink.record(record)
+        });
+        Bus::<usize, u32, u8>::write(&mut bus, 4, 0x55, 0xf).unwrap();
+        drop(bus);
+
+        let text = String::from_utf8(Arc::try_unwrap(writer).unwrap().into_inner()).unwrap();
+        assert_eq!(text.lines().count(), 1);
+        assert!(text.contains("write 0x0+0x4 w4 = 0x55"), "{text}");
+    }
+}
@@ -7,7 +7,10 @@ use std::error::Error;
 use std::format;
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
+use std::path::Path;
+use std::str::FromStr;
 use std::string::String;
+use std::vec::Vec;
 
 const O_SYNC: i32 = 0x101000;
 
@@ -28,22 +31,62 @@ fn write_file_from_string(path: &str, text: &str) -> Result<(), Box<dyn Error>>
     Ok(())
 }
 
+const SYS_CLASS_BASE: &str = "/sys/class";
+
+// `class_base` stands in for /sys/class
+fn read_attr_in(
+    class_base: &Path,
+    module_name: &str,
+    device_name: &str,
+    name: &str,
+) -> Result<String, Box<dyn Error>> {
+    let path = class_base.join(module_name).join(device_name).join(name);
+    Ok(read_file_to_string(&path.to_string_lossy())?.trim().into())
+}
+
+fn parse_attr_in<T>(
+    class_base: &Path,
+    module_name: &str,
+    device_name: &str,
+    name: &str,
+) -> Result<T, Box<dyn Error>>
+where
+    T: FromStr,
+    T::Err: Error + 'static,
+{
+    Ok(read_attr_in(class_base, module_name, device_name, name)?.parse()?)
+}
+
+fn parse_attr<T>(device_name: &str, module_name: &str, name: &str) -> Result<T, Box<dyn Error>>
+where
+    T: FromStr,
+    T::Err: Error + 'static,
+{
+    parse_attr_in(Path::new(SYS_CLASS_BASE), module_name, device_name, name)
+}
+
+fn read_phys_addr_in(
+    class_base: &Path,
+    module_name: &str,
+    device_name: &str,
+) -> Result<usize, Box<dyn Error>> {
+    let text = read_attr_in(class_base, module_name, device_name, "phys_addr")?;
+    let hex = text
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("{}: phys_addr is not hex: {}", device_name, text))?;
+    Ok(usize::from_str_radix(hex, 16)?)
+}
+
 pub fn read_phys_addr(device_name: &str, module_name: &str) -> Result<usize, Box<dyn Error>> {
-    let fname = format!("/sys/class/{}/{}/phys_addr", module_name, device_name);
-    Ok(usize::from_str_radix(
-        &read_file_to_string(&fname)?.trim()[2..],
-        16,
-    )?)
+    read_phys_addr_in(Path::new(SYS_CLASS_BASE), module_name, device_name)
 }
 
 pub fn read_size(device_name: &str, module_name: &str) -> Result<usize, Box<dyn Error>> {
-    let fname = format!("/sys/class/{}/{}/size", module_name, device_name);
-    Ok(read_file_to_string(&fname)?.trim().parse()?)
+    parse_attr(device_name, module_name, "size")
 }
 
 pub fn read_sync_mode(device_name: &str, module_name: &str) -> Result<u32, Box<dyn Error>> {
-    let fname = format!("/sys/class/{}/{}/sync_mode", module_name, device_name);
-    Ok(read_file_to_string(&fname)?.trim().parse()?)
+    parse_attr(device_name, module_name, "sync_mode")
 }
 
 pub fn write_sync_mode(
@@ -57,8 +100,7 @@ pub fn write_sync_mode(
 }
 
 pub fn read_sync_offset(device_name: &str, module_name: &str) -> Result<usize, Box<dyn Error>> {
-    let fname = format!("/sys/class/{}/{}/sync_offset", module_name, device_name);
-    Ok(read_file_to_string(&fname)?.trim().parse()?)
+    parse_attr(device_name, module_name, "sync_offset")
 }
 
 pub fn write_sync_offset(
@@ -72,8 +114,7 @@ pub fn write_sync_offset(
 }
 
 pub fn read_sync_size(device_name: &str, module_name: &str) -> Result<usize, Box<dyn Error>> {
-    let fname = format!("/sys/class/{}/{}/sync_size", module_name, device_name);
-    Ok(read_file_to_string(&fname)?.trim().parse()?)
+    parse_attr(device_name, module_name, "sync_size")
 }
 
 pub fn write_sync_size(
@@ -87,8 +128,7 @@ pub fn write_sync_size(
 }
 
 pub fn read_sync_direction(device_name: &str, module_name: &str) -> Result<u32, Box<dyn Error>> {
-    let fname = format!("/sys/class/{}/{}/sync_direction", module_name, device_name);
-    Ok(read_file_to_string(&fname)?.trim().parse()?)
+    parse_attr(device_name, module_name, "sync_direction")
 }
 
 pub fn write_sync_directione(
@@ -102,13 +142,11 @@ pub fn write_sync_directione(
 }
 
 pub fn read_dma_coherent(device_name: &str, module_name: &str) -> Result<u32, Box<dyn Error>> {
-    let fname = format!("/sys/class/{}/{}/dma_coherent", module_name, device_name);
-    Ok(read_file_to_string(&fname)?.trim().parse()?)
+    parse_attr(device_name, module_name, "dma_coherent")
 }
 
 pub fn read_sync_owner(device_name: &str, module_name: &str) -> Result<u32, Box<dyn Error>> {
-    let fname = format!("/sys/class/{}/{}/sync_owner", module_name, device_name);
-    Ok(read_file_to_string(&fname)?.trim().parse()?)
+    parse_attr(device_name, module_name, "sync_owner")
 }
 
 pub fn write_sync_for_cpu(device_name: &str, module_name: &str) -> Result<(), Box<dyn Error>> {
@@ -155,6 +193,122 @@ pub fn write_sync_for_device_with_range(
     write_file_from_string(&fname, text.as_str())
 }
 
+// -----------------------------
+//  Enumeration
+// -----------------------------
+
+const DEVICE_TREE_BASE: &str = "/sys/firmware/devicetree/base";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UdmabufDeviceInfo {
+    pub name: String,
+    pub module_name: String,
+    pub phys_addr: usize,
+    pub size: usize,
+    pub sync_mode: u32,
+    pub dma_coherent: u32,
+}
+
+impl UdmabufDeviceInfo {
+    pub fn read(device_name: &str, module_name: &str) -> Result<Self, Box<dyn Error>> {
+        Self::read_in(Path::new(SYS_CLASS_BASE), device_name, module_name)
+    }
+
+    fn read_in(
+        class_base: &Path,
+        device_name: &str,
+        module_name: &str,
+    ) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            name: String::from(device_name),
+            module_name: String::from(module_name),
+            phys_addr: read_phys_addr_in(class_base, module_name, device_name)?,
+            size: parse_attr_in(class_base, module_name, device_name, "size")?,
+            sync_mode: parse_attr_in(class_base, module_name, device_name, "sync_mode")?,
+            dma_coherent: parse_attr_in(class_base, module_name, device_name, "dma_coherent")?,
+        })
+    }
+
+    pub fn open<U>(&self, cache_enable: bool) -> Result<UdmabufAccessor<U>, Box<dyn Error>> {
+        UdmabufAccessor::<U>::new_with_module_name(&self.name, &self.module_name, cache_enable)
+    }
+}
+
+// devices whose attributes cannot be read (e.g. a half-initialized node) are skipped,
+// only a missing class directory is an error
+pub fn enumerate_udmabuf_devices(
+    module_name: &str,
+) -> Result<Vec<UdmabufDeviceInfo>, Box<dyn Error>> {
+    enumerate_udmabuf_devices_in(Path::new(SYS_CLASS_BASE), module_name)
+}
+
+fn enumerate_udmabuf_devices_in(
+    class_base: &Path,
+    module_name: &str,
+) -> Result<Vec<UdmabufDeviceInfo>, Box<dyn Error>> {
+    let mut devices: Vec<UdmabufDeviceInfo> = std::fs::read_dir(class_base.join(module_name))?
+        .filter_map(|entry| {
+            let device_name = entry.ok()?.file_name().to_string_lossy().into_owned();
+            UdmabufDeviceInfo::read_in(class_base, &device_name, module_name).ok()
+        })
+        .collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(devices)
+}
+
+// smallest device that has at least `min_size` bytes
+pub fn find_udmabuf_device_by_size(
+    min_size: usize,
+    module_name: &str,
+) -> Result<Option<UdmabufDeviceInfo>, Box<dyn Error>> {
+    Ok(enumerate_udmabuf_devices(module_name)?
+        .into_iter()
+        .filter(|dev| dev.size >= min_size)
+        .min_by_key(|dev| dev.size))
+}
+
+// label is resolved through /sys/firmware/devicetree/base/__symbols__,
+// so the device tree must be compiled with symbols (dtc -@)
+pub fn find_udmabuf_device_by_label(
+    label: &str,
+    module_name: &str,
+) -> Result<Option<UdmabufDeviceInfo>, Box<dyn Error>> {
+    find_udmabuf_device_by_label_in(
+        Path::new(SYS_CLASS_BASE),
+        Path::new(DEVICE_TREE_BASE),
+        label,
+        module_name,
+    )
+}
+
+fn find_udmabuf_device_by_label_in(
+    class_base: &Path,
+    device_tree_base: &Path,
+    label: &str,
+    module_name: &str,
+) -> Result<Option<UdmabufDeviceInfo>, Box<dyn Error>> {
+    let symbol = device_tree_base.join("__symbols__").join(label);
+    if !symbol.exists() {
+        return Ok(None);
+    }
+    let node_path = read_file_to_string(&symbol.to_string_lossy())?;
+    let node_path = node_path.trim_end_matches('\0').trim();
+    let node_dir = std::fs::canonicalize(device_tree_base.join(node_path.trim_start_matches('/')))?;
+
+    for dev in enumerate_udmabuf_devices_in(class_base, module_name)? {
+        let of_node = class_base
+            .join(module_name)
+            .join(&dev.name)
+            .join("device/of_node");
+        if let Ok(dev_node_dir) = std::fs::canonicalize(&of_node) {
+            if dev_node_dir == node_dir {
+                return Ok(Some(dev));
+            }
+        }
+    }
+    Ok(None)
+}
+
 // -----------------------------
 //  Udmabuf
 // -----------------------------
@@ -290,12 +444,16 @@ impl UdmabufRegion {
 
 impl MemRegion for UdmabufRegion {
     fn subclone(&self, offset: usize, size: usize) -> Self {
-        UdmabufRegion {
-            mmap_region: self.mmap_region.subclone(offset, size),
+        expect_subclone(self.try_subclone(offset, size), offset, size)
+    }
+
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        Ok(UdmabufRegion {
+            mmap_region: self.mmap_region.try_subclone(offset, size)?,
             phys_addr: self.phys_addr + offset,
             module_name: self.module_name.clone(),
             device_name: self.device_name.clone(),
-        }
+        })
     }
 
     fn phys_addr(&self) -> usize {
@@ -357,6 +515,18 @@ impl<U> UdmabufAccessor<U> {
         }
     }
 
+    pub fn try_subclone_<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<UdmabufAccessor<NewU>, MemAccessTryError> {
+        Ok(UdmabufAccessor::<NewU> {
+            mem_accessor: MemAccessor::<UdmabufRegion, NewU>::new(
+                self.mem_accessor.region().try_subclone(offset, size)?,
+            ),
+        })
+    }
+
     pub fn subclone8(&self, offset: usize, size: usize) -> UdmabufAccessor<u8> {
         self.subclone_::<u8>(offset, size)
     }
@@ -373,6 +543,38 @@ impl<U> UdmabufAccessor<U> {
         self.subclone_::<u64>(offset, size)
     }
 
+    pub fn try_subclone8(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<UdmabufAccessor<u8>, MemAccessTryError> {
+        self.try_subclone_::<u8>(offset, size)
+    }
+
+    pub fn try_subclone16(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<UdmabufAccessor<u16>, MemAccessTryError> {
+        self.try_subclone_::<u16>(offset, size)
+    }
+
+    pub fn try_subclone32(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<UdmabufAccessor<u32>, MemAccessTryError> {
+        self.try_subclone_::<u32>(offset, size)
+    }
+
+    pub fn try_subclone64(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<UdmabufAccessor<u64>, MemAccessTryError> {
+        self.try_subclone_::<u64>(offset, size)
+    }
+
     delegate! {
         to self.mem_accessor.region() {
             pub fn addr(&self) -> usize;
@@ -417,6 +619,22 @@ impl<U> Clone for UdmabufAccessor<U> {
     }
 }
 
+impl<U> SubcloneAs for UdmabufAccessor<U> {
+    type As<NewU> = UdmabufAccessor<NewU>;
+
+    fn subclone_as<NewU>(&self, offset: usize, size: usize) -> Self::As<NewU> {
+        self.subclone_::<NewU>(offset, size)
+    }
+
+    fn try_subclone_as<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<Self::As<NewU>, MemAccessTryError> {
+        self.try_subclone_::<NewU>(offset, size)
+    }
+}
+
 impl<U> MemAccessBase for UdmabufAccessor<U> {
     fn reg_size() -> usize {
         core::mem::size_of::<U>()
@@ -426,6 +644,10 @@ impl<U> MemAccessBase for UdmabufAccessor<U> {
         self.subclone_::<U>(offset, size)
     }
 
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        self.try_subclone_::<U>(offset, size)
+    }
+
     delegate! {
         to self.mem_accessor {
             unsafe fn copy_to_<V>(&self, src_adr: usize, dst_ptr: *mut V, count: usize);
@@ -576,3 +798,125 @@ impl<U> MemAccessSync for UdmabufAccessor<U> {
             .unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // minimal /sys/class + devicetree tree under a temp dir
+    struct Fixture {
+        root: PathBuf,
+    }
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!(
+                "jelly-mem_access-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&root);
+            std::fs::create_dir_all(root.join("class/u-dma-buf")).unwrap();
+            std::fs::create_dir_all(root.join("dt/__symbols__")).unwrap();
+            Fixture { root }
+        }
+
+        fn class_base(&self) -> PathBuf {
+            self.root.join("class")
+        }
+
+        fn dt_base(&self) -> PathBuf {
+            self.root.join("dt")
+        }
+
+        fn add_device(&self, name: &str, attrs: &[(&str, &str)]) -> PathBuf {
+            let dir = self.class_base().join("u-dma-buf").join(name);
+            std::fs::create_dir_all(dir.join("device")).unwrap();
+            for (attr, value) in attrs {
+                std::fs::write(dir.join(attr), value).unwrap();
+            }
+            dir
+        }
+
+        fn add_node(&self, dev_dir: &Path, node: &str, label: &str) {
+            let node_dir = self.dt_base().join(node);
+            std::fs::create_dir_all(&node_dir).unwrap();
+            std::os::unix::fs::symlink(&node_dir, dev_dir.join("device/of_node")).unwrap();
+            std::fs::write(
+                self.dt_base().join("__symbols__").join(label),
+                format!("/{}\0", node),
+            )
+            .unwrap();
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    fn attrs(phys_addr: &'static str, size: &'static str) -> [(&'static str, &'static str); 4] {
+        [
+            ("phys_addr", phys_addr),
+            ("size", size),
+            ("sync_mode", "1\n"),
+            ("dma_coherent", "0\n"),
+        ]
+    }
+
+    #[test]
+    fn enumerate_skips_broken_devices() {
+        let fx = Fixture::new("enumerate");
+        fx.add_device("udmabuf1", &attrs("0x40000000\n", "8192\n"));
+        fx.add_device("udmabuf0", &attrs("0x3f000000\n", "4096\n"));
+        // attributes not populated yet
+        fx.add_device("udmabuf2", &[("size", "4096\n")]);
+        fx.add_device("udmabuf3", &attrs("garbage\n", "4096\n"));
+
+        let devices = enumerate_udmabuf_devices_in(&fx.class_base(), "u-dma-buf").unwrap();
+        assert_eq!(
+            devices,
+            [
+                UdmabufDeviceInfo {
+                    name: "udmabuf0".into(),
+                    module_name: "u-dma-buf".into(),
+                    phys_addr: 0x3f00_0000,
+                    size: 4096,
+                    sync_mode: 1,
+                    dma_coherent: 0,
+                },
+                UdmabufDeviceInfo {
+                    name: "udmabuf1".into(),
+                    module_name: "u-dma-buf".into(),
+                    phys_addr: 0x4000_0000,
+                    size: 8192,
+                    sync_mode: 1,
+                    dma_coherent: 0,
+                },
+            ]
+        );
+
+        assert!(enumerate_udmabuf_devices_in(&fx.class_base(), "no-such-module").is_err());
+    }
+
+    #[test]
+    fn find_by_label() {
+        let fx = Fixture::new("label");
+        let dev0 = fx.add_device("udmabuf0", &attrs("0x3f000000\n", "4096\n"));
+        let dev1 = fx.add_device("udmabuf1", &attrs("0x40000000\n", "8192\n"));
+        fx.add_device("udmabuf2", &[]);
+        fx.add_node(&dev0, "udmabuf@0", "video_buf");
+        fx.add_node(&dev1, "udmabuf@1", "audio_buf");
+
+        let find = |label| {
+            find_udmabuf_device_by_label_in(&fx.class_base(), &fx.dt_base(), label, "u-dma-buf")
+                .unwrap()
+                .map(|dev| dev.name)
+        };
+        assert_eq!(find("audio_buf").as_deref(), Some("udmabuf1"));
+        assert_eq!(find("video_buf").as_deref(), Some("udmabuf0"));
+        assert_eq!(find("no_such_label"), None);
+    }
+}
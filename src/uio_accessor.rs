@@ -90,10 +90,14 @@ impl UioRegion {
 
 impl MemRegion for UioRegion {
     fn subclone(&self, offset: usize, size: usize) -> Self {
-        UioRegion {
-            mmap_region: self.mmap_region.subclone(offset, size),
+        expect_subclone(self.try_subclone(offset, size), offset, size)
+    }
+
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        Ok(UioRegion {
+            mmap_region: self.mmap_region.try_subclone(offset, size)?,
             phys_addr: self.phys_addr + offset,
-        }
+        })
     }
 
     fn phys_addr(&self) -> usize {
@@ -154,6 +158,18 @@ impl<U> UioAccessor<U> {
         }
     }
 
+    pub fn try_subclone_<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<UioAccessor<NewU>, MemAccessTryError> {
+        Ok(UioAccessor::<NewU> {
+            mem_accessor: MemAccessor::<UioRegion, NewU>::new(
+                self.mem_accessor.region().try_subclone(offset, size)?,
+            ),
+        })
+    }
+
     pub fn subclone8(&self, offset: usize, size: usize) -> UioAccessor<u8> {
         self.subclone_::<u8>(offset, size)
     }
@@ -170,6 +186,38 @@ impl<U> UioAccessor<U> {
         self.subclone_::<u64>(offset, size)
     }
 
+    pub fn try_subclone8(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<UioAccessor<u8>, MemAccessTryError> {
+        self.try_subclone_::<u8>(offset, size)
+    }
+
+    pub fn try_subclone16(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<UioAccessor<u16>, MemAccessTryError> {
+        self.try_subclone_::<u16>(offset, size)
+    }
+
+    pub fn try_subclone32(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<UioAccessor<u32>, MemAccessTryError> {
+        self.try_subclone_::<u32>(offset, size)
+    }
+
+    pub fn try_subclone64(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<UioAccessor<u64>, MemAccessTryError> {
+        self.try_subclone_::<u64>(offset, size)
+    }
+
     delegate! {
         to self.mem_accessor.region() {
             pub fn addr(&self) -> usize;
@@ -184,6 +232,22 @@ impl<U> UioAccessor<U> {
     }
 }
 
+impl<U> SubcloneAs for UioAccessor<U> {
+    type As<NewU> = UioAccessor<NewU>;
+
+    fn subclone_as<NewU>(&self, offset: usize, size: usize) -> Self::As<NewU> {
+        self.subclone_::<NewU>(offset, size)
+    }
+
+    fn try_subclone_as<NewU>(
+        &self,
+        offset: usize,
+        size: usize,
+    ) -> Result<Self::As<NewU>, MemAccessTryError> {
+        self.try_subclone_::<NewU>(offset, size)
+    }
+}
+
 impl<U> MemAccessBase for UioAccessor<U> {
     fn reg_size() -> usize {
         core::mem::size_of::<U>()
@@ -193,6 +257,10 @@ impl<U> MemAccessBase for UioAccessor<U> {
         self.subclone_::<U>(offset, size)
     }
 
+    fn try_subclone(&self, offset: usize, size: usize) -> Result<Self, MemAccessTryError> {
+        self.try_subclone_::<U>(offset, size)
+    }
+
     delegate! {
         to self.mem_accessor {
             unsafe fn copy_to_<V>(&self, src_adr: usize, dst_ptr: *mut V, count: usize);